@@ -6,7 +6,7 @@
 #[macro_use]
 extern crate serde_derive;
 
-use pickledb::{PickleDb, PickleDbDumpPolicy};
+use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
 use std::fmt::{self, Formatter, Display};
 
 /// Define an example struct which represents a rectangle. 
@@ -26,22 +26,22 @@ impl Display for Rectangle {
 fn main() {
 
     // create a new DB with AutoDum, meaning every change is written to the file
-    let mut db = PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
     
     // set the value 100 to the key 'key1'
-    db.set("key1", &100);
+    db.set("key1", &100).unwrap();
 
     // set the value 1.1 to the key 'key2'
-    db.set("key2", &1.1);
+    db.set("key2", &1.1).unwrap();
 
     // set the value 'hello world' to the key 'key3'
-    db.set("key3", &String::from("hello world"));
+    db.set("key3", &String::from("hello world")).unwrap();
 
     // set a vector value to the key 'key4'
-    db.set("key4", &vec![1,2,3]);
+    db.set("key4", &vec![1,2,3]).unwrap();
 
     // set a Rectangle value to the key 'key5'
-    db.set("key5", &Rectangle { width: 4, length: 10});
+    db.set("key5", &Rectangle { width: 4, length: 10}).unwrap();
 
     // print the value of key1
     println!("The value of key1 is: {}", db.get::<i32>("key1").unwrap());
@@ -60,21 +60,21 @@ fn main() {
 
 
     // override the value of key1. Please note the new value is of a different type the former one
-    db.set("key1", &String::from("override"));
+    db.set("key1", &String::from("override")).unwrap();
 
     // print the value of key1
     println!("The value of key1 is: {}", db.get::<String>("key1").unwrap());
 
     
     // remove key2
-    db.rem("key2");
+    db.rem("key2").unwrap();
 
     // was key2 removed?
     println!("key2 was removed. Is it still in the db? {}", db.get::<f32>("key2").is_some());
 
 
     // load an existing DB from a file (the same file in this case)
-    let db2 = PickleDb::load("example.db", PickleDbDumpPolicy::DumpUponRequest).unwrap();
+    let db2 = PickleDb::load("example.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json).unwrap();
 
     // print the value of key1
     println!("Value of key1 as loaded from file is: {}", db2.get::<String>("key1").unwrap());
@@ -8,7 +8,7 @@
 #[macro_use]
 extern crate serde_derive;
 
-use pickledb::{PickleDb, PickleDbDumpPolicy};
+use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
 use std::fmt::{self, Formatter, Display};
 
 /// Define an example struct which represents a rectangle. 
@@ -27,9 +27,9 @@ impl Display for Rectangle {
 
 /// Create a new DB and add one key-value pair to it
 fn create_db(db_name: &str) {
-    let mut new_db = PickleDb::new(db_name, PickleDbDumpPolicy::AutoDump);
+    let mut new_db = PickleDb::new(db_name, PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
-    new_db.set("key1", &100);
+    new_db.set("key1", &100).unwrap();
 }
 
 fn main() {
@@ -38,32 +38,32 @@ fn main() {
     create_db("example.db");
 
     // load the DB
-    let mut db = PickleDb::load("example.db", PickleDbDumpPolicy::AutoDump).unwrap();
+    let mut db = PickleDb::load("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).unwrap();
 
     // print the existing value in key1
     println!("The value of key1 is: {}", db.get::<i32>("key1").unwrap());
 
     // create a new list
-    db.lcreate("list1")
+    db.lcreate("list1").unwrap()
 
     // add an integer item to the list
-      .ladd(&200)
+      .ladd(&200).unwrap()
 
     // add an floating point item to the list
-      .ladd(&2.1)
+      .ladd(&2.1).unwrap()
 
     // add a string to the list
-      .ladd(&String::from("my list"))
+      .ladd(&String::from("my list")).unwrap()
 
     // add a vector of chars to the list
-      .ladd(&vec!['a', 'b', 'c'])
+      .ladd(&vec!['a', 'b', 'c']).unwrap()
 
     // add multiple values to the list: add 3 rectangles 
-      .lextend(&vec![
+      .lextend(&[
         Rectangle { width: 2, length: 4}, 
         Rectangle { width: 10, length: 22},
         Rectangle { width: 1, length: 22}, 
-        ]);
+        ]).unwrap();
 
     // print the list length
     println!("list1 length is: {}", db.llen("list1"));
@@ -78,24 +78,24 @@ fn main() {
     println!("list1[7] = {}", db.lget::<Rectangle>("list1", 6).unwrap());
 
     // remove an item in the list
-    db.lpop::<i32>("list1", 0);
+    db.lpop::<i32>("list1", 0).unwrap();
 
     // print the new first item of the list
     println!("The new list1[0] = {}", db.lget::<f32>("list1", 0).unwrap());
 
     // remove the entire list
-    db.lrem_list("list1");
+    db.lrem_list("list1").unwrap();
 
     // was list1 removed?
     println!("list1 was removed. Is it still in the db? {}", db.lexists("list1"));
 
 
     // create a new list
-    db.lcreate("list2")
-      .lextend(&vec![1,2,3,4]);
+    db.lcreate("list2").unwrap()
+      .lextend(&[1,2,3,4]).unwrap();
 
     // iterate over the items in list2
-    for item_iter in db.liter("list2") {
+    for item_iter in db.liter("list2").unwrap() {
         println!("Current item is: {}", item_iter.get_item::<i32>().unwrap());
     }
 }
@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::db_file;
+use crate::encryption::Cipher;
+use crate::error::PickleDbError;
+use crate::serialization::{SerializationMethod, SerializedValue};
+use crate::sorted_set::SortedSet;
+
+/// A record in the append-only log. Each record holds the whole state of a single key after it was changed,
+/// so replaying a record more than once or replaying records of keys that didn't change is harmless
+#[derive(Serialize, Deserialize)]
+pub(crate) enum LogRecord {
+    /// The key holds a value, with its expiry time if it has one
+    Value(String, SerializedValue, Option<u64>),
+    /// The key holds a list, with its expiry time if it has one
+    List(String, Vec<SerializedValue>, Option<u64>),
+    /// The key was removed
+    Removed(String),
+    /// The key holds a dict, with its expiry time if it has one
+    Dict(String, HashMap<String, SerializedValue>, Option<u64>),
+    /// The key holds a set, with its expiry time if it has one
+    Set(String, HashSet<SerializedValue>, Option<u64>),
+    /// The key holds a sorted set, with its expiry time if it has one
+    SortedSet(String, SortedSet, Option<u64>),
+}
+
+/// The path of the append-only log of a DB file
+pub(crate) fn log_file_path(db_file_path: &str) -> String {
+    format!("{}.log", db_file_path)
+}
+
+/// Append records to the log file, creating it if it doesn't exist. Every record is written with a 4 bytes
+/// little-endian length prefix so records of all serialization methods can be read back one by one, and starts with
+/// a flag that is set on the last of the appended records, so they're replayed either all together or not at all.
+/// Records of an encrypted DB are encrypted one by one, each of them chained to the one before it, where `chain` is
+/// the chain of the last record in the log and is set to the chain of the last appended record once they're written.
+/// If `durable` is set the records are synced to the disk
+pub(crate) fn append(
+    path: &str,
+    records: &[LogRecord],
+    ser_method: SerializationMethod,
+    cipher: Option<&Cipher>,
+    chain: &mut Vec<u8>,
+    durable: bool,
+) -> Result<(), PickleDbError> {
+    let mut buf = Vec::new();
+    let mut new_chain = chain.clone();
+    for (i, record) in records.iter().enumerate() {
+        let last = i == records.len() - 1;
+        let ser_record = ser_method.serialize_data(record).map_err(PickleDbError::Serialization)?;
+        let ser_record = match cipher {
+            Some(cipher) => cipher.encrypt_record(&ser_record, &mut new_chain, last)?,
+            None => [&[last as u8], &ser_record[..]].concat(),
+        };
+        let len = u32::try_from(ser_record.len())
+            .map_err(|_| PickleDbError::Serialization(String::from("log record is too large")))?;
+        buf.extend_from_slice(&len.to_le_bytes());
+        buf.extend_from_slice(&ser_record);
+    }
+
+    // write all records at once so a failure doesn't leave only some of them in the log
+    let created = !Path::new(path).exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&buf)?;
+    if durable {
+        file.sync_data()?;
+        if created {
+            db_file::sync_parent_dir(path)?;
+        }
+    }
+    *chain = new_chain;
+    Ok(())
+}
+
+/// Read all records from the log file, along with the length of the log up to the end of the last complete record.
+/// If the log file doesn't exist no records are returned. The records that were appended together are read only if
+/// all of them are in the log, so if the process crashed while appending them, none of them is read. A crash may cut
+/// the log anywhere, also right after one of them, so such a log isn't taken as tampered with.
+/// Records of an encrypted DB are read if they're chained to `chain`, which is then set to the chain of the last
+/// record that is read
+pub(crate) fn read(
+    path: &str,
+    ser_method: SerializationMethod,
+    cipher: Option<&Cipher>,
+    chain: &mut Vec<u8>,
+) -> Result<(Vec<LogRecord>, u64), PickleDbError> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(err) => return Err(PickleDbError::Io(err)),
+    };
+
+    let mut records = Vec::new();
+    // the records that were appended together with the last one that was read
+    let mut batch = Vec::new();
+    let mut batch_chain = chain.clone();
+    let mut valid_len = 0;
+    let mut rest = &contents[..];
+    while rest.len() >= 4 {
+        let len = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if rest.len() - 4 < len {
+            break;
+        }
+        let ser_record = &rest[4..4 + len];
+        rest = &rest[4 + len..];
+        let (ser_record, last) = match cipher {
+            Some(cipher) => cipher.decrypt_record(ser_record, &mut batch_chain)?,
+            None => match ser_record.split_first() {
+                Some((&last, ser_record)) if last <= 1 => (ser_record.to_vec(), last == 1),
+                _ => return Err(PickleDbError::Corrupted(String::from("invalid record in the log"))),
+            },
+        };
+        batch.push(ser_method.deserialize_data(&ser_record).map_err(PickleDbError::Corrupted)?);
+        if !last {
+            continue;
+        }
+        *chain = batch_chain.clone();
+        records.append(&mut batch);
+        valid_len = contents.len() - rest.len();
+    }
+    Ok((records, valid_len as u64))
+}
+
+/// Cut off the partially written records at the end of the log file, so records that are appended later on
+/// don't follow them. Does nothing if the log file doesn't exist or ends with the last of the records appended together
+pub(crate) fn truncate_torn_tail(path: &str, valid_len: u64) -> Result<(), PickleDbError> {
+    let file = match OpenOptions::new().write(true).open(path) {
+        Ok(file) => file,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(PickleDbError::Io(err)),
+    };
+    if file.metadata()?.len() > valid_len {
+        file.set_len(valid_len)?;
+    }
+    Ok(())
+}
+
+/// Truncate the log file after the DB was dumped to the DB file, or remove it if it isn't needed anymore
+pub(crate) fn reset(path: &str, keep: bool) -> Result<(), PickleDbError> {
+    if keep {
+        fs::write(path, [])?;
+        return Ok(());
+    }
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(PickleDbError::Io(err)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,797 @@
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+use std::panic;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::{RwLock, RwLockReadGuard};
+use tokio::task;
+
+#[cfg(feature = "encryption")]
+use crate::encryption::EncryptionKey;
+use crate::error::PickleDbError;
+use crate::serialization::{SerializationMethod, SerializedValue};
+use crate::{expiry_time, ListEnd, PickleDb, PickleDbDumpPolicy, PickleDbEvent, PickleDbLockPolicy, PickleDbSnapshot, PickleDbSubscription};
+
+/// An asynchronous version of PickleDB for [tokio](https://tokio.rs/) users. Available with the `async` feature.
+///
+/// `AsyncPickleDb` provides the same APIs as `PickleDb`, but the APIs that may read or write the DB file are `async`
+/// and run the file I/O on tokio's blocking thread pool, so they never block the runtime threads. That includes
+/// [load()](#method.load), [dump()](#method.dump) and all APIs that change the DB, since they may dump it
+/// according to the dump policy. The dump policies work exactly the same as they do with `PickleDb`.
+///
+/// `AsyncPickleDb` is cheap to clone and all clones refer to the same DB, so it can be shared between tasks.
+/// Reading APIs of different tasks can run concurrently while APIs that change the DB are serialized.
+/// Any other API of `PickleDb`, such as iterating over the DB, can be used in [read()](#method.read) or
+/// [write()](#method.write).
+///
+/// Please note that when the last clone of the handle is dropped the DB is dropped as well and it's dumped
+/// according to the dump policy in the thread that dropped it. In order to avoid blocking a runtime thread
+/// please call [dump()](#method.dump) before dropping it.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use pickledb::{AsyncPickleDb, PickleDbDumpPolicy, SerializationMethod};
+///
+/// let db = AsyncPickleDb::load("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).await?;
+/// db.set("key1", &100).await?;
+/// println!("The value of key1 is: {}", db.get::<i32>("key1").await.unwrap());
+/// ```
+///
+#[derive(Clone)]
+pub struct AsyncPickleDb {
+    db: Arc<RwLock<PickleDb>>,
+    serialization_method: SerializationMethod,
+}
+
+impl AsyncPickleDb {
+    /// Constructs a new `AsyncPickleDb` instance. Please see [PickleDb::new()](struct.PickleDb.html#method.new).
+    ///
+    pub fn new(location: &str, dump_policy: PickleDbDumpPolicy, serialization_method: SerializationMethod) -> AsyncPickleDb {
+        AsyncPickleDb::from(PickleDb::new(location, dump_policy, serialization_method))
+    }
+
+    /// Load a DB from a file. Please see [PickleDb::load()](struct.PickleDb.html#method.load).
+    ///
+    pub async fn load(location: &str, dump_policy: PickleDbDumpPolicy, serialization_method: SerializationMethod) -> Result<AsyncPickleDb, PickleDbError> {
+        let location = String::from(location);
+        let db = spawn_blocking(move || PickleDb::load(&location, dump_policy, serialization_method)).await?;
+        Ok(AsyncPickleDb::from(db))
+    }
+
+    /// Load an encrypted DB from a file. Please see [PickleDb::load_encrypted()](struct.PickleDb.html#method.load_encrypted).
+    ///
+    #[cfg(feature = "encryption")]
+    pub async fn load_encrypted(
+        location: &str,
+        dump_policy: PickleDbDumpPolicy,
+        serialization_method: SerializationMethod,
+        key: &EncryptionKey,
+    ) -> Result<AsyncPickleDb, PickleDbError> {
+        let location = String::from(location);
+        let key = key.clone();
+        let db = spawn_blocking(move || PickleDb::load_encrypted(&location, dump_policy, serialization_method, &key)).await?;
+        Ok(AsyncPickleDb::from(db))
+    }
+
+    /// Load a DB from a file and lock it. Please see [PickleDb::load_locked()](struct.PickleDb.html#method.load_locked).
+    ///
+    pub async fn load_locked(
+        location: &str,
+        dump_policy: PickleDbDumpPolicy,
+        serialization_method: SerializationMethod,
+        lock_policy: PickleDbLockPolicy,
+    ) -> Result<AsyncPickleDb, PickleDbError> {
+        let location = String::from(location);
+        let db = spawn_blocking(move || PickleDb::load_locked(&location, dump_policy, serialization_method, lock_policy)).await?;
+        Ok(AsyncPickleDb::from(db))
+    }
+
+    /// Load a DB from a file in read-only mode. Please see [PickleDb::load_read_only()](struct.PickleDb.html#method.load_read_only).
+    ///
+    pub async fn load_read_only(location: &str, serialization_method: SerializationMethod) -> Result<AsyncPickleDb, PickleDbError> {
+        AsyncPickleDb::load(location, PickleDbDumpPolicy::NeverDump, serialization_method).await
+    }
+
+    /// Load a DB from a file in read-only mode and take a shared lock of it.
+    /// Please see [PickleDb::load_read_only_locked()](struct.PickleDb.html#method.load_read_only_locked).
+    ///
+    pub async fn load_read_only_locked(
+        location: &str,
+        serialization_method: SerializationMethod,
+        lock_policy: PickleDbLockPolicy,
+    ) -> Result<AsyncPickleDb, PickleDbError> {
+        AsyncPickleDb::load_locked(location, PickleDbDumpPolicy::NeverDump, serialization_method, lock_policy).await
+    }
+
+    /// Run a closure that reads from the DB.
+    ///
+    /// The closure runs on the current thread, so it shouldn't block. Closures of different tasks that read
+    /// from the DB can run concurrently. This method returns the value the closure returned.
+    ///
+    pub async fn read<F, R>(&self, f: F) -> R
+        where
+            F: FnOnce(&PickleDb) -> R
+    {
+        f(&*self.read_lock().await)
+    }
+
+    /// Run a closure that changes the DB.
+    ///
+    /// The closure runs on tokio's blocking thread pool since changes may be dumped to the file.
+    /// Only one closure that changes the DB runs at a time, and no other task reads from the DB while it runs.
+    /// This method returns the value the closure returned.
+    ///
+    pub async fn write<F, R>(&self, f: F) -> R
+        where
+            F: FnOnce(&mut PickleDb) -> R + Send + 'static,
+            R: Send + 'static
+    {
+        let db = Arc::clone(&self.db);
+        spawn_blocking(move || f(&mut db.blocking_write())).await
+    }
+
+    /// Run a closure in a transaction. Please see [PickleDb::transaction()](struct.PickleDb.html#method.transaction).
+    ///
+    /// The closure runs on tokio's blocking thread pool, like in [write()](#method.write).
+    ///
+    pub async fn transaction<F, R, E>(&self, f: F) -> Result<R, E>
+        where
+            F: FnOnce(&mut PickleDb) -> Result<R, E> + Send + 'static,
+            R: Send + 'static,
+            E: From<PickleDbError> + Send + 'static
+    {
+        self.write(move |db| db.transaction(f)).await
+    }
+
+    /// Dump the data to the file. Please see [PickleDb::dump()](struct.PickleDb.html#method.dump).
+    ///
+    pub async fn dump(&self) -> Result<(), PickleDbError> {
+        self.write(|db| db.dump()).await
+    }
+
+    /// Reload the DB from its file. Please see [PickleDb::reload()](struct.PickleDb.html#method.reload).
+    ///
+    pub async fn reload(&self) -> Result<(), PickleDbError> {
+        self.write(|db| db.reload()).await
+    }
+
+    /// Reload the DB from its file if the file changed. Please see
+    /// [PickleDb::reload_if_changed()](struct.PickleDb.html#method.reload_if_changed).
+    ///
+    pub async fn reload_if_changed(&self) -> Result<bool, PickleDbError> {
+        self.write(|db| db.reload_if_changed()).await
+    }
+
+    /// Write a copy of the DB to another file. Please see [PickleDb::save_as()](struct.PickleDb.html#method.save_as).
+    ///
+    pub async fn save_as(&self, path: &str) -> Result<(), PickleDbError> {
+        let path = String::from(path);
+        self.write(move |db| db.save_as(&path)).await
+    }
+
+    /// Get a point-in-time snapshot of the DB. Please see [PickleDb::snapshot()](struct.PickleDb.html#method.snapshot).
+    ///
+    pub async fn snapshot(&self) -> PickleDbSnapshot {
+        self.read(|db| db.snapshot()).await
+    }
+
+    /// Restore a backup of the DB file. Please see [PickleDb::restore_backup()](struct.PickleDb.html#method.restore_backup).
+    ///
+    pub async fn restore_backup(&self, number: usize) -> Result<(), PickleDbError> {
+        self.write(move |db| db.restore_backup(number)).await
+    }
+
+    /// Subscribe to the changes of keys in the DB. Please see [PickleDb::subscribe()](struct.PickleDb.html#method.subscribe).
+    ///
+    /// The callback runs while the DB is locked, usually on tokio's blocking thread pool, so it must not use the DB
+    /// itself. In order to handle the events in a task the callback can send them to a tokio channel.
+    ///
+    pub async fn subscribe<F>(&self, prefix: &str, callback: F) -> PickleDbSubscription
+        where
+            F: FnMut(&PickleDbEvent) + Send + Sync + 'static
+    {
+        self.db.write().await.subscribe(prefix, callback)
+    }
+
+    /// Subscribe to the changes of keys in the DB through a channel. Please see
+    /// [PickleDb::subscribe_channel()](struct.PickleDb.html#method.subscribe_channel).
+    ///
+    /// The channel is a standard one, so a task should receive from it with `try_recv()` rather than block on `recv()`.
+    ///
+    pub async fn subscribe_channel(&self, prefix: &str) -> Receiver<PickleDbEvent> {
+        self.db.write().await.subscribe_channel(prefix)
+    }
+
+    /// Cancel a subscription to the changes of keys in the DB. Please see
+    /// [PickleDb::unsubscribe()](struct.PickleDb.html#method.unsubscribe).
+    ///
+    pub async fn unsubscribe(&self, subscription: PickleDbSubscription) -> bool {
+        self.db.write().await.unsubscribe(subscription)
+    }
+
+    /// Set a key-value pair. Please see [PickleDb::set()](struct.PickleDb.html#method.set).
+    ///
+    pub async fn set<V>(&self, key: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        let (key, value) = (String::from(key), self.serialize(value)?);
+        self.write(move |db| db.set_serialized(&key, value, None)).await
+    }
+
+    /// Set a key-value pair that expires after a certain time. Please see
+    /// [PickleDb::set_with_ttl()](struct.PickleDb.html#method.set_with_ttl).
+    ///
+    pub async fn set_with_ttl<V>(&self, key: &str, value: &V, ttl: Duration) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        let (key, value) = (String::from(key), self.serialize(value)?);
+        self.write(move |db| db.set_serialized(&key, value, Some(expiry_time(ttl)))).await
+    }
+
+    /// Get a value of a key. Please see [PickleDb::get()](struct.PickleDb.html#method.get).
+    ///
+    pub async fn get<V>(&self, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.get(key)
+    }
+
+    /// Get a value of a key, telling a missing key apart from a wrong type. Please see
+    /// [PickleDb::try_get()](struct.PickleDb.html#method.try_get).
+    ///
+    pub async fn try_get<V>(&self, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.try_get(key)
+    }
+
+    /// Check if a key exists. Please see [PickleDb::exists()](struct.PickleDb.html#method.exists).
+    ///
+    pub async fn exists(&self, key: &str) -> bool {
+        self.read_lock().await.exists(key)
+    }
+
+    /// Get a vector of all the keys in the DB. Please see [PickleDb::get_all()](struct.PickleDb.html#method.get_all).
+    ///
+    pub async fn get_all(&self) -> Vec<String> {
+        self.read_lock().await.get_all()
+    }
+
+    /// Get the keys in a range, sorted. Please see [PickleDb::range()](struct.PickleDb.html#method.range).
+    ///
+    pub async fn range<'r, R>(&self, range: R) -> Vec<String>
+        where
+            R: RangeBounds<&'r str>
+    {
+        self.read_lock().await.range(range)
+    }
+
+    /// Get the keys that start with a prefix, sorted. Please see [PickleDb::scan_prefix()](struct.PickleDb.html#method.scan_prefix).
+    ///
+    pub async fn scan_prefix(&self, prefix: &str) -> Vec<String> {
+        self.read_lock().await.scan_prefix(prefix)
+    }
+
+    /// Get the total number of keys in the DB. Please see [PickleDb::total_keys()](struct.PickleDb.html#method.total_keys).
+    ///
+    pub async fn total_keys(&self) -> usize {
+        self.read_lock().await.total_keys()
+    }
+
+    /// Remove a key-value pair, a list, a dict, a set or a sorted set from the DB. Please see [PickleDb::rem()](struct.PickleDb.html#method.rem).
+    ///
+    pub async fn rem(&self, key: &str) -> Result<bool, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.rem(&key)).await
+    }
+
+    /// Set a time to live for an existing key or list. Please see [PickleDb::expire()](struct.PickleDb.html#method.expire).
+    ///
+    pub async fn expire(&self, key: &str, ttl: Duration) -> Result<bool, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.expire(&key, ttl)).await
+    }
+
+    /// Get the remaining time to live of a key or list. Please see [PickleDb::ttl()](struct.PickleDb.html#method.ttl).
+    ///
+    pub async fn ttl(&self, key: &str) -> Result<Option<Duration>, PickleDbError> {
+        self.read_lock().await.ttl(key)
+    }
+
+    /// Remove the time to live of a key or list. Please see [PickleDb::persist()](struct.PickleDb.html#method.persist).
+    ///
+    pub async fn persist(&self, key: &str) -> Result<bool, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.persist(&key)).await
+    }
+
+    /// Increment the integer value of a key by one. Please see [PickleDb::incr()](struct.PickleDb.html#method.incr).
+    ///
+    pub async fn incr(&self, key: &str) -> Result<i64, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.incr(&key)).await
+    }
+
+    /// Decrement the integer value of a key by one. Please see [PickleDb::decr()](struct.PickleDb.html#method.decr).
+    ///
+    pub async fn decr(&self, key: &str) -> Result<i64, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.decr(&key)).await
+    }
+
+    /// Increment the integer value of a key. Please see [PickleDb::incr_by()](struct.PickleDb.html#method.incr_by).
+    ///
+    pub async fn incr_by(&self, key: &str, by: i64) -> Result<i64, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.incr_by(&key, by)).await
+    }
+
+    /// Increment the floating point value of a key. Please see [PickleDb::incr_by_float()](struct.PickleDb.html#method.incr_by_float).
+    ///
+    pub async fn incr_by_float(&self, key: &str, by: f64) -> Result<f64, PickleDbError> {
+        let key = String::from(key);
+        self.write(move |db| db.incr_by_float(&key, by)).await
+    }
+
+    /// Create a new list. Please see [PickleDb::lcreate()](struct.PickleDb.html#method.lcreate).
+    ///
+    /// Unlike `PickleDb::lcreate()` this method doesn't return an object for adding items to the list,
+    /// please use [lextend()](#method.lextend) for adding them.
+    ///
+    pub async fn lcreate(&self, name: &str) -> Result<(), PickleDbError> {
+        let name = String::from(name);
+        self.write(move |db| db.lcreate(&name).map(|_| ())).await
+    }
+
+    /// Check if a list exists. Please see [PickleDb::lexists()](struct.PickleDb.html#method.lexists).
+    ///
+    pub async fn lexists(&self, name: &str) -> bool {
+        self.read_lock().await.lexists(name)
+    }
+
+    /// Add a single item to an existing list. Please see [PickleDb::ladd()](struct.PickleDb.html#method.ladd).
+    ///
+    pub async fn ladd<V>(&self, name: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.lextend(name, &[value]).await
+    }
+
+    /// Add multiple items to an existing list. Please see [PickleDb::lextend()](struct.PickleDb.html#method.lextend).
+    ///
+    pub async fn lextend<V>(&self, name: &str, seq: &[V]) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        let name = String::from(name);
+        let serialized = seq.iter()
+            .map(|x| self.serialize(x))
+            .collect::<Result<Vec<SerializedValue>, PickleDbError>>()?;
+        self.write(move |db| db.lextend_serialized(&name, serialized)).await
+    }
+
+    /// Get an item of of a certain list in a certain position. Please see [PickleDb::lget()](struct.PickleDb.html#method.lget).
+    ///
+    pub async fn lget<V>(&self, name: &str, pos: usize) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.lget(name, pos)
+    }
+
+    /// Get an item of of a certain list in a certain position, telling a missing item apart from a wrong type.
+    /// Please see [PickleDb::try_lget()](struct.PickleDb.html#method.try_lget).
+    ///
+    pub async fn try_lget<V>(&self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.try_lget(name, pos)
+    }
+
+    /// Get the length of a list. Please see [PickleDb::llen()](struct.PickleDb.html#method.llen).
+    ///
+    pub async fn llen(&self, name: &str) -> usize {
+        self.read_lock().await.llen(name)
+    }
+
+    /// Remove a list. Please see [PickleDb::lrem_list()](struct.PickleDb.html#method.lrem_list).
+    ///
+    pub async fn lrem_list(&self, name: &str) -> Result<usize, PickleDbError> {
+        let name = String::from(name);
+        self.write(move |db| db.lrem_list(&name)).await
+    }
+
+    /// Pop an item out of a list. Please see [PickleDb::lpop()](struct.PickleDb.html#method.lpop).
+    ///
+    pub async fn lpop<V>(&self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned + Send + 'static
+    {
+        let name = String::from(name);
+        self.write(move |db| db.lpop(&name, pos)).await
+    }
+
+    /// Remove an item out of a list. Please see [PickleDb::lrem_value()](struct.PickleDb.html#method.lrem_value).
+    ///
+    pub async fn lrem_value<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.lrem_serialized(&name, &value)).await
+    }
+
+    /// Add an item to the front of an existing list. Please see [PickleDb::lpush()](struct.PickleDb.html#method.lpush).
+    ///
+    pub async fn lpush<V>(&self, name: &str, value: &V) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.linsert(name, 0, value).await
+    }
+
+    /// Insert an item into an existing list at a certain position. Please see [PickleDb::linsert()](struct.PickleDb.html#method.linsert).
+    ///
+    pub async fn linsert<V>(&self, name: &str, index: i64, value: &V) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.linsert_serialized(&name, index, value)).await
+    }
+
+    /// Insert an item into an existing list before another item. Please see
+    /// [PickleDb::linsert_before()](struct.PickleDb.html#method.linsert_before).
+    ///
+    pub async fn linsert_before<P, V>(&self, name: &str, pivot: &P, value: &V) -> Result<bool, PickleDbError>
+        where
+            P: Serialize,
+            V: Serialize
+    {
+        let (name, pivot, value) = (String::from(name), self.serialize(pivot)?, self.serialize(value)?);
+        self.write(move |db| db.linsert_pivot_serialized(&name, &pivot, value, false)).await
+    }
+
+    /// Insert an item into an existing list after another item. Please see
+    /// [PickleDb::linsert_after()](struct.PickleDb.html#method.linsert_after).
+    ///
+    pub async fn linsert_after<P, V>(&self, name: &str, pivot: &P, value: &V) -> Result<bool, PickleDbError>
+        where
+            P: Serialize,
+            V: Serialize
+    {
+        let (name, pivot, value) = (String::from(name), self.serialize(pivot)?, self.serialize(value)?);
+        self.write(move |db| db.linsert_pivot_serialized(&name, &pivot, value, true)).await
+    }
+
+    /// Replace an item of an existing list. Please see [PickleDb::lset()](struct.PickleDb.html#method.lset).
+    ///
+    pub async fn lset<V>(&self, name: &str, index: i64, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.lset_serialized(&name, index, value)).await
+    }
+
+    /// Get an item of a list by its index. Please see [PickleDb::lindex()](struct.PickleDb.html#method.lindex).
+    ///
+    pub async fn lindex<V>(&self, name: &str, index: i64) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.lindex(name, index)
+    }
+
+    /// Get a range of items of a list. Please see [PickleDb::lrange()](struct.PickleDb.html#method.lrange).
+    ///
+    pub async fn lrange<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.lrange(name, start, end)
+    }
+
+    /// Trim an existing list to a range of items. Please see [PickleDb::ltrim()](struct.PickleDb.html#method.ltrim).
+    ///
+    pub async fn ltrim(&self, name: &str, start: i64, end: i64) -> Result<usize, PickleDbError> {
+        let name = String::from(name);
+        self.write(move |db| db.ltrim(&name, start, end)).await
+    }
+
+    /// Remove several items that equal to a value out of a list. Please see
+    /// [PickleDb::lrem_value_all()](struct.PickleDb.html#method.lrem_value_all).
+    ///
+    pub async fn lrem_value_all<V>(&self, name: &str, value: &V, count: i64) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.lrem_all_serialized(&name, &value, count)).await
+    }
+
+    /// Move an item from one list to another. Please see [PickleDb::lmove()](struct.PickleDb.html#method.lmove).
+    ///
+    pub async fn lmove<V>(&self, source: &str, destination: &str, from: ListEnd, to: ListEnd) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned + Send + 'static
+    {
+        let (source, destination) = (String::from(source), String::from(destination));
+        self.write(move |db| db.lmove(&source, &destination, from, to)).await
+    }
+
+    /// Create a new dict. Please see [PickleDb::dcreate()](struct.PickleDb.html#method.dcreate).
+    ///
+    pub async fn dcreate(&self, name: &str) -> Result<(), PickleDbError> {
+        let name = String::from(name);
+        self.write(move |db| db.dcreate(&name)).await
+    }
+
+    /// Add a key-value pair to an existing dict. Please see [PickleDb::dadd()](struct.PickleDb.html#method.dadd).
+    ///
+    pub async fn dadd<V>(&self, name: &str, key: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, key, value) = (String::from(name), String::from(key), self.serialize(value)?);
+        self.write(move |db| db.dadd_serialized(&name, &key, value)).await
+    }
+
+    /// Get the value of a key inside a dict. Please see [PickleDb::dget()](struct.PickleDb.html#method.dget).
+    ///
+    pub async fn dget<V>(&self, name: &str, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.dget(name, key)
+    }
+
+    /// Get the value of a key inside a dict, telling a missing key apart from a wrong type. Please see
+    /// [PickleDb::try_dget()](struct.PickleDb.html#method.try_dget).
+    ///
+    pub async fn try_dget<V>(&self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.try_dget(name, key)
+    }
+
+    /// Get all the key-value pairs of a dict. Please see [PickleDb::dgetall()](struct.PickleDb.html#method.dgetall).
+    ///
+    pub async fn dgetall<V>(&self, name: &str) -> Option<HashMap<String, V>>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.dgetall(name)
+    }
+
+    /// Get the keys of a dict. Please see [PickleDb::dkeys()](struct.PickleDb.html#method.dkeys).
+    ///
+    pub async fn dkeys(&self, name: &str) -> Option<Vec<String>> {
+        self.read_lock().await.dkeys(name)
+    }
+
+    /// Get the values of a dict. Please see [PickleDb::dvals()](struct.PickleDb.html#method.dvals).
+    ///
+    pub async fn dvals<V>(&self, name: &str) -> Option<Vec<V>>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.dvals(name)
+    }
+
+    /// Check if a key exists inside a dict. Please see [PickleDb::dexists()](struct.PickleDb.html#method.dexists).
+    ///
+    pub async fn dexists(&self, name: &str, key: &str) -> bool {
+        self.read_lock().await.dexists(name, key)
+    }
+
+    /// Get the number of key-value pairs in a dict. Please see [PickleDb::dlen()](struct.PickleDb.html#method.dlen).
+    ///
+    pub async fn dlen(&self, name: &str) -> usize {
+        self.read_lock().await.dlen(name)
+    }
+
+    /// Pop a key-value pair out of a dict. Please see [PickleDb::dpop()](struct.PickleDb.html#method.dpop).
+    ///
+    pub async fn dpop<V>(&self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned + Send + 'static
+    {
+        let (name, key) = (String::from(name), String::from(key));
+        self.write(move |db| db.dpop(&name, &key)).await
+    }
+
+    /// Remove a dict. Please see [PickleDb::drem()](struct.PickleDb.html#method.drem).
+    ///
+    pub async fn drem(&self, name: &str) -> Result<usize, PickleDbError> {
+        let name = String::from(name);
+        self.write(move |db| db.drem(&name)).await
+    }
+
+    /// Merge a dict into another one. Please see [PickleDb::dmerge()](struct.PickleDb.html#method.dmerge).
+    ///
+    pub async fn dmerge(&self, name: &str, other: &str) -> Result<(), PickleDbError> {
+        let (name, other) = (String::from(name), String::from(other));
+        self.write(move |db| db.dmerge(&name, &other)).await
+    }
+
+    /// Add a member to a set. Please see [PickleDb::sadd()](struct.PickleDb.html#method.sadd).
+    ///
+    pub async fn sadd<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.sadd_serialized(&name, value)).await
+    }
+
+    /// Remove a member from a set. Please see [PickleDb::srem()](struct.PickleDb.html#method.srem).
+    ///
+    pub async fn srem<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.srem_serialized(&name, &value)).await
+    }
+
+    /// Check if a member is in a set. Please see [PickleDb::sismember()](struct.PickleDb.html#method.sismember).
+    ///
+    pub async fn sismember<V>(&self, name: &str, value: &V) -> bool
+        where
+            V: Serialize
+    {
+        self.read_lock().await.sismember(name, value)
+    }
+
+    /// Get all the members of a set. Please see [PickleDb::smembers()](struct.PickleDb.html#method.smembers).
+    ///
+    pub async fn smembers<V>(&self, name: &str) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.smembers(name)
+    }
+
+    /// Get the number of members in a set. Please see [PickleDb::scard()](struct.PickleDb.html#method.scard).
+    ///
+    pub async fn scard(&self, name: &str) -> usize {
+        self.read_lock().await.scard(name)
+    }
+
+    /// Get the union of several sets. Please see [PickleDb::sunion()](struct.PickleDb.html#method.sunion).
+    ///
+    pub async fn sunion<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.sunion(names)
+    }
+
+    /// Get the intersection of several sets. Please see [PickleDb::sinter()](struct.PickleDb.html#method.sinter).
+    ///
+    pub async fn sinter<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.sinter(names)
+    }
+
+    /// Get the difference between a set and other sets. Please see [PickleDb::sdiff()](struct.PickleDb.html#method.sdiff).
+    ///
+    pub async fn sdiff<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.sdiff(names)
+    }
+
+    /// Add a member to a sorted set or update its score. Please see [PickleDb::zadd()](struct.PickleDb.html#method.zadd).
+    ///
+    pub async fn zadd<V>(&self, name: &str, score: f64, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.zadd_serialized(&name, score, value)).await
+    }
+
+    /// Remove a member from a sorted set. Please see [PickleDb::zrem()](struct.PickleDb.html#method.zrem).
+    ///
+    pub async fn zrem<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.zrem_serialized(&name, &value)).await
+    }
+
+    /// Get the score of a member of a sorted set. Please see [PickleDb::zscore()](struct.PickleDb.html#method.zscore).
+    ///
+    pub async fn zscore<V>(&self, name: &str, value: &V) -> Option<f64>
+        where
+            V: Serialize
+    {
+        self.read_lock().await.zscore(name, value)
+    }
+
+    /// Get a range of members of a sorted set by their rank. Please see
+    /// [PickleDb::zrange_by_rank()](struct.PickleDb.html#method.zrange_by_rank).
+    ///
+    pub async fn zrange_by_rank<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.zrange_by_rank(name, start, end)
+    }
+
+    /// Get the members of a sorted set whose score is within a range. Please see
+    /// [PickleDb::zrange_by_score()](struct.PickleDb.html#method.zrange_by_score).
+    ///
+    pub async fn zrange_by_score<V>(&self, name: &str, min: f64, max: f64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().await.zrange_by_score(name, min, max)
+    }
+
+    /// Increment the score of a member of a sorted set. Please see [PickleDb::zincrby()](struct.PickleDb.html#method.zincrby).
+    ///
+    pub async fn zincrby<V>(&self, name: &str, by: f64, value: &V) -> Result<f64, PickleDbError>
+        where
+            V: Serialize
+    {
+        let (name, value) = (String::from(name), self.serialize(value)?);
+        self.write(move |db| db.zincrby_serialized(&name, by, value)).await
+    }
+
+    /// Get the number of members in a sorted set. Please see [PickleDb::zcard()](struct.PickleDb.html#method.zcard).
+    ///
+    pub async fn zcard(&self, name: &str) -> usize {
+        self.read_lock().await.zcard(name)
+    }
+
+    async fn read_lock(&self) -> RwLockReadGuard<'_, PickleDb> {
+        self.db.read().await
+    }
+
+    fn serialize<V>(&self, value: &V) -> Result<SerializedValue, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.serialization_method.serialize_data(value)
+            .map(SerializedValue)
+            .map_err(PickleDbError::Serialization)
+    }
+}
+
+impl From<PickleDb> for AsyncPickleDb {
+    fn from(db: PickleDb) -> AsyncPickleDb {
+        AsyncPickleDb {
+            serialization_method: db.serialization_method,
+            db: Arc::new(RwLock::new(db)),
+        }
+    }
+}
+
+/// Run a closure on tokio's blocking thread pool. A panic of the closure is resumed in the calling task
+async fn spawn_blocking<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static
+{
+    match task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(err) => panic::resume_unwind(err.into_panic()),
+    }
+}
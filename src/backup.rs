@@ -0,0 +1,104 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::db_file;
+use crate::PickleDbBackupPolicy;
+
+/// A backup of the DB file. Please see [PickleDb::backups()](struct.PickleDb.html#method.backups)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickleDbBackup {
+    number: usize,
+    path: String,
+    modified: Option<SystemTime>,
+}
+
+impl PickleDbBackup {
+    /// The number of the backup, where 1 is the most recent backup. Please see
+    /// [PickleDb::restore_backup()](struct.PickleDb.html#method.restore_backup)
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// The path of the backup file
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The time the backed up version of the DB file was dumped, if the platform provides it
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+}
+
+/// The path of a backup of a DB file
+pub(crate) fn backup_file_path(db_file_path: &str, number: usize) -> String {
+    format!("{}.bak.{}", db_file_path, number)
+}
+
+/// The path of the file whose modification time is the time of the most recent backup. The backups themselves keep
+/// the time their version of the DB file was dumped, so they can't tell when they were made
+fn backup_time_file_path(db_file_path: &str) -> String {
+    format!("{}.bak.time", db_file_path)
+}
+
+/// The backups of a DB file, from the most recent one to the oldest one
+pub(crate) fn list(db_file_path: &str) -> io::Result<Vec<PickleDbBackup>> {
+    let path = Path::new(db_file_path);
+    let prefix = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => format!("{}.bak.", name),
+        None => return Ok(Vec::new()),
+    };
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(db_file::parent_dir(path))? {
+        let entry = entry?;
+        let number = entry.file_name().to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|number| number.parse::<usize>().ok());
+        if let Some(number) = number.filter(|number| *number > 0) {
+            backups.push(PickleDbBackup {
+                number,
+                path: backup_file_path(db_file_path, number),
+                modified: entry.metadata().and_then(|metadata| metadata.modified()).ok(),
+            });
+        }
+    }
+    backups.sort_by_key(|backup| backup.number);
+    Ok(backups)
+}
+
+/// Keep the current DB file as the most recent backup before it's replaced, if the backup policy says so. Older backups
+/// are renamed to the next number and backups beyond the number of backups to keep are removed. The time of the backup
+/// is recorded, so the periodic policy measures its interval from the time the backup was made
+pub(crate) fn rotate(db_file_path: &str, policy: PickleDbBackupPolicy) -> io::Result<()> {
+    let (interval, keep) = match policy {
+        PickleDbBackupPolicy::EveryDump(keep) => (None, keep),
+        PickleDbBackupPolicy::Periodic(interval, keep) => (Some(interval), keep),
+    };
+    if keep == 0 || !Path::new(db_file_path).exists() {
+        return Ok(());
+    }
+    if let Some(interval) = interval {
+        let last_backup_time = fs::metadata(backup_time_file_path(db_file_path)).and_then(|metadata| metadata.modified());
+        if let Ok(last_backup_time) = last_backup_time {
+            if SystemTime::now().duration_since(last_backup_time).map_or(true, |elapsed| elapsed < interval) {
+                return Ok(());
+            }
+        }
+    }
+
+    for backup in list(db_file_path)?.iter().rev() {
+        if backup.number >= keep {
+            fs::remove_file(&backup.path)?;
+        } else {
+            fs::rename(&backup.path, backup_file_path(db_file_path, backup.number + 1))?;
+        }
+    }
+    // the DB file is linked rather than renamed, so it exists until the new dump replaces it
+    let first_backup = backup_file_path(db_file_path, 1);
+    if fs::hard_link(db_file_path, &first_backup).is_err() {
+        fs::copy(db_file_path, &first_backup)?;
+    }
+    File::create(backup_time_file_path(db_file_path))?.set_modified(SystemTime::now())
+}
@@ -0,0 +1,129 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::backup;
+use crate::PickleDbBackupPolicy;
+
+/// Counts the temp files of this process, so two dumps never use the same temp file
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// How old a temp file must be to be removed when it isn't known whether the process that wrote it is still running.
+/// It's much longer than any dump takes, so a temp file that is older than that is never in the middle of a dump
+const STALE_TEMP_FILE_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// The prefix of the temp files of a DB file
+fn temp_file_prefix(db_file_path: &str) -> String {
+    format!("{}.temp.", db_file_path)
+}
+
+/// The path of a new temp file for dumping a DB file. The path is unique within the process and across processes
+fn temp_file_path(db_file_path: &str) -> String {
+    format!("{}{}.{}", temp_file_prefix(db_file_path), process::id(), TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Write a DB file through a temp file that replaces it, so the DB file always holds either the previous dump or
+/// the new one. If `durable` is set the temp file and the directory are synced to the disk as well, so the new dump
+/// isn't lost or truncated after a power loss. The previous DB file is backed up according to the backup policy, once
+/// the temp file is written and synced to the disk, so a dump that fails or is lost doesn't rotate the backups
+pub(crate) fn write(db_file_path: &str, contents: &[u8], durable: bool, backup_policy: Option<PickleDbBackupPolicy>) -> io::Result<()> {
+    let temp_file_path = temp_file_path(db_file_path);
+    let res = write_temp_file(&temp_file_path, contents, durable || backup_policy.is_some())
+        .and_then(|_| backup_policy.map_or(Ok(()), |policy| backup::rotate(db_file_path, policy)))
+        .and_then(|_| fs::rename(&temp_file_path, db_file_path))
+        .and_then(|_| if durable { sync_parent_dir(db_file_path) } else { Ok(()) });
+    if res.is_err() {
+        let _ = fs::remove_file(&temp_file_path);
+    }
+    res
+}
+
+fn write_temp_file(temp_file_path: &str, contents: &[u8], durable: bool) -> io::Result<()> {
+    let mut file = File::create(temp_file_path)?;
+    file.write_all(contents)?;
+    if durable {
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Sync the directory of a file to the disk, so a file that was created or renamed in it isn't lost after a power loss
+#[cfg(unix)]
+pub(crate) fn sync_parent_dir(path: &str) -> io::Result<()> {
+    File::open(parent_dir(Path::new(path)))?.sync_all()
+}
+
+/// Directories can't be opened on this platform, so renames are as durable as the platform makes them
+#[cfg(not(unix))]
+pub(crate) fn sync_parent_dir(_path: &str) -> io::Result<()> {
+    Ok(())
+}
+
+/// The directory of a file, which is the current directory for a relative path without one
+pub(crate) fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Whether two paths refer to the same file. A file that doesn't exist yet is compared by its canonical directory and
+/// its name, and paths that can't be canonicalized at all are compared as they are
+pub(crate) fn same_file(path1: &str, path2: &str) -> bool {
+    let canonical = |path: &str| {
+        let path = Path::new(path);
+        fs::canonicalize(path).ok().or_else(|| {
+            let name = path.file_name()?;
+            fs::canonicalize(parent_dir(path)).ok().map(|dir| dir.join(name))
+        })
+    };
+    match (canonical(path1), canonical(path2)) {
+        (Some(path1), Some(path2)) => path1 == path2,
+        _ => Path::new(path1) == Path::new(path2),
+    }
+}
+
+/// Remove the temp files of a DB file that were left behind by dumps that didn't complete, for example because
+/// the process crashed. Temp files of other DBs may be in the middle of a dump, so a temp file is only removed if
+/// the process that wrote it isn't running anymore or if it's older than [STALE_TEMP_FILE_AGE]. Files that can't be
+/// removed are left in place
+pub(crate) fn remove_stale_temp_files(db_file_path: &str) {
+    let path = Path::new(db_file_path);
+    let prefix = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => temp_file_prefix(name),
+        None => return,
+    };
+    let entries = match fs::read_dir(parent_dir(path)) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let suffix = match file_name.to_str().and_then(|name| name.strip_prefix(&prefix)) {
+            Some(suffix) => suffix,
+            None => continue,
+        };
+        let too_old = entry.metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age >= STALE_TEMP_FILE_AGE);
+        if too_old || temp_file_writer_exited(suffix) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Whether the process that wrote a temp file is known to have exited. The suffix of the temp file path holds the
+/// process ID, which can only be checked where `/proc` is available. Temp files in the old naming, whose suffix is
+/// a timestamp, don't hold a process ID
+fn temp_file_writer_exited(temp_file_suffix: &str) -> bool {
+    let pid = match temp_file_suffix.split_once('.').and_then(|(pid, _)| pid.parse::<u32>().ok()) {
+        Some(pid) => pid,
+        None => return false,
+    };
+    pid != process::id() && Path::new("/proc/self").exists() && !Path::new(&format!("/proc/{}", pid)).exists()
+}
@@ -0,0 +1,232 @@
+#[cfg(feature = "encryption")]
+use argon2::Argon2;
+#[cfg(feature = "encryption")]
+use chacha20poly1305::aead::rand_core::RngCore;
+#[cfg(feature = "encryption")]
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+#[cfg(feature = "encryption")]
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::PickleDbError;
+
+/// The bytes an encrypted DB file starts with, followed by the version of the file layout
+const MAGIC: &[u8] = b"PICKLEDB-ENC\x01";
+#[cfg(feature = "encryption")]
+const KDF_NONE: u8 = 0;
+#[cfg(feature = "encryption")]
+const KDF_ARGON2ID: u8 = 1;
+#[cfg(feature = "encryption")]
+const SALT_LEN: usize = 16;
+#[cfg(feature = "encryption")]
+const NONCE_LEN: usize = 12;
+#[cfg(feature = "encryption")]
+const TAG_LEN: usize = 16;
+
+/// Whether the content of a DB file was written by an encrypted DB
+pub(crate) fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(MAGIC)
+}
+
+/// A key for encrypting the DB file. Please see [PickleDb::with_encryption()](struct.PickleDb.html#method.with_encryption)
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+pub struct EncryptionKey(KeySource);
+
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+enum KeySource {
+    Key([u8; 32]),
+    Passphrase(String),
+}
+
+#[cfg(feature = "encryption")]
+impl EncryptionKey {
+    /// An encryption key of 32 random bytes, for example one that is kept in a secret store
+    pub fn new(key: [u8; 32]) -> EncryptionKey {
+        EncryptionKey(KeySource::Key(key))
+    }
+
+    /// An encryption key derived from a passphrase with Argon2id. The salt of the derivation is stored in the DB file,
+    /// so the same passphrase loads the DB again. Deriving the key is deliberately slow, and it's done once when the
+    /// DB is created or loaded rather than upon every dump
+    pub fn from_passphrase(passphrase: &str) -> EncryptionKey {
+        EncryptionKey(KeySource::Passphrase(String::from(passphrase)))
+    }
+}
+
+/// Encrypts the DB file and the records of its append-only log with ChaCha20-Poly1305.
+///
+/// An encrypted DB file is made of a header (the magic bytes, the key derivation and its salt), a random nonce
+/// and the encrypted content, and the header is authenticated along with the content. Every log record is
+/// encrypted on its own with a random nonce, and it's authenticated along with the header and the record before it,
+/// the first record in the log along with the nonce of the DB file it follows
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+pub(crate) struct Cipher {
+    key: EncryptionKey,
+    aead: ChaCha20Poly1305,
+    header: Vec<u8>,
+}
+
+/// Without the `encryption` feature there's no cipher, so DB files and log records are never encrypted
+#[cfg(not(feature = "encryption"))]
+#[derive(Clone)]
+pub(crate) enum Cipher {}
+
+#[cfg(feature = "encryption")]
+impl Cipher {
+    /// A cipher for a new DB file. A key derived from a passphrase gets a new random salt
+    pub(crate) fn new(key: &EncryptionKey) -> Cipher {
+        match &key.0 {
+            KeySource::Key(raw_key) => Cipher::with_header(key, raw_key, vec![KDF_NONE]),
+            KeySource::Passphrase(passphrase) => {
+                let mut salt = [0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                Cipher::from_passphrase(key, passphrase, &salt)
+            },
+        }
+    }
+
+    /// A cipher for an existing DB file, which takes the salt of a key derived from a passphrase from the file
+    pub(crate) fn for_file(key: &EncryptionKey, contents: &[u8]) -> Result<Cipher, PickleDbError> {
+        if !is_encrypted(contents) {
+            return Err(PickleDbError::Decryption(String::from("the DB file isn't encrypted")));
+        }
+        let header = &contents[MAGIC.len()..];
+        match (&key.0, header.first()) {
+            (KeySource::Key(raw_key), Some(&KDF_NONE)) => Ok(Cipher::with_header(key, raw_key, vec![KDF_NONE])),
+            (KeySource::Passphrase(passphrase), Some(&KDF_ARGON2ID)) if header.len() > SALT_LEN =>
+                Ok(Cipher::from_passphrase(key, passphrase, &header[1..=SALT_LEN])),
+            (KeySource::Key(_), Some(&KDF_ARGON2ID)) =>
+                Err(PickleDbError::Decryption(String::from("the DB file is encrypted with a passphrase rather than a key"))),
+            (KeySource::Passphrase(_), Some(&KDF_NONE)) =>
+                Err(PickleDbError::Decryption(String::from("the DB file is encrypted with a key rather than a passphrase"))),
+            _ => Err(PickleDbError::Corrupted(String::from("invalid header of an encrypted DB file"))),
+        }
+    }
+
+    /// A cipher with the same key for another content of the DB file, which may have been encrypted with another salt
+    pub(crate) fn for_contents(&self, contents: &[u8]) -> Result<Cipher, PickleDbError> {
+        if contents.starts_with(&self.header) {
+            return Ok(self.clone());
+        }
+        Cipher::for_file(&self.key, contents)
+    }
+
+    fn from_passphrase(key: &EncryptionKey, passphrase: &str, salt: &[u8]) -> Cipher {
+        let mut raw_key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut raw_key)
+            .expect("the salt and the key length are valid for Argon2");
+        let mut kdf = vec![KDF_ARGON2ID];
+        kdf.extend_from_slice(salt);
+        Cipher::with_header(key, &raw_key, kdf)
+    }
+
+    fn with_header(key: &EncryptionKey, raw_key: &[u8; 32], kdf: Vec<u8>) -> Cipher {
+        let mut header = MAGIC.to_vec();
+        header.extend(kdf);
+        Cipher { key: key.clone(), aead: ChaCha20Poly1305::new(Key::from_slice(raw_key)), header }
+    }
+
+    /// Encrypt the content of the DB file
+    pub(crate) fn encrypt_file(&self, contents: &[u8]) -> Result<Vec<u8>, PickleDbError> {
+        let mut buf = self.header.clone();
+        buf.extend(self.seal(contents, &self.header)?);
+        Ok(buf)
+    }
+
+    /// Decrypt the content of the DB file. A wrong key and a file that was changed can't be told apart
+    pub(crate) fn decrypt_file(&self, contents: &[u8]) -> Result<Vec<u8>, PickleDbError> {
+        if !contents.starts_with(&self.header) {
+            return Err(wrong_key_error());
+        }
+        self.open(&contents[self.header.len()..], &self.header)
+    }
+
+    /// The chain that the first record appended to the log after a dump of the DB file with the given content is bound
+    /// to, which is the random nonce of the file. Every dump has another nonce, so records of the log of an earlier dump
+    /// fail to decrypt after the DB file was dumped again
+    pub(crate) fn log_chain_start(&self, contents: &[u8]) -> Vec<u8> {
+        contents.iter().skip(self.header.len()).take(NONCE_LEN).copied().collect()
+    }
+
+    /// Encrypt a record of the append-only log, which is authenticated along with the header and `chain`: the nonce of
+    /// the DB file for the first record in the log and the tag of the previous record for the others. `chain` is then
+    /// set to the tag of this record. Records can't be reordered, replayed, dropped from the middle of the log or moved
+    /// to the log of another dump without failing to decrypt. `last` tells whether the record is the last one of the
+    /// records that are appended together, so a log that was cut off in the middle of them is told apart
+    pub(crate) fn encrypt_record(&self, record: &[u8], chain: &mut Vec<u8>, last: bool) -> Result<Vec<u8>, PickleDbError> {
+        let mut msg = vec![last as u8];
+        msg.extend_from_slice(record);
+        let encrypted = self.seal(&msg, &self.record_aad(chain))?;
+        *chain = encrypted[encrypted.len() - TAG_LEN..].to_vec();
+        Ok(encrypted)
+    }
+
+    /// Decrypt a record of the append-only log that is chained to `chain`, which is then set to the tag of this record.
+    /// Returns the record and whether it's the last one of the records that were appended together
+    pub(crate) fn decrypt_record(&self, record: &[u8], chain: &mut Vec<u8>) -> Result<(Vec<u8>, bool), PickleDbError> {
+        let mut msg = self.open(record, &self.record_aad(chain))?;
+        *chain = record[record.len() - TAG_LEN..].to_vec();
+        match msg.first() {
+            Some(&last) if last <= 1 => Ok((msg.split_off(1), last == 1)),
+            _ => Err(PickleDbError::Corrupted(String::from("invalid record in the log of an encrypted DB"))),
+        }
+    }
+
+    fn record_aad(&self, chain: &[u8]) -> Vec<u8> {
+        let mut aad = self.header.clone();
+        aad.extend_from_slice(chain);
+        aad
+    }
+
+    fn seal(&self, msg: &[u8], aad: &[u8]) -> Result<Vec<u8>, PickleDbError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self.aead.encrypt(&nonce, Payload { msg, aad })
+            .map_err(|_| PickleDbError::Serialization(String::from("encryption failed")))?;
+        let mut buf = nonce.to_vec();
+        buf.extend(ciphertext);
+        Ok(buf)
+    }
+
+    fn open(&self, contents: &[u8], aad: &[u8]) -> Result<Vec<u8>, PickleDbError> {
+        if contents.len() < NONCE_LEN {
+            return Err(wrong_key_error());
+        }
+        let (nonce, msg) = contents.split_at(NONCE_LEN);
+        self.aead.decrypt(Nonce::from_slice(nonce), Payload { msg, aad }).map_err(|_| wrong_key_error())
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+impl Cipher {
+    pub(crate) fn for_contents(&self, _contents: &[u8]) -> Result<Cipher, PickleDbError> {
+        match *self {}
+    }
+
+    pub(crate) fn decrypt_file(&self, _contents: &[u8]) -> Result<Vec<u8>, PickleDbError> {
+        match *self {}
+    }
+
+    pub(crate) fn encrypt_file(&self, _contents: &[u8]) -> Result<Vec<u8>, PickleDbError> {
+        match *self {}
+    }
+
+    pub(crate) fn log_chain_start(&self, _contents: &[u8]) -> Vec<u8> {
+        match *self {}
+    }
+
+    pub(crate) fn encrypt_record(&self, _record: &[u8], _chain: &mut Vec<u8>, _last: bool) -> Result<Vec<u8>, PickleDbError> {
+        match *self {}
+    }
+
+    pub(crate) fn decrypt_record(&self, _record: &[u8], _chain: &mut Vec<u8>) -> Result<(Vec<u8>, bool), PickleDbError> {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn wrong_key_error() -> PickleDbError {
+    PickleDbError::Decryption(String::from("the key is wrong or the DB file was tampered with"))
+}
@@ -0,0 +1,84 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// An enum that represents the errors PickleDB may return
+#[derive(Debug)]
+pub enum PickleDbError {
+    /// Reading from or writing to the DB file failed
+    Io(io::Error),
+    /// A value, a list item or the DB itself couldn't be serialized
+    Serialization(String),
+    /// A value or a list item couldn't be deserialized to the requested type, which usually means the
+    /// value is of a different type. Contains the key or list name and the deserialization error message
+    Deserialization {
+        /// The key of the value or the name of the list
+        key: String,
+        /// The error message returned by the deserializer
+        message: String,
+    },
+    /// The key or list doesn't exist in the DB. Contains the key name
+    KeyNotFound(String),
+    /// The key exists in the DB but holds a different kind of data than the one the operation works on,
+    /// for example a value where a list is expected. Contains the key name
+    WrongType(String),
+    /// The DB was loaded with [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump)
+    /// so it can't be dumped to the file
+    ReadOnly,
+    /// The content of the DB file couldn't be deserialized
+    Corrupted(String),
+    /// A transaction can't be started or the DB can't be dumped because a transaction is already in progress
+    TransactionInProgress,
+    /// A transaction can't be committed or rolled back because no transaction is in progress
+    NoTransaction,
+    /// Incrementing or decrementing a counter or a score would make it overflow. Contains the key name
+    Overflow(String),
+    /// The score of a sorted set member isn't a finite number. Contains the name of the sorted set
+    InvalidScore(String),
+    /// An encrypted DB file couldn't be decrypted, usually because the key is wrong or the file was tampered with,
+    /// or the DB file isn't encrypted the way it's loaded. Contains the reason
+    Decryption(String),
+    /// The DB file is locked by another DB, in this process or in another one. Please see
+    /// [PickleDb::load_locked()](struct.PickleDb.html#method.load_locked)
+    Locked,
+    /// The DB can't be reloaded because it has changes that weren't dumped yet. Please see
+    /// [PickleDb::reload()](struct.PickleDb.html#method.reload)
+    UnsavedChanges,
+}
+
+impl fmt::Display for PickleDbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PickleDbError::Io(err) => write!(f, "I/O error: {}", err),
+            PickleDbError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            PickleDbError::Deserialization { key, message } =>
+                write!(f, "Deserialization error of key '{}': {}", key, message),
+            PickleDbError::KeyNotFound(key) => write!(f, "Key '{}' doesn't exist", key),
+            PickleDbError::WrongType(key) => write!(f, "Key '{}' holds a different kind of data", key),
+            PickleDbError::ReadOnly => write!(f, "DB is read-only"),
+            PickleDbError::Corrupted(msg) => write!(f, "DB file is corrupted: {}", msg),
+            PickleDbError::TransactionInProgress => write!(f, "A transaction is already in progress"),
+            PickleDbError::NoTransaction => write!(f, "No transaction is in progress"),
+            PickleDbError::Overflow(key) => write!(f, "Value of key '{}' would overflow", key),
+            PickleDbError::InvalidScore(key) => write!(f, "Score in sorted set '{}' isn't a finite number", key),
+            PickleDbError::Decryption(msg) => write!(f, "DB file couldn't be decrypted: {}", msg),
+            PickleDbError::Locked => write!(f, "DB file is locked by another DB"),
+            PickleDbError::UnsavedChanges => write!(f, "DB has changes that weren't dumped yet"),
+        }
+    }
+}
+
+impl error::Error for PickleDbError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PickleDbError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PickleDbError {
+    fn from(err: io::Error) -> PickleDbError {
+        PickleDbError::Io(err)
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::mpsc::Sender;
+
+/// A change of a key in the DB, which is sent to the subscribers of the DB. Please see
+/// [PickleDb::subscribe()](struct.PickleDb.html#method.subscribe)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickleDbEvent {
+    /// A value was set. Contains the key
+    Set(String),
+    /// The key was removed. Contains the key
+    Removed(String),
+    /// A list was created or changed. Contains the list name
+    ListChanged(String),
+    /// A dict was created or changed. Contains the dict name
+    DictChanged(String),
+    /// A set was created or changed. Contains the set name
+    SetChanged(String),
+    /// A sorted set was created or changed. Contains the sorted set name
+    SortedSetChanged(String),
+    /// The key expired and was removed from the DB. Contains the key
+    Expired(String),
+}
+
+impl PickleDbEvent {
+
+    /// Get the key that changed
+    pub fn key(&self) -> &str {
+        match self {
+            PickleDbEvent::Set(key)
+            | PickleDbEvent::Removed(key)
+            | PickleDbEvent::ListChanged(key)
+            | PickleDbEvent::DictChanged(key)
+            | PickleDbEvent::SetChanged(key)
+            | PickleDbEvent::SortedSetChanged(key)
+            | PickleDbEvent::Expired(key) => key,
+        }
+    }
+}
+
+/// A subscription to the changes of the DB. Returned in [PickleDb::subscribe()](struct.PickleDb.html#method.subscribe)
+/// and used for [PickleDb::unsubscribe()](struct.PickleDb.html#method.unsubscribe)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PickleDbSubscription(u64);
+
+/// Where the events of a subscriber are sent
+enum Callback {
+    Closure(Box<dyn FnMut(&PickleDbEvent) + Send + Sync>),
+    Channel(Sender<PickleDbEvent>),
+}
+
+struct Subscriber {
+    id: u64,
+    prefix: String,
+    callback: Callback,
+}
+
+/// The subscribers of a DB
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    subscribers: Vec<Subscriber>,
+    next_id: u64,
+}
+
+impl Subscribers {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    pub(crate) fn add_closure<F>(&mut self, prefix: &str, callback: F) -> PickleDbSubscription
+        where
+            F: FnMut(&PickleDbEvent) + Send + Sync + 'static
+    {
+        self.add(prefix, Callback::Closure(Box::new(callback)))
+    }
+
+    pub(crate) fn add_channel(&mut self, prefix: &str, sender: Sender<PickleDbEvent>) -> PickleDbSubscription {
+        self.add(prefix, Callback::Channel(sender))
+    }
+
+    fn add(&mut self, prefix: &str, callback: Callback) -> PickleDbSubscription {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscribers.push(Subscriber { id, prefix: String::from(prefix), callback });
+        PickleDbSubscription(id)
+    }
+
+    /// Remove a subscriber. Returns whether it was found
+    pub(crate) fn remove(&mut self, subscription: PickleDbSubscription) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers.retain(|subscriber| subscriber.id != subscription.0);
+        self.subscribers.len() < len
+    }
+
+    /// Send an event to the subscribers whose prefix matches the key. Channels whose receiver was dropped are removed
+    pub(crate) fn notify(&mut self, event: &PickleDbEvent) {
+        self.subscribers.retain_mut(|subscriber| {
+            if !event.key().starts_with(&subscriber.prefix) {
+                return true;
+            }
+            match &mut subscriber.callback {
+                Callback::Closure(callback) => {
+                    callback(event);
+                    true
+                },
+                Callback::Channel(sender) => sender.send(event.clone()).is_ok(),
+            }
+        });
+    }
+}
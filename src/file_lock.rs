@@ -0,0 +1,47 @@
+use std::fs::{File, OpenOptions};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fs2::FileExt;
+
+use crate::error::PickleDbError;
+use crate::PickleDbLockPolicy;
+
+/// How long to sleep between attempts to take a lock that is held by another DB
+const RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The path of the lock file of a DB file. The DB file itself can't be locked since every dump replaces it
+pub(crate) fn lock_file_path(db_file_path: &str) -> String {
+    format!("{}.lock", db_file_path)
+}
+
+/// Take an advisory lock of a DB file, which is released when the returned file is closed. An exclusive lock is
+/// held by a single DB while a shared lock can be held by several DBs at once, as long as there is no exclusive lock
+pub(crate) fn lock(db_file_path: &str, exclusive: bool, lock_policy: PickleDbLockPolicy) -> Result<File, PickleDbError> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_file_path(db_file_path))?;
+    let deadline = match lock_policy {
+        PickleDbLockPolicy::NoWait => None,
+        PickleDbLockPolicy::Wait(timeout) => Some(Instant::now() + timeout),
+    };
+    loop {
+        // called through the trait since newer versions of std have a method of the same name
+        let res = if exclusive {
+            FileExt::try_lock_exclusive(&file)
+        } else {
+            FileExt::try_lock_shared(&file)
+        };
+        match res {
+            Ok(()) => return Ok(file),
+            Err(ref err) if err.kind() == fs2::lock_contended_error().kind() => match deadline {
+                Some(deadline) if Instant::now() < deadline => thread::sleep(RETRY_INTERVAL),
+                _ => return Err(PickleDbError::Locked),
+            },
+            Err(err) => return Err(PickleDbError::Io(err)),
+        }
+    }
+}
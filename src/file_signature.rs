@@ -0,0 +1,50 @@
+use std::fs;
+use std::io;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::time::SystemTime;
+
+use crate::append_log;
+use crate::error::PickleDbError;
+
+/// The metadata of a file that changes when the file is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileMetadata {
+    len: u64,
+    modified: Option<SystemTime>,
+    /// A dump replaces the DB file with a new one, which has a new inode even if its size and time are the same
+    #[cfg(unix)]
+    inode: u64,
+}
+
+/// The state of a DB file and its append-only log, used for telling whether another DB changed them.
+/// Please see [PickleDb::reload_if_changed()](struct.PickleDb.html#method.reload_if_changed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileSignature {
+    db_file: Option<FileMetadata>,
+    log_file: Option<FileMetadata>,
+}
+
+impl FileSignature {
+    /// The current signature of a DB file. A file that doesn't exist has no metadata
+    pub(crate) fn read(db_file_path: &str) -> Result<FileSignature, PickleDbError> {
+        Ok(FileSignature {
+            db_file: file_metadata(db_file_path)?,
+            log_file: file_metadata(&append_log::log_file_path(db_file_path))?,
+        })
+    }
+}
+
+fn file_metadata(path: &str) -> Result<Option<FileMetadata>, PickleDbError> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(PickleDbError::Io(err)),
+    };
+    Ok(Some(FileMetadata {
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+        #[cfg(unix)]
+        inode: metadata.ino(),
+    }))
+}
@@ -1,29 +1,38 @@
-use std::collections::hash_map;
+use std::collections::{hash_map, hash_set, HashMap};
 use std::slice;
 use serde::{de::DeserializeOwned};
 
-use crate::serialization::deserialize_data;
+use crate::error::PickleDbError;
+use crate::keymap;
+use crate::serialization::{SerializationMethod, SerializedValue};
 
 /// Iterator object for iterating over keys and values in PickleDB. Returned in [PickleDb::iter()](struct.PickleDb.html#method.iter)
+/// 
+/// If the DB has ordered keys (please see [PickleDb::with_ordered_keys()](struct.PickleDb.html#method.with_ordered_keys))
+/// the keys are iterated in order, otherwise in an arbitrary order
 pub struct PickleDbIterator<'a> {
-    pub(crate) map_iter: hash_map::Iter<'a, String, String>
+    pub(crate) map_iter: keymap::Iter<'a, SerializedValue>,
+    pub(crate) expiry: &'a HashMap<String, u64>,
+    pub(crate) now: u64,
+    pub(crate) ser_method: SerializationMethod,
 }
 
 impl<'a> Iterator for PickleDbIterator<'a> {
     type Item = PickleDbIteratorItem<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.map_iter.next() {
-            Some((key, value_as_string)) => Some(PickleDbIteratorItem { key: key, value_as_string: value_as_string}),
-            None => None
-        }
+        let (expiry, now, ser_method) = (self.expiry, self.now, self.ser_method);
+        self.map_iter
+            .find(|(key, _)| expiry.get(*key).is_none_or(|expires_at| *expires_at > now))
+            .map(|(key, value)| PickleDbIteratorItem { key, value, ser_method })
     }
 }
 
 /// The object returned in each iteration when iterating over keys and values in PickleDB
 pub struct PickleDbIteratorItem<'a> {
     key: &'a str,
-    value_as_string: &'a str,
+    value: &'a SerializedValue,
+    ser_method: SerializationMethod,
 }
 
 impl<'a> PickleDbIteratorItem<'a> {
@@ -44,29 +53,43 @@ impl<'a> PickleDbIteratorItem<'a> {
     /// The method returns `Some(V)` if deserialization succeeds or `None` otherwise.
     /// 
     pub fn get_value<V>(&self) -> Option<V> where V: DeserializeOwned {
-        deserialize_data::<V>(self.value_as_string)
+        self.try_get_value().ok()
+    }
+
+    /// Get the value of the key, reporting why it couldn't be retrieved.
+    /// 
+    /// This method is similar to [get_value()](#method.get_value) but if the value can't be deserialized
+    /// to `V`, which usually means the value is of a different type,
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with
+    /// the key name and the deserialization error.
+    /// 
+    pub fn try_get_value<V>(&self) -> Result<V, PickleDbError> where V: DeserializeOwned {
+        self.ser_method.deserialize_data::<V>(&self.value.0)
+            .map_err(|message| PickleDbError::Deserialization { key: String::from(self.key), message })
     }
 }
 
 /// Iterator object for iterating over items in a PickleDB list. Returned in [PickleDb::liter()](struct.PickleDb.html#method.liter)
 pub struct PickleDbListIterator<'a> {
-    pub(crate) list_iter: slice::Iter<'a, String>
+    pub(crate) list_name: &'a str,
+    pub(crate) list_iter: slice::Iter<'a, SerializedValue>,
+    pub(crate) ser_method: SerializationMethod,
 }
 
 impl<'a> Iterator for PickleDbListIterator<'a> {
     type Item = PickleDbListIteratorItem<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.list_iter.next() {
-            Some(value_as_string) => Some(PickleDbListIteratorItem { value_as_string: value_as_string }),
-            None => None
-        }
+        let (list_name, ser_method) = (self.list_name, self.ser_method);
+        self.list_iter.next().map(|value| PickleDbListIteratorItem { list_name, value, ser_method })
     }
 }
 
 /// The object returned in each iteration when iterating over PickleDB list
 pub struct PickleDbListIteratorItem<'a> {
-    value_as_string: &'a str,
+    list_name: &'a str,
+    value: &'a SerializedValue,
+    ser_method: SerializationMethod,
 }
 
 impl<'a> PickleDbListIteratorItem<'a> {
@@ -80,6 +103,121 @@ impl<'a> PickleDbListIteratorItem<'a> {
     /// The method returns `Some(V)` if deserialization succeeds or `None` otherwise.
     /// 
     pub fn get_item<V>(&self) -> Option<V> where V: DeserializeOwned {
-        deserialize_data(self.value_as_string)
+        self.try_get_item().ok()
+    }
+
+    /// Get the item in the current position, reporting why it couldn't be retrieved.
+    /// 
+    /// This method is similar to [get_item()](#method.get_item) but if the item can't be deserialized
+    /// to `V`, which usually means the item is of a different type,
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with
+    /// the list name and the deserialization error.
+    /// 
+    pub fn try_get_item<V>(&self) -> Result<V, PickleDbError> where V: DeserializeOwned {
+        self.ser_method.deserialize_data(&self.value.0)
+            .map_err(|message| PickleDbError::Deserialization { key: String::from(self.list_name), message })
+    }
+}
+/// Iterator object for iterating over keys and values in a PickleDB dict. Returned in [PickleDb::diter()](struct.PickleDb.html#method.diter)
+pub struct PickleDbDictIterator<'a> {
+    pub(crate) dict_name: &'a str,
+    pub(crate) dict_iter: hash_map::Iter<'a, String, SerializedValue>,
+    pub(crate) ser_method: SerializationMethod,
+}
+
+impl<'a> Iterator for PickleDbDictIterator<'a> {
+    type Item = PickleDbDictIteratorItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (dict_name, ser_method) = (self.dict_name, self.ser_method);
+        self.dict_iter.next().map(|(key, value)| PickleDbDictIteratorItem { dict_name, key, value, ser_method })
+    }
+}
+
+/// The object returned in each iteration when iterating over a PickleDB dict
+pub struct PickleDbDictIteratorItem<'a> {
+    dict_name: &'a str,
+    key: &'a str,
+    value: &'a SerializedValue,
+    ser_method: SerializationMethod,
+}
+
+impl<'a> PickleDbDictIteratorItem<'a> {
+
+    /// Get the key inside the dict
+    pub fn get_key(&self) -> &str {
+        self.key
+    }
+
+    /// Get the value of the key inside the dict.
+    /// 
+    /// Dicts are heterogeneous, so it's the user's responsibility to know the value type and give it
+    /// while calling this method. Since the values are stored in a serialized way the returned object is
+    /// not a reference to the value stored in a DB but actually a new instance of it.
+    /// The method returns `Some(V)` if deserialization succeeds or `None` otherwise.
+    /// 
+    pub fn get_value<V>(&self) -> Option<V> where V: DeserializeOwned {
+        self.try_get_value().ok()
+    }
+
+    /// Get the value of the key inside the dict, reporting why it couldn't be retrieved.
+    /// 
+    /// This method is similar to [get_value()](#method.get_value) but if the value can't be deserialized
+    /// to `V`, which usually means the value is of a different type,
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with
+    /// the dict name and the deserialization error.
+    /// 
+    pub fn try_get_value<V>(&self) -> Result<V, PickleDbError> where V: DeserializeOwned {
+        self.ser_method.deserialize_data(&self.value.0)
+            .map_err(|message| PickleDbError::Deserialization { key: String::from(self.dict_name), message })
+    }
+}
+
+/// Iterator object for iterating over members of a PickleDB set. Returned in [PickleDb::siter()](struct.PickleDb.html#method.siter)
+pub struct PickleDbSetIterator<'a> {
+    pub(crate) set_name: &'a str,
+    pub(crate) set_iter: hash_set::Iter<'a, SerializedValue>,
+    pub(crate) ser_method: SerializationMethod,
+}
+
+impl<'a> Iterator for PickleDbSetIterator<'a> {
+    type Item = PickleDbSetIteratorItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (set_name, ser_method) = (self.set_name, self.ser_method);
+        self.set_iter.next().map(|value| PickleDbSetIteratorItem { set_name, value, ser_method })
+    }
+}
+
+/// The object returned in each iteration when iterating over a PickleDB set
+pub struct PickleDbSetIteratorItem<'a> {
+    set_name: &'a str,
+    value: &'a SerializedValue,
+    ser_method: SerializationMethod,
+}
+
+impl<'a> PickleDbSetIteratorItem<'a> {
+
+    /// Get the current member.
+    /// 
+    /// Sets are heterogeneous, so it's the user's responsibility to know the member type and give it while
+    /// calling this method. Since the members are stored in a serialized way the returned object is not a
+    /// reference to the member stored in a DB but actually a new instance of it.
+    /// The method returns `Some(V)` if deserialization succeeds or `None` otherwise.
+    /// 
+    pub fn get_item<V>(&self) -> Option<V> where V: DeserializeOwned {
+        self.try_get_item().ok()
     }
-}
\ No newline at end of file
+
+    /// Get the current member, reporting why it couldn't be retrieved.
+    /// 
+    /// This method is similar to [get_item()](#method.get_item) but if the member can't be deserialized
+    /// to `V`, which usually means the member is of a different type,
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with
+    /// the set name and the deserialization error.
+    /// 
+    pub fn try_get_item<V>(&self) -> Result<V, PickleDbError> where V: DeserializeOwned {
+        self.ser_method.deserialize_data(&self.value.0)
+            .map_err(|message| PickleDbError::Deserialization { key: String::from(self.set_name), message })
+    }
+}
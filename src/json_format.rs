@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::ser::{Formatter, PrettyFormatter, Serializer};
+use serde_json::Value;
+
+use crate::{DbFileContents, PickleDb};
+use crate::serialization::SerializedValue;
+use crate::sorted_set::SortedSet;
+
+/// The options of a readable dump. Please see [PickleDb::with_readable_dump()](struct.PickleDb.html#method.with_readable_dump)
+#[derive(Clone, Copy)]
+pub(crate) struct ReadableDump {
+    pub(crate) pretty_values: bool,
+}
+
+/// The DB data as it's written by a readable dump. Values are written as nested JSON and keys are sorted
+#[derive(Serialize)]
+struct ReadableContents<'a> {
+    values: BTreeMap<&'a str, OrderedValue>,
+    lists: BTreeMap<&'a str, Vec<OrderedValue>>,
+    dicts: BTreeMap<&'a str, BTreeMap<&'a str, OrderedValue>>,
+    sets: BTreeMap<&'a str, Vec<OrderedValue>>,
+    sorted_sets: BTreeMap<&'a str, Vec<OrderedValue>>,
+    expiry: BTreeMap<&'a str, u64>,
+}
+
+/// The DB data as it's read from a file written by a readable dump
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReadableFileContents {
+    #[serde(default)] values: HashMap<String, OrderedValue>,
+    #[serde(default)] lists: HashMap<String, Vec<OrderedValue>>,
+    #[serde(default)] dicts: HashMap<String, HashMap<String, OrderedValue>>,
+    #[serde(default)] sets: HashMap<String, Vec<OrderedValue>>,
+    #[serde(default)] sorted_sets: HashMap<String, Vec<(OrderedValue, f64)>>,
+    #[serde(default)] expiry: HashMap<String, u64>,
+}
+
+/// Whether a JSON DB file was written by a readable dump. The default layout is a JSON array while
+/// a readable dump writes a JSON object
+pub(crate) fn is_readable(contents: &[u8]) -> bool {
+    contents.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'{')
+}
+
+/// Read a DB file that was written by a readable dump
+pub(crate) fn read(contents: &[u8]) -> Result<DbFileContents, String> {
+    let file_contents: ReadableFileContents = serde_json::from_slice(contents).map_err(|err| err.to_string())?;
+
+    let map = file_contents.values.into_iter()
+        .map(|(key, value)| to_serialized(&value).map(|value| (key, value)))
+        .collect::<Result<_, _>>()?;
+    let list_map = file_contents.lists.into_iter()
+        .map(|(name, items)| items.iter().map(to_serialized).collect::<Result<_, _>>().map(|items| (name, items)))
+        .collect::<Result<_, _>>()?;
+    let dict_map = file_contents.dicts.into_iter()
+        .map(|(name, pairs)| {
+            pairs.into_iter()
+                .map(|(dict_key, value)| to_serialized(&value).map(|value| (dict_key, value)))
+                .collect::<Result<_, _>>()
+                .map(|pairs| (name, pairs))
+        })
+        .collect::<Result<_, _>>()?;
+    let set_map = file_contents.sets.into_iter()
+        .map(|(name, members)| members.iter().map(to_serialized).collect::<Result<_, _>>().map(|members| (name, members)))
+        .collect::<Result<_, _>>()?;
+    let sorted_set_map = file_contents.sorted_sets.into_iter()
+        .map(|(name, members)| {
+            members.iter()
+                .map(|(member, score)| to_serialized(member).map(|member| (member, *score)))
+                .collect::<Result<_, _>>()
+                .map(|members| (name, members))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(DbFileContents(map, list_map, file_contents.expiry, dict_map, set_map, sorted_set_map))
+}
+
+/// Write the DB with sorted keys and values as nested JSON, one entry per line. Set members are sorted too and
+/// members of sorted sets are written as member and score pairs, ordered by score
+pub(crate) fn write(db: &PickleDb, options: ReadableDump) -> Result<Vec<u8>, String> {
+    let mut contents = ReadableContents {
+        values: BTreeMap::new(),
+        lists: BTreeMap::new(),
+        dicts: BTreeMap::new(),
+        sets: BTreeMap::new(),
+        sorted_sets: BTreeMap::new(),
+        expiry: db.expiry.iter().map(|(key, expires_at)| (key.as_str(), *expires_at)).collect(),
+    };
+    for (key, value) in db.map.iter() {
+        contents.values.insert(key, from_serialized(value)?);
+    }
+    for (name, items) in db.list_map.iter() {
+        contents.lists.insert(name, items.iter().map(from_serialized).collect::<Result<_, _>>()?);
+    }
+    for (name, pairs) in db.dict_map.iter() {
+        let pairs = pairs.iter()
+            .map(|(dict_key, value)| from_serialized(value).map(|value| (dict_key.as_str(), value)))
+            .collect::<Result<_, _>>()?;
+        contents.dicts.insert(name, pairs);
+    }
+    for (name, members) in db.set_map.iter() {
+        contents.sets.insert(name, sorted_members(members)?);
+    }
+    for (name, sorted_set) in db.sorted_set_map.iter() {
+        contents.sorted_sets.insert(name, scored_members(sorted_set)?);
+    }
+    to_vec(&contents, 2, options)
+}
+
+/// The members of a set as JSON values, sorted by their serialized form so the same set is always written the same way
+pub(crate) fn sorted_members(members: &HashSet<SerializedValue>) -> Result<Vec<OrderedValue>, String> {
+    let mut members: Vec<&SerializedValue> = members.iter().collect();
+    members.sort_by(|a, b| a.0.cmp(&b.0));
+    members.into_iter().map(from_serialized).collect()
+}
+
+/// The members of a sorted set and their scores as JSON arrays of two items, ordered by score
+pub(crate) fn scored_members(sorted_set: &SortedSet) -> Result<Vec<OrderedValue>, String> {
+    sorted_set.iter()
+        .map(|(member, score)| {
+            from_serialized(member).map(|member| OrderedValue::Array(vec![member, OrderedValue::Scalar(Value::from(score))]))
+        })
+        .collect()
+}
+
+/// Serialize to JSON with every entry of the objects up to `entry_depth` on its own line. Deeper values are
+/// written on the same line as their entry, unless they should be pretty-printed too
+pub(crate) fn to_vec<T: Serialize>(value: &T, entry_depth: usize, options: ReadableDump) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    if options.pretty_values {
+        value.serialize(&mut Serializer::with_formatter(&mut buf, PrettyFormatter::new()))
+    } else {
+        value.serialize(&mut Serializer::with_formatter(&mut buf, EntryPerLineFormatter { entry_depth, depth: 0, has_value: false }))
+    }.map_err(|err| err.to_string())?;
+    buf.push(b'\n');
+    Ok(buf)
+}
+
+/// A value as it's stored in the DB. The order of object keys is kept, so a value that is read back from a file gets
+/// the same bytes it was stored with, which the APIs that compare values, like `sismember()`, rely on
+pub(crate) fn to_serialized(value: &OrderedValue) -> Result<SerializedValue, String> {
+    serde_json::to_vec(value).map(SerializedValue).map_err(|err| err.to_string())
+}
+
+/// A value as it's written to a file. JSON numbers that don't fit in 64 bits, like `i128` values, are read as floats
+/// and would be written with less precision, so a value that isn't written back the same way is an error
+pub(crate) fn from_serialized(value: &SerializedValue) -> Result<OrderedValue, String> {
+    let ordered_value = serde_json::from_slice(&value.0).map_err(|err| err.to_string())?;
+    if to_serialized(&ordered_value)? != *value {
+        return Err(String::from("a value holds a number that can't be written as nested JSON without losing precision"));
+    }
+    Ok(ordered_value)
+}
+
+/// A JSON value whose objects keep the order of their keys, unlike `serde_json::Value` whose objects sort them
+#[derive(Clone, Debug)]
+pub(crate) enum OrderedValue {
+    Object(Vec<(String, OrderedValue)>),
+    Array(Vec<OrderedValue>),
+    /// Any value that is neither an object nor an array
+    Scalar(Value),
+}
+
+impl Serialize for OrderedValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            OrderedValue::Object(pairs) => {
+                let mut map = serializer.serialize_map(Some(pairs.len()))?;
+                for (key, value) in pairs {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            },
+            OrderedValue::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            },
+            OrderedValue::Scalar(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<OrderedValue, D::Error> {
+        deserializer.deserialize_any(OrderedValueVisitor)
+    }
+}
+
+struct OrderedValueVisitor;
+
+impl<'de> Visitor<'de> for OrderedValueVisitor {
+    type Value = OrderedValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::from(value)))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::from(value)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::from(value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::from(value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::from(value)))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::from(value)))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::Null))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Scalar(Value::Null))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<OrderedValue, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OrderedValue, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(OrderedValue::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedValue, A::Error> {
+        let mut pairs: Vec<(String, OrderedValue)> = Vec::new();
+        while let Some((key, value)) = map.next_entry::<String, OrderedValue>()? {
+            // like serde_json, the last value of a key that appears more than once wins
+            match pairs.iter_mut().find(|(existing, _)| *existing == key) {
+                Some(pair) => pair.1 = value,
+                None => pairs.push((key, value)),
+            }
+        }
+        Ok(OrderedValue::Object(pairs))
+    }
+}
+
+/// A JSON formatter that pretty-prints objects up to a certain depth and writes deeper values compactly
+struct EntryPerLineFormatter {
+    entry_depth: usize,
+    depth: usize,
+    has_value: bool,
+}
+
+impl EntryPerLineFormatter {
+    fn indent<W: ?Sized + io::Write>(writer: &mut W, depth: usize) -> io::Result<()> {
+        for _ in 0..depth {
+            writer.write_all(b"  ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for EntryPerLineFormatter {
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth -= 1;
+        if self.depth < self.entry_depth && self.has_value {
+            writer.write_all(b"\n")?;
+            EntryPerLineFormatter::indent(writer, self.depth)?;
+        }
+        writer.write_all(b"}")
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        if !first {
+            writer.write_all(b",")?;
+        }
+        if self.depth <= self.entry_depth {
+            writer.write_all(b"\n")?;
+            EntryPerLineFormatter::indent(writer, self.depth)?;
+        }
+        Ok(())
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.depth <= self.entry_depth {
+            writer.write_all(b": ")
+        } else {
+            writer.write_all(b":")
+        }
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+}
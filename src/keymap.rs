@@ -0,0 +1,143 @@
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
+use std::ops::Bound;
+use serde::{Serialize, Serializer};
+
+/// A map from keys to the data they hold, which is either a hash map or an ordered map.
+/// Please see [PickleDb::with_ordered_keys()](struct.PickleDb.html#method.with_ordered_keys)
+#[derive(Clone)]
+pub(crate) enum KeyMap<V> {
+    Hashed(HashMap<String, V>),
+    Ordered(BTreeMap<String, V>),
+}
+
+impl<V> KeyMap<V> {
+    pub(crate) fn new() -> KeyMap<V> {
+        KeyMap::Hashed(HashMap::new())
+    }
+
+    pub(crate) fn is_ordered(&self) -> bool {
+        matches!(self, KeyMap::Ordered(_))
+    }
+
+    /// Move all entries to an ordered map. Does nothing if the map is already ordered
+    pub(crate) fn make_ordered(&mut self) {
+        if let KeyMap::Hashed(map) = self {
+            *self = KeyMap::Ordered(map.drain().collect());
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&V> {
+        match self {
+            KeyMap::Hashed(map) => map.get(key),
+            KeyMap::Ordered(map) => map.get(key),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        match self {
+            KeyMap::Hashed(map) => map.get_mut(key),
+            KeyMap::Ordered(map) => map.get_mut(key),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, key: String, value: V) -> Option<V> {
+        match self {
+            KeyMap::Hashed(map) => map.insert(key, value),
+            KeyMap::Ordered(map) => map.insert(key, value),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &str) -> Option<V> {
+        match self {
+            KeyMap::Hashed(map) => map.remove(key),
+            KeyMap::Ordered(map) => map.remove(key),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, V> {
+        match self {
+            KeyMap::Hashed(map) => Iter::Hashed(map.iter()),
+            KeyMap::Ordered(map) => Iter::Ordered(map.iter()),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// The keys within the given bounds, sorted. An ordered map finds them without going over all keys
+    pub(crate) fn range_keys<'a>(&'a self, start: Bound<&'a str>, end: Bound<&'a str>) -> Box<dyn Iterator<Item = &'a String> + 'a> {
+        match self {
+            KeyMap::Hashed(map) => {
+                let mut keys: Vec<&String> = map.keys().filter(|key| in_bounds(key, start, end)).collect();
+                keys.sort();
+                Box::new(keys.into_iter())
+            },
+            // BTreeMap::range() panics if start comes after end or if both bounds exclude the same key
+            KeyMap::Ordered(_) if !is_valid_range(start, end) => Box::new(std::iter::empty()),
+            KeyMap::Ordered(map) => Box::new(map.range::<str, _>((start, end)).map(|(key, _)| key)),
+        }
+    }
+}
+
+impl<V> From<HashMap<String, V>> for KeyMap<V> {
+    fn from(map: HashMap<String, V>) -> KeyMap<V> {
+        KeyMap::Hashed(map)
+    }
+}
+
+impl<V: Serialize> Serialize for KeyMap<V> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer
+    {
+        match self {
+            KeyMap::Hashed(map) => map.serialize(serializer),
+            KeyMap::Ordered(map) => map.serialize(serializer),
+        }
+    }
+}
+
+/// Iterator over the keys and values of a `KeyMap`
+pub(crate) enum Iter<'a, V> {
+    Hashed(hash_map::Iter<'a, String, V>),
+    Ordered(btree_map::Iter<'a, String, V>),
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (&'a String, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Hashed(iter) => iter.next(),
+            Iter::Ordered(iter) => iter.next(),
+        }
+    }
+}
+
+fn in_bounds(key: &str, start: Bound<&str>, end: Bound<&str>) -> bool {
+    let after_start = match start {
+        Bound::Included(start) => key >= start,
+        Bound::Excluded(start) => key > start,
+        Bound::Unbounded => true,
+    };
+    let before_end = match end {
+        Bound::Included(end) => key <= end,
+        Bound::Excluded(end) => key < end,
+        Bound::Unbounded => true,
+    };
+    after_start && before_end
+}
+
+fn is_valid_range(start: Bound<&str>, end: Bound<&str>) -> bool {
+    match (start, end) {
+        (Bound::Excluded(start), Bound::Excluded(end)) => start < end,
+        (Bound::Included(start), Bound::Included(end) | Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end)) => start <= end,
+        _ => true,
+    }
+}
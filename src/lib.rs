@@ -7,7 +7,7 @@
 //! periodically according to a policy defined by the user. There are APIs to create a new key-value store in memory or to load it from a file.
 //! Everything runs in the user's process and thread and in its memory, which means that the key-value data will be stored in the user 
 //! process's memory and each API call will access that key-value store directly and may trigger a dump to the DB file. There are no additional 
-//! threads or processes created throughout the life-cycle of any of the APIs.
+//! threads or processes created throughout the life-cycle of any of the APIs, unless a background thread for dumping the DB is started explicitly.
 //! 
 //! ## So what is it useful for? 
 //! 
@@ -28,7 +28,16 @@
 //! * Manage lists. Every list has a name (which is its key in the key-value store) and a list of items it stores. PickleDB provides APIs to 
 //!   create and delete lists and to add or remove items from them. Lists are also heterogeneous, meaning each list can store objects of different 
 //!   types. Please see more details below
-//! * Iterate over keys and values in the DB and over items in a list
+//! * Manage dicts. Like in [Python's PickleDB](https://pythonhosted.org/pickleDB/), every dict has a name and holds key-value
+//!   pairs whose values can be of different types. PickleDB provides APIs to create and delete dicts, to add, get and remove
+//!   key-value pairs and to merge dicts
+//! * Manage sets. Every set has a name and holds unique members of different types. PickleDB provides APIs to add, remove
+//!   and check members, and to get the union, intersection and difference of sets
+//! * Manage sorted sets, like in Redis. Every member of a sorted set has a score and members can be queried by their rank
+//!   or by their score, which is useful for leaderboards and priority queues
+//! * Iterate over keys and values in the DB, over items in a list, over key-value pairs in a dict and over members of a set
+//! * Increment and decrement counters, like Redis' `INCR` and `DECR`
+//! * Subscribe to the changes of keys, with a callback or a channel
 //! 
 //! Please take a look at the API documentation to get more details.
 //! 
@@ -57,9 +66,20 @@
 //! their performance cost but high performance is not one of PickleDB's main objectives and I think it's a fair price to pay for achieving 
 //! heterogeneous data structures.
 //! 
-//! In order to achieve this magic, all objects must be serializable. PickleDB uses the [Serde](https://serde.rs/) library for serialization and 
-//! it currently supports only [JSON serialization](https://docs.serde.rs/serde_json/). In the future I intend to add more serialization options
-//! such as [bincode](https://crates.io/crates/bincode) or [pickle](https://crates.io/crates/serde-pickle).
+//! In order to achieve this magic, all objects must be serializable. PickleDB uses the [Serde](https://serde.rs/) library for serialization and
+//! currently supports the following serialization methods:
+//! * [JSON serialization](https://crates.io/crates/serde_json)
+//! * [Bincode serialization](https://crates.io/crates/bincode)
+//! * [YAML serialization](https://crates.io/crates/serde_yaml)
+//! * [CBOR serialization](https://crates.io/crates/serde_cbor)
+//! * [MessagePack serialization](https://crates.io/crates/rmp-serde)
+//!
+//! The serialization method is chosen by the user when creating a new DB or loading one from a file (please see
+//! [SerializationMethod](enum.SerializationMethod.html)) and it is used both for the values and list items and for the DB file itself.
+//! JSON and YAML produce human-readable files, while the binary formats produce smaller files and are faster to serialize.
+//! A DB file can only be loaded with the same serialization method it was dumped with.
+//! JSON DB files can also be written with sorted keys, one key per line and values as nested JSON, which makes them easy
+//! to read and to diff. Please see [with_readable_dump()](struct.PickleDb.html#method.with_readable_dump) for more details.
 //! 
 //! So what does it mean that all objects must be serializable? That means that all map values and list items that you use must be serializable.
 //! Fortunately Serde already provides out-of-the-box serialization for most of the common objects: all primitive types, strings, vectors and tuples
@@ -89,22 +109,171 @@
 //! * [PickleDbDumpPolicy::PeriodicDump(Duration)](enum.PickleDbDumpPolicy.html#variant.PeriodicDump) - changes will be dumped to the file periodically, 
 //!   no sooner than the Duration provided by the user. The way this mechanism works is as follows: each time there is a DB change the last DB dump time 
 //!   is checked. If the time that has passed since the last dump is higher than Duration, changes will be dumped, otherwise changes will not be dumped.  
+//! * [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) - every change will be appended immediately
+//!   and automatically to a log file next to the DB file, instead of rewriting the whole DB file. Please see more details below
 //! 
 //! Apart from this dump policy, persistency is also kept by a implementing the `Drop` trait for the `PickleDB` object which ensures all in-memory data 
 //! is dumped to the file upon destruction of the object.
 //! 
-use std::io::Error;
-use std::collections::HashMap;
+//! Every dump writes the DB to a temp file that replaces the DB file once it's written, so a crash of the process never
+//! leaves a partially written DB file behind. In order to survive a power loss as well, the dumps can be synced to the disk
+//! by calling [with_durable_dumps()](struct.PickleDb.html#method.with_durable_dumps).
+//! 
+//! Previous versions of the DB file can be kept as rotating backups by calling [with_backups()](struct.PickleDb.html#method.with_backups),
+//! either upon every dump or once in a while, and restored by calling [restore_backup()](struct.PickleDb.html#method.restore_backup).
+//! 
+//! Copying the DB file while the DB may be dumping isn't safe. Instead, [save_as()](struct.PickleDb.html#method.save_as)
+//! writes a copy of the DB to another file, and [snapshot()](struct.PickleDb.html#method.snapshot) takes a read-only
+//! copy of the data that later changes don't affect, which can be read by another thread and written to a file by
+//! [PickleDbSnapshot::write_to()](struct.PickleDbSnapshot.html#method.write_to).
+//! 
+//! PickleDB keeps track of whether the DB has changes that weren't dumped yet (please see [is_dirty()](struct.PickleDb.html#method.is_dirty)).
+//! A DB without such changes isn't written to the file again, neither by [dump()](struct.PickleDb.html#method.dump) nor upon destruction,
+//! so loading a DB just for reading it never rewrites its file.
+//! 
+//! ## Ordered keys
+//! 
+//! Keys are stored in hash maps by default, so they're returned and iterated in an arbitrary order. A DB can keep
+//! its keys ordered instead by calling [with_ordered_keys()](struct.PickleDb.html#method.with_ordered_keys) after creating or
+//! loading it. Keys can be queried by range or by prefix with [range()](struct.PickleDb.html#method.range) and
+//! [scan_prefix()](struct.PickleDb.html#method.scan_prefix), which is handy for namespaced keys like `session:<id>`.
+//! These queries work with any DB, but a DB with ordered keys answers them without going over all of its keys.
+//! 
+//! ## Compatibility with Python's PickleDB
+//! 
+//! By default the DB file holds the values serialized with the DB's serialization method, so it can't be read by
+//! [Python's PickleDB](https://pythonhosted.org/pickleDB/), which stores the whole DB as a single JSON object.
+//! A DB created with [new_python_compat()](struct.PickleDb.html#method.new_python_compat) or loaded with
+//! [load_python_compat()](struct.PickleDb.html#method.load_python_compat) reads and writes the file in Python's layout,
+//! where lists are JSON arrays and dicts are JSON objects, so Python and Rust code can share the same DB file.
+//! Python's PickleDB has no sets, so sets and sorted sets are written as JSON objects with a single `$set` or
+//! `$sorted_set` key, which Python sees as dicts and which are loaded back as sets and sorted sets.
+//! 
+//! ## Append-only log
+//! 
+//! With [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump) every change rewrites the whole DB file, which
+//! becomes expensive as the DB gets bigger. [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly)
+//! provides the same durability by appending only the keys that changed to a log file whose path is the DB file path with a `.log` suffix.
+//! When the DB is loaded the log is replayed on top of the DB file, whatever dump policy it's loaded with. The log grows with every
+//! change, so it should be compacted from time to time by calling [compact()](struct.PickleDb.html#method.compact), which writes
+//! the whole DB to the DB file and truncates the log.
+//! 
+//! ## Key expiry
+//! 
+//! Values and lists can be given a time to live (TTL), which makes PickleDB useful as a local cache: once the TTL passes the key
+//! expires and it's no longer visible to any API, as if it was removed. Please see [set_with_ttl()](struct.PickleDb.html#method.set_with_ttl),
+//! [expire()](struct.PickleDb.html#method.expire), [ttl()](struct.PickleDb.html#method.ttl) and [persist()](struct.PickleDb.html#method.persist).
+//! Expired keys are purged from memory when the DB is dumped, and the expiry time of the other keys is stored in the DB file so it
+//! survives loading the DB again.
+//! 
+//! ## Transactions
+//! 
+//! Every change to the DB may trigger a dump according to the dump policy, so an update that is made of several changes may be
+//! dumped when only some of them were applied. In order to avoid that the changes can be grouped in a transaction, either by calling
+//! [transaction()](struct.PickleDb.html#method.transaction) with a closure or by calling [begin()](struct.PickleDb.html#method.begin),
+//! [commit()](struct.PickleDb.html#method.commit) and [rollback()](struct.PickleDb.html#method.rollback). Changes made in a transaction
+//! aren't dumped until it's committed, and then they're dumped once according to the dump policy. If the transaction is rolled back,
+//! or if the closure returns an error or panics, the DB returns to the state it was in when the transaction started.
+//! 
+//! ## Sharing a DB between threads
+//! 
+//! `PickleDb` needs a mutable reference for every change, so it can't be used by several threads as is.
+//! [SharedPickleDb](struct.SharedPickleDb.html) is a cloneable handle that can be sent to other threads. Reading from the DB
+//! through it can be done by several threads concurrently, while changes are made by one thread at a time.
+//! A shared DB can also be dumped periodically by a background thread, please see
+//! [SharedPickleDb::start_flusher()](struct.SharedPickleDb.html#method.start_flusher).
+//! 
+//! ## Sharing a DB file between processes
+//! 
+//! Every DB keeps its own copy of the data in memory, so several DBs that change the same DB file, whether in the same
+//! process or in different processes, overwrite each other's changes when they're dumped. A DB created with
+//! [new_locked()](struct.PickleDb.html#method.new_locked) or loaded with [load_locked()](struct.PickleDb.html#method.load_locked)
+//! holds an advisory lock of its file until it's dropped: an exclusive lock if it may write the file and a shared lock if
+//! it's read-only. A read-only DB can also take a shared lock with [load_read_only_locked()](struct.PickleDb.html#method.load_read_only_locked).
+//! A DB that can't take the lock fails with [PickleDbError::Locked](enum.PickleDbError.html#variant.Locked),
+//! either right away or after waiting for it, please see [PickleDbLockPolicy](enum.PickleDbLockPolicy.html).
+//! DBs created by [new()](struct.PickleDb.html#method.new) or loaded by [load()](struct.PickleDb.html#method.load) and
+//! [load_read_only()](struct.PickleDb.html#method.load_read_only) don't lock their file, so DBs that share a file keep
+//! working as they did before locking was added. The lock is advisory, so it only protects the file from DBs that lock it too.
+//! 
+//! A DB keeps the data it loaded in memory, so it doesn't see the changes that other DBs dump to the same file later on.
+//! Such a DB, usually a read-only one, can follow them by calling [reload()](struct.PickleDb.html#method.reload) or
+//! [reload_if_changed()](struct.PickleDb.html#method.reload_if_changed), or by reloading a [SharedPickleDb](struct.SharedPickleDb.html)
+//! in a background thread whenever its file changes, please see [SharedPickleDb::start_watcher()](struct.SharedPickleDb.html#method.start_watcher).
+//! 
+//! ## Async API
+//! 
+//! All file I/O of `PickleDb` is blocking, which doesn't play well with async executors. With the `async` feature
+//! PickleDB provides [AsyncPickleDb](struct.AsyncPickleDb.html) for [tokio](https://tokio.rs/) users: its APIs that may
+//! read or write the DB file are `async` and run the file I/O on tokio's blocking thread pool. In order to use it
+//! please enable the feature in your `Cargo.toml` file: `pickledb = { version = "0.2", features = ["async"] }`
+//! 
+//! ## Encryption
+//! 
+//! The DB file holds the data as is, so anyone who can read the file can read the data. With the `encryption` feature
+//! a DB can be encrypted by calling [with_encryption()](struct.PickleDb.html#method.with_encryption) with a key or a
+//! passphrase, and then its file and its append-only log are encrypted and authenticated with ChaCha20-Poly1305.
+//! An encrypted DB is loaded by [load_encrypted()](struct.PickleDb.html#method.load_encrypted), which returns
+//! [PickleDbError::Decryption](enum.PickleDbError.html#variant.Decryption) if the key is wrong or the file was tampered with.
+//! In order to use it please enable the feature in your `Cargo.toml` file: `pickledb = { version = "0.2", features = ["encryption"] }`
+//! 
+//! ## Error handling
+//! 
+//! APIs that may fail return a `Result` with a [PickleDbError](enum.PickleDbError.html) instead of panicking. That includes I/O and
+//! serialization errors while dumping the DB, a corrupted DB file while loading it and operations on lists that don't exist.
+//! Please note that under [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump) and
+//! [PickleDbDumpPolicy::PeriodicDump(Duration)](enum.PickleDbDumpPolicy.html#variant.PeriodicDump) a DB change may trigger a dump,
+//! so a dump error is returned by the API that made the change. In that case the change is still kept in memory.
+//! 
+use std::collections::{HashMap, HashSet};
+use std::ops::{Bound, Range, RangeBounds};
+use std::sync::mpsc::{self, Receiver};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::fs;
+use std::io;
 use serde::{de::DeserializeOwned, Serialize};
+use serde_derive::Deserialize;
 
-use crate::serialization::{serialize_data, deserialize_data};
+use crate::append_log::LogRecord;
+use crate::encryption::Cipher;
+use crate::file_signature::FileSignature;
+use crate::events::Subscribers;
+use crate::json_format::ReadableDump;
+use crate::keymap::KeyMap;
+use crate::serialization::SerializedValue;
+use crate::sorted_set::SortedSet;
 
-pub use self::iterators::{PickleDbIterator, PickleDbIteratorItem, PickleDbListIterator, PickleDbListIteratorItem};
+#[cfg(feature = "async")]
+pub use self::async_db::AsyncPickleDb;
+pub use self::backup::PickleDbBackup;
+#[cfg(feature = "encryption")]
+pub use self::encryption::EncryptionKey;
+pub use self::error::PickleDbError;
+pub use self::events::{PickleDbEvent, PickleDbSubscription};
+pub use self::iterators::{PickleDbDictIterator, PickleDbDictIteratorItem, PickleDbIterator, PickleDbIteratorItem,
+                          PickleDbListIterator, PickleDbListIteratorItem, PickleDbSetIterator, PickleDbSetIteratorItem};
+pub use self::serialization::SerializationMethod;
+pub use self::shared::{PickleDbFlusher, PickleDbWatcher, SharedPickleDb};
+pub use self::snapshot::PickleDbSnapshot;
 
+mod append_log;
+#[cfg(feature = "async")]
+mod async_db;
+mod backup;
+mod db_file;
+mod encryption;
+mod error;
+mod events;
+mod file_lock;
+mod file_signature;
 mod iterators;
+mod json_format;
+mod keymap;
+mod python_compat;
 mod serialization;
+mod shared;
+mod snapshot;
+mod sorted_set;
 
 /// An enum that determines the policy of dumping PickleDB changes into the file 
 pub enum PickleDbDumpPolicy {
@@ -119,6 +288,42 @@ pub enum PickleDbDumpPolicy {
     /// If the time that has passed since the last dump is higher than Duration, changes will be dumped, 
     /// otherwise changes will not be dumped
     PeriodicDump(Duration),
+    /// Every change will be appended immediately and automatically to a log file next to the DB file, rather than
+    /// rewriting the whole DB file. The log is replayed when the DB is loaded and is truncated when the DB is dumped
+    /// or compacted (please see [PickleDB::compact()](struct.PickleDb.html#method.compact))
+    AppendOnly,
+}
+
+/// An enum that determines which versions of the DB file are kept as backups when it's dumped.
+/// Please see [PickleDb::with_backups()](struct.PickleDb.html#method.with_backups)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickleDbBackupPolicy {
+    /// Back up the DB file upon every dump and keep the number of most recent backups provided by the user
+    EveryDump(usize),
+    /// Back up the DB file no sooner than the Duration provided by the user since the last backup, for example
+    /// hourly or daily, and keep the number of most recent backups provided by the user
+    Periodic(Duration, usize),
+}
+
+/// An enum that determines what happens when the DB file is locked by another DB.
+/// Please see [PickleDb::load_locked()](struct.PickleDb.html#method.load_locked)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickleDbLockPolicy {
+    /// Fail right away with [PickleDbError::Locked](enum.PickleDbError.html#variant.Locked)
+    NoWait,
+    /// Wait for the lock to be released, and fail with [PickleDbError::Locked](enum.PickleDbError.html#variant.Locked)
+    /// if it's still held after the Duration provided by the user
+    Wait(Duration),
+}
+
+/// An enum for specifying an end of a list, used for moving items between lists.
+/// Please see [PickleDb::lmove()](struct.PickleDb.html#method.lmove)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListEnd {
+    /// The first item of the list
+    Front,
+    /// The last item of the list
+    Back,
 }
 
 /// A struct for extending PickleDB lists and adding more items to them
@@ -135,7 +340,7 @@ impl<'a> PickleDbListExtender<'a> {
     /// That includes all primitive types, vectors, tuples and every struct that has the 
     /// `#[derive(Serialize, Deserialize)` attribute.
     /// The method returns another `PickleDbListExtender` object that enables to continue adding
-    /// items to the list.
+    /// items to the list, or an error if the item couldn't be added (please see [PickleDb::ladd()](struct.PickleDb.html#method.ladd)).
     /// 
     /// # Arguments
     /// 
@@ -145,19 +350,19 @@ impl<'a> PickleDbListExtender<'a> {
     /// 
     /// ```rust,ignore
     /// // create a new list
-    /// db.lcreate("list1")
+    /// db.lcreate("list1")?
     /// 
     /// // add items of different types to the list
-    ///   .ladd(&100)
-    ///   .ladd(&String::from("my string"))
-    ///   .ladd(&vec!["aa", "bb", "cc"]);
+    ///   .ladd(&100)?
+    ///   .ladd(&String::from("my string"))?
+    ///   .ladd(&vec!["aa", "bb", "cc"])?;
     /// ```
     /// 
-    pub fn ladd<V>(&mut self, value: &V) -> PickleDbListExtender
+    pub fn ladd<V>(&mut self, value: &V) -> Result<PickleDbListExtender<'_>, PickleDbError>
         where
             V: Serialize
     {
-        self.db.ladd(&self.list_name, value).unwrap()
+        self.db.ladd(&self.list_name, value)
     }
 
     /// Add multiple items to an existing list.
@@ -170,7 +375,7 @@ impl<'a> PickleDbListExtender<'a> {
     /// of them are of the same type. Of course it doesn't mean that the list cannot contain items
     /// of other types as well, as you can see in the example below.
     /// The method returns another `PickleDbListExtender` object that enables to continue adding 
-    /// items to the list.
+    /// items to the list, or an error if the items couldn't be added (please see [PickleDb::lextend()](struct.PickleDb.html#method.lextend)).
     /// 
     /// # Arguments
     /// 
@@ -180,443 +385,1971 @@ impl<'a> PickleDbListExtender<'a> {
     /// 
     /// ```rust,ignore
     /// // create a new list
-    /// db.lcreate("list1");
+    /// db.lcreate("list1")?;
     /// 
     /// // add a bunch of numbers to the list
-    /// db.lextends("list1", &vec![100, 200, 300])
+    /// db.lextend("list1", &vec![100, 200, 300])?
     /// 
     /// // add a bunch of strings to the list
-    ///   .lextends(&vec!["aa", "bb", "cc"]);
+    ///   .lextend(&vec!["aa", "bb", "cc"])?;
     /// 
     /// // now the list contains 6 items and looks like this: [100, 200, 300, "aa, "bb", "cc"]
     /// ```
     /// 
-    pub fn lextend<V>(&mut self, seq: &Vec<V>) -> PickleDbListExtender
+    pub fn lextend<V>(&mut self, seq: &[V]) -> Result<PickleDbListExtender<'_>, PickleDbError>
         where
             V: Serialize
     {
-        self.db.lextend(&self.list_name, seq).unwrap()
+        self.db.lextend(&self.list_name, seq)
+    }
+}
+
+/// The DB data as it's stored in the DB file. Fields that were added after the first version
+/// have a default value so older DB files can still be loaded
+#[derive(Deserialize)]
+struct DbFileContents(
+    HashMap<String, SerializedValue>,
+    HashMap<String, Vec<SerializedValue>>,
+    #[serde(default)] HashMap<String, u64>,
+    #[serde(default)] HashMap<String, HashMap<String, SerializedValue>>,
+    #[serde(default)] HashMap<String, HashSet<SerializedValue>>,
+    #[serde(default)] HashMap<String, SortedSet>,
+);
+
+/// A set name with its set, or `None` if the set doesn't exist
+type NamedSet<'a> = (&'a str, Option<&'a HashSet<SerializedValue>>);
+
+/// The DB data as it was when a transaction started, used for rolling the transaction back
+struct DbSnapshot {
+    map: KeyMap<SerializedValue>,
+    list_map: KeyMap<Vec<SerializedValue>>,
+    dict_map: KeyMap<HashMap<String, SerializedValue>>,
+    set_map: KeyMap<HashSet<SerializedValue>>,
+    sorted_set_map: KeyMap<SortedSet>,
+    expiry: HashMap<String, u64>,
+    dirty: bool,
+    changed_keys: HashSet<String>,
+    /// The events of the changes made in the transaction, which are sent to the subscribers when it's committed
+    events: Vec<PickleDbEvent>,
+}
+
+/// Rolls back the transaction it guards unless it was committed, also when the transaction closure panics
+struct TransactionGuard<'a>(&'a mut PickleDb);
+
+impl Drop for TransactionGuard<'_> {
+    fn drop(&mut self) {
+        // after a successful commit there's nothing to roll back
+        let _ = self.0.rollback();
     }
 }
 
 /// A struct that represents a PickleDB object
 pub struct PickleDb {
-    map: HashMap<String, String>, 
-    list_map: HashMap<String, Vec<String>>,
+    map: KeyMap<SerializedValue>,
+    list_map: KeyMap<Vec<SerializedValue>>,
+    dict_map: KeyMap<HashMap<String, SerializedValue>>,
+    set_map: KeyMap<HashSet<SerializedValue>>,
+    sorted_set_map: KeyMap<SortedSet>,
+    /// The expiry time of keys with a TTL, in milliseconds since the Unix epoch
+    expiry: HashMap<String, u64>,
+    serialization_method: SerializationMethod,
     db_file_path: String,
     dump_policy: PickleDbDumpPolicy,
-    last_dump: Instant
+    last_dump: Instant,
+    tx_snapshot: Option<DbSnapshot>,
+    /// Keys that were changed but weren't appended to the log yet, used with PickleDbDumpPolicy::AppendOnly
+    changed_keys: HashSet<String>,
+    /// Whether the log file belongs to the DB file, meaning records can be appended to it
+    log_started: bool,
+    /// What the next record that is appended to the log of an encrypted DB is chained to: the tag of the last record
+    /// in the log, or the nonce of the DB file if the log is empty
+    log_chain: Vec<u8>,
+    /// Whether there are changes that weren't dumped to the file yet
+    dirty: bool,
+    /// Whether the DB file is written in the layout of Python's pickleDB
+    python_compat: bool,
+    /// Whether dumps are synced to the disk
+    durable_dumps: bool,
+    /// Which versions of the DB file are kept as backups when it's dumped
+    backup_policy: Option<PickleDbBackupPolicy>,
+    /// How the DB file is written if it should be readable, used with SerializationMethod::Json
+    readable_dump: Option<ReadableDump>,
+    /// Encrypts the DB file and its log if the DB is encrypted
+    cipher: Option<Cipher>,
+    /// Holds the lock of the DB file until the DB is dropped, if the DB was created or loaded with a lock
+    lock_file: Option<fs::File>,
+    /// The state of the DB file when it was last loaded or reloaded, used for telling whether another DB changed it
+    file_signature: Option<FileSignature>,
+    subscribers: Subscribers,
 }
 
 impl PickleDb {
 
     /// Constructs a new `PickleDB` instance.
     /// 
+    /// The DB file isn't locked, so another DB that writes the same file overwrites the changes of this DB.
+    /// Please see [PickleDB::new_locked()](#method.new_locked) for a DB that locks its file.
+    /// 
     /// # Arguments
     /// 
     /// * `location` - a path where the DB will be stored
     /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. Please see
-    ///    [PickleDB::load()](#method.load) to understand the different policy options
+    ///   [PickleDB::load()](#method.load) to understand the different policy options
+    /// * `serialization_method` - the serialization method to use for storing the data in memory and in the file.
+    ///   Please see [SerializationMethod](enum.SerializationMethod.html) for the available options
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// use pickledb::PickleDb;
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
     /// 
-    /// let mut db = PickleDB::new("example.db", false);
+    /// let mut db = PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
     /// ```
-    pub fn new(location: &str, dump_policy: PickleDbDumpPolicy) -> PickleDb {
+    pub fn new(location: &str, dump_policy: PickleDbDumpPolicy, serialization_method: SerializationMethod) -> PickleDb {
         PickleDb { 
-            map: HashMap::new(), 
-            list_map: HashMap::new(), 
+            map: KeyMap::new(), 
+            list_map: KeyMap::new(), 
+            dict_map: KeyMap::new(),
+            set_map: KeyMap::new(),
+            sorted_set_map: KeyMap::new(),
+            expiry: HashMap::new(),
+            serialization_method,
             db_file_path: String::from(location), 
-            dump_policy: dump_policy,
-            last_dump: Instant::now() }
+            dump_policy,
+            last_dump: Instant::now(),
+            tx_snapshot: None,
+            changed_keys: HashSet::new(),
+            log_started: false,
+            log_chain: Vec::new(),
+            dirty: true,
+            python_compat: false,
+            durable_dumps: false,
+            backup_policy: None,
+            readable_dump: None,
+            cipher: None,
+            lock_file: None,
+            file_signature: None,
+            subscribers: Subscribers::default(),
+        }
     }
 
-    /// Load a DB from a file.
+    /// Constructs a new `PickleDB` instance whose file is compatible with [Python's PickleDB](https://pythonhosted.org/pickleDB/).
     /// 
-    /// This method tries to load a DB from a file. Upon success an instance of `PickleDB` is returned, 
-    /// otherwise an error is returned.
+    /// This method is similar to [PickleDB::new()](#method.new) but the DB is always serialized with
+    /// [SerializationMethod::Json](enum.SerializationMethod.html#variant.Json) and the DB file is written in the
+    /// layout of Python's PickleDB. Please see [PickleDB::load_python_compat()](#method.load_python_compat) for more details.
     /// 
     /// # Arguments
     /// 
-    /// * `location` - a path where the DB is loaded from
-    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. 
-    ///   The user can choose between the following options:
-    ///   * [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump) - never dump any change,
-    ///     file will always remain read-only. When choosing this policy even calling to [dump()](#method.dump) won't dump the data.
-    ///     Choosing this option is the same like calling [PickleDB::load_read_only()](#method.load_read_only)
-    ///   * [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump) - every change will be dumped
-    ///     immediately and automatically to the file
-    ///   * [PickleDbDumpPolicy::DumpUponRequest](enum.PickleDbDumpPolicy.html#variant.DumpUponRequest) - data won't be dumped
-    ///     unless the user calls [dump()](#method.dump) proactively to dump the data
-    ///   * [PickleDbDumpPolicy::PeriodicDump(Duration)](enum.PickleDbDumpPolicy.html#variant.PeriodicDump) - changes will be
-    ///     dumped to the file periodically, no sooner than the Duration provided by the user. The way this mechanism works is
-    ///     as follows: each time there is a DB change the last DB dump time is checked. If the time that has passed
-    ///     since the last dump is higher than Duration, changes will be dumped, otherwise changes will not be dumped.    
+    /// * `location` - a path where the DB will be stored
+    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. Please see
+    ///   [PickleDB::load()](#method.load) to understand the different policy options
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// use pickledb::PickleDb;
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy};
     /// 
-    /// let db = PickleDB::load("example.db", PickleDbDumpPolicy::AutoDump);
+    /// let mut db = PickleDb::new_python_compat("example.db", PickleDbDumpPolicy::AutoDump);
     /// ```
-    pub fn load(location: &str, dump_policy: PickleDbDumpPolicy) -> Result<PickleDb, Error> {
-        let contents = fs::read_to_string(location)?;
-        let map_from_file: (_,_) = deserialize_data(&contents).unwrap();
-        Ok(PickleDb { 
-            map: map_from_file.0, 
-            list_map: map_from_file.1, 
-            db_file_path: String::from(location), 
-            dump_policy: dump_policy,
-            last_dump: Instant::now()
-            })
+    pub fn new_python_compat(location: &str, dump_policy: PickleDbDumpPolicy) -> PickleDb {
+        let mut db = PickleDb::new(location, dump_policy, SerializationMethod::Json);
+        db.python_compat = true;
+        db
     }
 
-    /// Load a DB from a file in read-only mode.
-    ///
-    /// This method is similar to the [PickleDB::load()](#method.load) method with the only difference
-    /// that the file is loaded from DB with a dump policy of 
-    /// [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump), meaning
-    /// changes will not be saved to the file, even when calling [dump()](#method.dump)
+    /// Constructs a new `PickleDB` instance that locks its file.
+    /// 
+    /// This method is similar to [PickleDB::new()](#method.new) but it takes an advisory lock of the DB file,
+    /// which is held until the DB is dropped. Please see [PickleDB::load_locked()](#method.load_locked) for more details.
     /// 
     /// # Arguments
     /// 
-    /// * `location` - a path where the DB is loaded from
+    /// * `location` - a path where the DB will be stored
+    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. Please see
+    ///   [PickleDB::load()](#method.load) to understand the different policy options
+    /// * `serialization_method` - the serialization method to use for storing the data in memory and in the file
+    /// * `lock_policy` - what to do if the DB file is locked by another DB. Please see
+    ///   [PickleDbLockPolicy](enum.PickleDbLockPolicy.html)
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// use pickledb::PickleDb;
-    /// 
-    /// let readonly_db = PickleDB::load("example.db");
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbLockPolicy, SerializationMethod};
     /// 
-    /// // nothing happens by calling this method
-    /// readonly_db.dump();
+    /// let mut db = PickleDb::new_locked("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json, PickleDbLockPolicy::NoWait)?;
     /// ```
-    /// 
-    pub fn load_read_only(location: &str) -> Result<PickleDb, Error> {
-        PickleDb::load(location, PickleDbDumpPolicy::NeverDump)
+    pub fn new_locked(
+        location: &str,
+        dump_policy: PickleDbDumpPolicy,
+        serialization_method: SerializationMethod,
+        lock_policy: PickleDbLockPolicy,
+    ) -> Result<PickleDb, PickleDbError> {
+        let lock_file = file_lock::lock(location, !matches!(dump_policy, PickleDbDumpPolicy::NeverDump), lock_policy)?;
+        let mut db = PickleDb::new(location, dump_policy, serialization_method);
+        db.lock_file = Some(lock_file);
+        Ok(db)
     }
 
-    /// Dump the data to the file.
+    /// Keep the keys of the DB ordered.
     /// 
-    /// Calling this method is necessary only if the DB is loaded or created with `auto_dump = true`.
-    /// Otherwise the data is dumped to the file upon every change. This method returns `true` if
-    /// dump is successful, false otherwise.
+    /// By default keys are stored in hash maps, so [get_all()](#method.get_all) and [iter()](#method.iter) return them
+    /// in an arbitrary order. This method moves the keys of values, lists, dicts, sets and sorted sets to ordered maps,
+    /// so `get_all()` returns the keys sorted, `iter()` iterates over them in order and [range()](#method.range) and
+    /// [scan_prefix()](#method.scan_prefix) find keys without going over all the keys in the DB. In exchange, getting
+    /// and setting a key takes logarithmic time rather than constant time. The order of the keys isn't stored
+    /// in the DB file, so this method should be called again every time the DB is loaded.
     /// 
-    pub fn dump(&mut self) -> bool {
-        if let PickleDbDumpPolicy::NeverDump = self.dump_policy {
-            return true
-        }
-
-        match serialize_data(&(&self.map, &self.list_map)) {
-            Ok(db_as_json) => {
-                let temp_file_path = format!("{}.temp.{}", 
-                    self.db_file_path, 
-                    SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs());
-                fs::write(&temp_file_path, &db_as_json).expect("Unable to write to temp file");
-                fs::rename(temp_file_path, &self.db_file_path).expect("Unable to rename file");
-                if let PickleDbDumpPolicy::PeriodicDump(_dur) = self.dump_policy {
-                    self.last_dump = Instant::now();
-                }
-                true
-            }
-            Err(_) => false,
-        }
-    }
-
-    fn dumpdb(&mut self) {
-        match self.dump_policy {
-            PickleDbDumpPolicy::AutoDump => {
-                self.dump();
-            },
-            PickleDbDumpPolicy::PeriodicDump(duration) => {
-                let now = Instant::now();
-                if now.duration_since(self.last_dump) > duration {
-                    self.last_dump = Instant::now();
-                    self.dump();
-                }
-            },
-
-            _ => (),
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
+    /// 
+    /// let mut db = PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_ordered_keys();
+    /// 
+    /// let db = PickleDb::load("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)?.with_ordered_keys();
+    /// ```
+    pub fn with_ordered_keys(mut self) -> PickleDb {
+        self.map.make_ordered();
+        self.list_map.make_ordered();
+        self.dict_map.make_ordered();
+        self.set_map.make_ordered();
+        self.sorted_set_map.make_ordered();
+        if let Some(snapshot) = &mut self.tx_snapshot {
+            snapshot.map.make_ordered();
+            snapshot.list_map.make_ordered();
+            snapshot.dict_map.make_ordered();
+            snapshot.set_map.make_ordered();
+            snapshot.sorted_set_map.make_ordered();
         }
+        self
     }
 
-    /// Set a key-value pair.
-    /// 
-    /// The key has to be a string but the value can be of any type that is serializable.
-    /// That includes all primitive types, vectors, tuples and every struct that has the 
-    /// `#[derive(Serialize, Deserialize)` attribute.
+    /// Dump the DB to a readable and diffable JSON file.
+    /// 
+    /// By default a DB serialized with [SerializationMethod::Json](enum.SerializationMethod.html#variant.Json) is written
+    /// to the file on a single line, its keys are written in an arbitrary order and its values are written as JSON strings.
+    /// After calling this method the keys are written sorted, every key is written on its own line and values are written
+    /// as nested JSON, so dumping the same data always produces the same file and a change to a key changes only its line.
+    /// Set members are written sorted and sorted set members are written as member and score pairs, ordered by score.
+    /// That's useful for DB files that are checked into version control. The file is loaded by [PickleDB::load()](#method.load)
+    /// like any other JSON DB file, but this method should be called again every time the DB is loaded so it's dumped
+    /// the same way. A DB that is compatible with Python's PickleDB (please see [PickleDB::load_python_compat()](#method.load_python_compat))
+    /// keeps its layout, but its keys are written on their own lines as well. Numbers that don't fit in 64 bits, like
+    /// `i128` values, can't be written as nested JSON, so dumping a DB that holds them returns
+    /// [PickleDbError::Serialization](enum.PickleDbError.html#variant.Serialization) rather than changing them.
+    /// This method has no effect on DBs serialized with other serialization methods.
     /// 
     /// # Arguments
     /// 
-    /// * `key` - a string key
-    /// * `value` - a value of any serializable type
+    /// * `pretty_values` - whether values should be pretty-printed across several lines too, rather than written
+    ///   on the same line as their key
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // set a number
-    /// db.set("key1", &100);
-    /// 
-    /// // set a floating point number
-    /// db.set("key2", &1.234);
-    /// 
-    /// // set a String
-    /// db.set("key3", &String::from("hello world"));
-    /// 
-    /// // set a Vec
-    /// db.set("key4", &vec![1,2,3]);
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
+    /// use serde::{Deserialize, Serialize};
     /// 
-    /// // set a struct
     /// #[derive(Serialize, Deserialize)]
-    /// struct Coor {
+    /// struct Point {
     ///     x: i32,
     ///     y: i32,
     /// }
-    /// let mycoor = Coor { x: 1, y : 2 };
-    /// db.set("key5", &mycoor);
-    /// ```
     /// 
-    pub fn set<V>(&mut self, key: &str, value: &V)
-        where
-            V: Serialize
-    {
-        if self.list_map.contains_key(key) {
-            self.list_map.remove(key);
-        }
-        self.map.insert(String::from(key), serialize_data(value).unwrap());
-        self.dumpdb();
+    /// let mut db = PickleDb::load("config.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)?.with_readable_dump(false);
+    /// db.set("key1", &100)?;
+    /// db.set("key2", &Point { x: 1, y: 2 })?;
+    /// db.sadd("set1", &2)?;
+    /// db.sadd("set1", &1)?;
+    /// db.zadd("zset1", 1.5, &String::from("a"))?;
+    /// 
+    /// // config.db now contains:
+    /// // {
+    /// //   "values": {
+    /// //     "key1": 100,
+    /// //     "key2": {"x":1,"y":2}
+    /// //   },
+    /// //   "lists": {},
+    /// //   "dicts": {},
+    /// //   "sets": {
+    /// //     "set1": [1,2]
+    /// //   },
+    /// //   "sorted_sets": {
+    /// //     "zset1": [["a",1.5]]
+    /// //   },
+    /// //   "expiry": {}
+    /// // }
+    /// ```
+    pub fn with_readable_dump(mut self, pretty_values: bool) -> PickleDb {
+        self.readable_dump = Some(ReadableDump { pretty_values });
+        self
     }
 
-    /// Get a value of a key.
-    /// 
-    /// The key is always a string but the value can be of any type. It's the user's
-    /// responsibility to know the value type and give it while calling this method.
-    /// If the key doesn't exist or if the type is wrong, `None` will be returned.
-    /// Otherwise `Some(V)` will be returned.
-    /// Since the values are stored in a serialized way the returned object is
-    /// not a reference to the value stored in a DB but actually a new instance of it
-    /// 
-    /// # Arguments
+    /// Make dumps of the DB durable.
     /// 
-    /// * `key` - a string key
+    /// The DB file is dumped to a temp file that replaces the DB file once it's written, so a crash of the process never
+    /// leaves a partially written DB file. However, the operating system may keep the written data in memory for a while,
+    /// so after a power loss the DB file may still be empty or truncated. After calling this method every dump syncs
+    /// the temp file and its directory to the disk before it returns, and every change appended to the log under
+    /// [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) is synced as well.
+    /// That makes every dump considerably slower, so it's worth combining with a dump policy that doesn't dump upon
+    /// every change.
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // read a num
-    /// let num = db.get::<i32>("key1").unwrap();
-    /// 
-    /// // read a floating point number
-    /// let float_num = db.get::<f32>("key2").unwrap();
-    /// 
-    /// // read a String
-    /// let my_str = db.get::<String>("key3").unwrap();
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
     /// 
-    /// // read a Vec
-    /// ley vec = db.get::<Vec<i32>>("key4").unwrap();
+    /// let mut db = PickleDb::load("example.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)?.with_durable_dumps();
+    /// db.set("key1", &100)?;
     /// 
-    /// // read a struct
-    /// let coor = db.get::<Coor>("key5").unwrap();
+    /// // key1 is on the disk once dump() returns
+    /// db.dump()?;
     /// ```
-    /// 
-    pub fn get<V>(&self, key: &str) -> Option<V> 
-        where 
-            V: DeserializeOwned
-    {
-        match self.map.get(key) {
-            Some(val_as_string) => deserialize_data::<V>(&val_as_string),
-            None => None,
-        }
+    pub fn with_durable_dumps(mut self) -> PickleDb {
+        self.durable_dumps = true;
+        self
     }
 
-    /// Check if a key exists.
-    /// 
-    /// This method returns `true` if the key exists and `false` otherwise.
+    /// Keep backups of the DB file.
+    /// 
+    /// Every dump replaces the DB file, so its previous version is lost. After calling this method the previous version
+    /// is kept as a backup before the DB file is replaced, according to the backup policy: upon every dump or once in
+    /// a while. Backups are kept next to the DB file and their path is the DB file path with a `.bak.N` suffix, where
+    /// backup 1 is the most recent one. When a new backup is made the other backups are renamed to the next number and
+    /// the oldest backups beyond the number of backups to keep are removed. The time of the most recent backup is kept
+    /// in a file whose path is the DB file path with a `.bak.time` suffix, which the periodic policy measures its interval from.
+    /// Under [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) the DB file is only
+    /// replaced when the log is compacted, so the changes in the log aren't backed up until then.
+    /// Please see [backups()](#method.backups) and [restore_backup()](#method.restore_backup) for using the backups.
     /// 
     /// # Arguments
     /// 
-    /// * `key` - the key to check
+    /// * `backup_policy` - an enum value that determines which versions of the DB file are kept. Please see
+    ///   [PickleDbBackupPolicy](enum.PickleDbBackupPolicy.html)
     /// 
-    pub fn exists(&self, key: &str) -> bool {
-        self.map.get(key).is_some() || self.list_map.get(key).is_some()
-    }
-
-    /// Get a vector of all the keys in the DB.
+    /// # Examples
     /// 
-    /// The keys returned in the vector are not references to the actual key string
-    /// objects but rather a clone of them.
+    /// ```rust,ignore
+    /// use pickledb::{PickleDb, PickleDbBackupPolicy, PickleDbDumpPolicy, SerializationMethod};
+    /// use std::time::Duration;
     /// 
-    pub fn get_all(&self) -> Vec<String> {
-        [self.map
-            .iter()
-            .map(|(key, _)| key.clone())
-            .collect::<Vec<String>>(),
-
-        self.list_map
-            .iter()
-            .map(|(key, _)| key.clone())
-            .collect::<Vec<String>>()]
-        
-        .concat()
+    /// // keep a copy of the DB file of each of the last 7 days
+    /// let mut db = PickleDb::load("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)?
+    ///     .with_backups(PickleDbBackupPolicy::Periodic(Duration::from_secs(24 * 60 * 60), 7));
+    /// ```
+    pub fn with_backups(mut self, backup_policy: PickleDbBackupPolicy) -> PickleDb {
+        self.backup_policy = Some(backup_policy);
+        self
     }
 
-    /// Get the total number of keys in the DB.
+    /// Encrypt the DB file.
     /// 
-    pub fn total_keys(&self) -> usize {
-        self.map.iter().len() + self.list_map.iter().len()
-    }
-
-    /// Remove a key-value pair or a list from the DB.
+    /// After calling this method the DB file and its append-only log (please see
+    /// [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly)) are encrypted with
+    /// ChaCha20-Poly1305, which also authenticates them, so a DB file that was tampered with is never loaded.
+    /// The key is either 32 random bytes or derived from a passphrase, please see [EncryptionKey](struct.EncryptionKey.html).
+    /// An encrypted DB is loaded by [PickleDB::load_encrypted()](#method.load_encrypted) with the same key.
     /// 
-    /// This methods returns `true` if the key was found in the DB or false if it wasn't found
+    /// A DB that was loaded from an unencrypted file is encrypted the next time it's dumped, so [dump()](#method.dump)
+    /// should be called in order to encrypt the file right away. The same goes for changing the key of an encrypted DB. Python's PickleDB can't read an encrypted file, so a DB
+    /// that is compatible with it (please see [PickleDB::load_python_compat()](#method.load_python_compat)) is written
+    /// in the default layout once it's encrypted.
+    /// This method is available with the `encryption` feature.
     /// 
     /// # Arguments
     /// 
-    /// * `key` - the key or list name to remove
-    /// 
-    pub fn rem(&mut self, key: &str) -> bool {
-        let res = self.map.remove(key).is_some() || self.list_map.remove(key).is_some();
-        self.dumpdb();
-        res
-    }
-
-    /// Create a new list.
-    /// 
-    /// This method just creates a new list, it doesn't add any elements to it.
-    /// For adding elements to the list please call [ladd()](#method.ladd) or [lextend()](#method.lextend).
-    /// If another list or value is already set under this key, they will be overridden,
-    /// meaning the new list will override the old list or value.
-    /// The method returns an object of type `PickleDbListExtender` that enables to add items 
-    /// to the newly created list
+    /// * `key` - the key to encrypt the DB file with
     /// 
-    /// # Arguments
+    /// # Examples
     /// 
-    /// * `name` - the key of the list that will be created
+    /// ```rust,ignore
+    /// use pickledb::{EncryptionKey, PickleDb, PickleDbDumpPolicy, SerializationMethod};
     /// 
-    pub fn lcreate(&mut self, name: &str) -> PickleDbListExtender {
-        let new_list: Vec<String> = Vec::new();
-        if self.map.contains_key(name) {
-            self.map.remove(name);
-        }
-        self.list_map.insert(String::from(name), new_list);
-        self.dumpdb();
-        PickleDbListExtender { db: self, list_name: String::from(name) }
+    /// let key = EncryptionKey::from_passphrase("correct horse battery staple");
+    /// let mut db = PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_encryption(&key);
+    /// db.set("token", &String::from("secret"))?;
+    /// ```
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, key: &EncryptionKey) -> PickleDb {
+        self.cipher = Some(Cipher::new(key));
+        self.python_compat = false;
+        // the DB file and the log are written again with the new key before anything is appended to the log
+        self.dirty = true;
+        self.log_started = false;
+        self
     }
 
-    /// Check if a list exists.
+    /// Load a DB from a file.
     /// 
-    /// This method returns `true` if the list name exists and `false` otherwise.
-    /// The difference between this method and [exists()](#method.exists) is that this methods checks only
-    /// for lists with that name (key) and [exists()](#method.exists) checks for both values and lists.
+    /// This method tries to load a DB from a file. If the DB has an append-only log (please see
+    /// [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly)) the log is replayed
+    /// on top of the DB file. Upon success an instance of `PickleDB` is returned, 
+    /// otherwise an error is returned: [PickleDbError::Io](enum.PickleDbError.html#variant.Io) if the file
+    /// couldn't be read or [PickleDbError::Corrupted](enum.PickleDbError.html#variant.Corrupted) if its content
+    /// couldn't be deserialized with the given serialization method. An encrypted DB file can't be loaded by this method,
+    /// please see [PickleDB::load_encrypted()](#method.load_encrypted).
+    /// 
+    /// The DB file is dumped through temp files next to it, whose path is the DB file path with a `.temp.` suffix.
+    /// Temp files left behind by dumps that didn't complete, for example because the process crashed, are removed
+    /// when the DB is loaded with any dump policy but [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump).
+    /// Since another DB may be in the middle of a dump, a temp file is only removed if the process that wrote it isn't
+    /// running anymore, which can be told where `/proc` is available, or if it's older than an hour.
+    /// 
+    /// The DB file isn't locked by this method. Locking is opt-in since a lock would make DBs that share a file
+    /// fail to load where they used to work, for example a writer and read-only DBs in the same process, and it
+    /// leaves a lock file next to the DB file. Please see [PickleDB::load_locked()](#method.load_locked) for
+    /// loading a DB that locks its file.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key to check
+    /// * `location` - a path where the DB is loaded from
+    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. 
+    ///   The user can choose between the following options:
+    ///   * [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump) - never dump any change,
+    ///     file will always remain read-only. When choosing this policy even calling to [dump()](#method.dump) won't dump the data.
+    ///     Choosing this option is the same like calling [PickleDB::load_read_only()](#method.load_read_only)
+    ///   * [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump) - every change will be dumped
+    ///     immediately and automatically to the file
+    ///   * [PickleDbDumpPolicy::DumpUponRequest](enum.PickleDbDumpPolicy.html#variant.DumpUponRequest) - data won't be dumped
+    ///     unless the user calls [dump()](#method.dump) proactively to dump the data
+    ///   * [PickleDbDumpPolicy::PeriodicDump(Duration)](enum.PickleDbDumpPolicy.html#variant.PeriodicDump) - changes will be
+    ///     dumped to the file periodically, no sooner than the Duration provided by the user. The way this mechanism works is
+    ///     as follows: each time there is a DB change the last DB dump time is checked. If the time that has passed
+    ///     since the last dump is higher than Duration, changes will be dumped, otherwise changes will not be dumped.
+    ///   * [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) - every change will be
+    ///     appended immediately and automatically to a log file next to the DB file
+    /// * `serialization_method` - the serialization method the file was dumped with. It will also be used for
+    ///   the data stored from now on. Please see [SerializationMethod](enum.SerializationMethod.html) for the available options
     /// 
-    pub fn lexists(&self, name: &str) -> bool {
-        self.list_map.get(name).is_some()
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
+    /// 
+    /// let db = PickleDb::load("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)?;
+    /// ```
+    pub fn load(location: &str, dump_policy: PickleDbDumpPolicy, serialization_method: SerializationMethod) -> Result<PickleDb, PickleDbError> {
+        PickleDb::load_file(location, dump_policy, serialization_method, false, |_| Ok(None))
     }
 
-    /// Add a single item to an existing list.
+    /// Load an encrypted DB from a file.
     /// 
-    /// As mentioned before, the lists are heterogeneous, meaning a single list can contain 
-    /// items of different types. That means that the item can be of any type that is serializable.
-    /// That includes all primitive types, vectors, tuples and every struct that has the 
-    /// `#[derive(Serialize, Deserialize)` attribute.
-    /// The method returns a `Some(PickleDbListExtender)` object that enables to add more items to the list
-    /// if the item was added successfully or `None` if the list name isn't found in the DB.
+    /// This method is similar to [PickleDB::load()](#method.load) for a DB file that was written after calling
+    /// [with_encryption()](#method.with_encryption). The DB stays encrypted with the same key, so there's no need
+    /// to call `with_encryption()` again. If the key is wrong or the DB file or its log was tampered with,
+    /// [PickleDbError::Decryption](enum.PickleDbError.html#variant.Decryption) is returned, and so it is if the
+    /// DB file isn't encrypted. This method is available with the `encryption` feature.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key
-    /// * `value` - a reference of the item to add to the list
+    /// * `location` - a path where the DB is loaded from
+    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. Please see
+    ///   [PickleDB::load()](#method.load) to understand the different policy options
+    /// * `serialization_method` - the serialization method the file was dumped with
+    /// * `key` - the key the DB file was encrypted with
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // create a new list
-    /// db.lcreate("list1");
+    /// use pickledb::{EncryptionKey, PickleDb, PickleDbDumpPolicy, SerializationMethod};
     /// 
-    /// // add items of different types to the list
-    /// db.ladd("list1", &100).unwrap()
-    ///   .ladd(&String::from("my string"))
-    ///   .ladd(&vec!["aa", "bb", "cc"]);
+    /// let key = EncryptionKey::from_passphrase("correct horse battery staple");
+    /// let db = PickleDb::load_encrypted("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json, &key)?;
     /// ```
-    /// 
-    pub fn ladd<V>(&mut self, name: &str, value: &V) -> Option<PickleDbListExtender>
-        where
-            V: Serialize
-    {
-        self.lextend(name, &vec![value])
+    #[cfg(feature = "encryption")]
+    pub fn load_encrypted(
+        location: &str,
+        dump_policy: PickleDbDumpPolicy,
+        serialization_method: SerializationMethod,
+        key: &EncryptionKey,
+    ) -> Result<PickleDb, PickleDbError> {
+        PickleDb::load_file(location, dump_policy, serialization_method, false, |contents| Cipher::for_file(key, contents).map(Some))
     }
 
-    /// Add multiple items to an existing list.
-    /// 
-    /// As mentioned before, the lists are heterogeneous, meaning a single list can contain 
-    /// items of different types. That means that the item can be of any type that is serializable.
-    /// That includes all primitive types, vectors, tuples and every struct that has the 
-    /// `#[derive(Serialize, Deserialize)` attribute.
-    /// This method adds multiple items to the list, but since they're in a vector that means all
-    /// of them are of the same type. Of course it doesn't mean that the list cannot contain items
-    /// of other types as well, as you can see in the example below.
-    /// The method return `Some(PickleDbListExtender)` that enables to add more items to the list 
-    /// if all items were added successfully or `None` if the list name isn't found in the DB.
+    /// Load a DB from a file written by [Python's PickleDB](https://pythonhosted.org/pickleDB/).
+    /// 
+    /// Python's PickleDB stores the whole DB as a single JSON object in which every key holds its value as is.
+    /// This method loads such a file: JSON arrays are loaded as lists, JSON objects are loaded as dicts and
+    /// any other JSON value is loaded as a value. Python's PickleDB has no sets, so a set is written as a JSON object
+    /// whose only key `$set` holds an array of its members and a sorted set as a JSON object whose only key
+    /// `$sorted_set` holds an array of member and score pairs, and such objects are loaded as sets and sorted sets.
+    /// A dict that would be written the same way, with a single `$set` or `$sorted_set` key holding an array, can't
+    /// be dumped and dumping it returns [PickleDbError::Serialization](enum.PickleDbError.html#variant.Serialization).
+    /// Neither can a value that is serialized to a JSON array or object, such as a `Vec` or a `HashMap`, since it
+    /// would be loaded as a list or a dict; it should be stored as a list or a dict instead. The DB is serialized with
+    /// [SerializationMethod::Json](enum.SerializationMethod.html#variant.Json), and whenever it's dumped the file
+    /// is written in the same layout so Python's PickleDB can keep reading it.
+    /// 
+    /// Python's PickleDB has no notion of key expiry, so the expiry time of keys isn't written to the file and
+    /// keys with a TTL don't expire after the DB is loaded again. Under
+    /// [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) changes are appended to
+    /// a log that Python's PickleDB doesn't read, so they're visible to it only after calling [compact()](#method.compact).
+    /// 
+    /// This method returns the same errors as [PickleDB::load()](#method.load).
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key
-    /// * `seq` - a vector containing the new items to add to the list
+    /// * `location` - a path where the DB is loaded from
+    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. Please see
+    ///   [PickleDB::load()](#method.load) to understand the different policy options
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // create a new list
-    /// db.lcreate("list1");
-    /// 
-    /// // add a bunch of numbers to the list
-    /// db.lextends("list1", &vec![100, 200, 300])
-    /// 
-    /// // add a String item to the list
-    ///   .ladd(&String::from("my string"))
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy};
     /// 
-    /// // add a vector item to the list
-    ///   .ladd(&vec!["aa", "bb", "cc"]);
+    /// // the file was written by Python: db.set('key1', 100) and db.lcreate('list1')
+    /// let mut db = PickleDb::load_python_compat("example.db", PickleDbDumpPolicy::AutoDump)?;
     /// 
-    /// // now the list contains 5 items and looks like this: [100, 200, 300, "my string", ["aa, "bb", "cc"]]
+    /// assert_eq!(db.get::<i32>("key1"), Some(100));
+    /// db.ladd("list1", &String::from("written by Rust"))?;
     /// ```
-    /// 
-    pub fn lextend<V>(&mut self, name: &str, seq: &Vec<V>) -> Option<PickleDbListExtender>
+    pub fn load_python_compat(location: &str, dump_policy: PickleDbDumpPolicy) -> Result<PickleDb, PickleDbError> {
+        PickleDb::load_file(location, dump_policy, SerializationMethod::Json, true, |_| Ok(None))
+    }
+
+    /// Load the DB file into a new DB, which reads it in the layout of Python's PickleDB or decrypts it with the cipher
+    /// returned by `cipher` for the content of the file
+    fn load_file<F>(
+        location: &str,
+        dump_policy: PickleDbDumpPolicy,
+        serialization_method: SerializationMethod,
+        python_compat: bool,
+        cipher: F,
+    ) -> Result<PickleDb, PickleDbError>
         where
-            V: Serialize
+            F: FnOnce(&[u8]) -> Result<Option<Cipher>, PickleDbError>
     {
-        match self.list_map.get_mut(name) {
-            Some(list) => {
-                let serialized: Vec<String> = seq.iter()
-                .map(|x| serialize_data(x).unwrap())
-                .collect();
-                list.extend(serialized);
-                self.dumpdb();
-                Some(PickleDbListExtender { db: self, list_name: String::from(name)})
-            },
+        let (contents, file_signature) = read_file(location)?;
+        let mut db = PickleDb::new(location, dump_policy, serialization_method);
+        // a DB that failed to load shouldn't overwrite the file when it's dropped
+        db.dirty = false;
+        db.python_compat = python_compat;
+        db.cipher = cipher(&contents)?;
+        db.replace_data(&contents, file_signature)?;
+        // a read-only DB may be loaded while another DB is dumping the file, so it leaves the temp files alone
+        if !matches!(db.dump_policy, PickleDbDumpPolicy::NeverDump) {
+            db_file::remove_stale_temp_files(location);
+        }
+        Ok(db)
+    }
 
-            None => None,
+    /// Replace the data of the DB with the content of the DB file and replay its log on top of it.
+    /// If the file can't be read the data of the DB isn't changed
+    fn replace_data(&mut self, contents: &[u8], file_signature: FileSignature) -> Result<(), PickleDbError> {
+        let map_from_file = self.decode_file_contents(contents)?;
+        let log_file_path = append_log::log_file_path(&self.db_file_path);
+        let mut log_chain = self.log_chain_start(contents);
+        let (records, valid_len) = append_log::read(&log_file_path, self.serialization_method, self.cipher.as_ref(), &mut log_chain)?;
+        // records appended after a partially written record couldn't be read back, so it's cut off. A read-only DB
+        // leaves the log alone since the record may be in the middle of being appended by another DB
+        if !matches!(self.dump_policy, PickleDbDumpPolicy::NeverDump) {
+            append_log::truncate_torn_tail(&log_file_path, valid_len)?;
+        }
+        self.set_data(map_from_file);
+        self.changed_keys.clear();
+        self.log_started = true;
+        self.log_chain = log_chain;
+        // without the AppendOnly policy the log isn't used anymore, so it should be dumped into the DB file,
+        // unless the DB is read-only
+        self.dirty = !records.is_empty()
+            && !matches!(self.dump_policy, PickleDbDumpPolicy::AppendOnly | PickleDbDumpPolicy::NeverDump);
+        for record in records {
+            self.apply_log_record(record);
         }
+        self.file_signature = Some(file_signature);
+        Ok(())
     }
 
-    /// Get an item of of a certain list in a certain position.
-    /// 
-    /// This method takes a list name and a position inside the list 
-    /// and retrieves the item in this position. It's the user's responsibility 
-    /// to know what is the correct type of the item and give it while calling this method.
-    /// Since the item in the lists are stored in a serialized way the returned object 
+    /// What the first record that is appended to the log after the DB file with the given content is chained to
+    fn log_chain_start(&self, contents: &[u8]) -> Vec<u8> {
+        match &self.cipher {
+            Some(cipher) => cipher.log_chain_start(contents),
+            None => Vec::new(),
+        }
+    }
+
+    /// Deserialize the content of a DB file the way this DB writes it
+    fn decode_file_contents(&self, contents: &[u8]) -> Result<DbFileContents, PickleDbError> {
+        match &self.cipher {
+            Some(cipher) => read_db_file_contents(&cipher.decrypt_file(contents)?, self.serialization_method),
+            None if encryption::is_encrypted(contents) =>
+                Err(PickleDbError::Decryption(String::from("the DB file is encrypted, it should be loaded with its key"))),
+            None if self.python_compat => python_compat::read(contents).map_err(PickleDbError::Corrupted),
+            None => read_db_file_contents(contents, self.serialization_method),
+        }
+    }
+
+    /// Replace all the data of the DB, whose keys stay ordered if they were
+    fn set_data(&mut self, map_from_file: DbFileContents) {
+        let ordered_keys = self.map.is_ordered();
+        self.map = map_from_file.0.into();
+        self.list_map = map_from_file.1.into();
+        self.expiry = map_from_file.2;
+        self.dict_map = map_from_file.3.into();
+        self.set_map = map_from_file.4.into();
+        self.sorted_set_map = map_from_file.5.into();
+        if ordered_keys {
+            self.map.make_ordered();
+            self.list_map.make_ordered();
+            self.dict_map.make_ordered();
+            self.set_map.make_ordered();
+            self.sorted_set_map.make_ordered();
+        }
+    }
+
+    /// Reload the DB from its file.
+    /// 
+    /// A DB keeps the data it loaded in memory, so it doesn't see the changes that another DB, in this process or
+    /// in another one, dumps to the same file later on. This method reads the DB file and its append-only log again
+    /// and replaces all the data of the DB with them, the same way the DB was loaded: in the layout of Python's
+    /// PickleDB if it was loaded by [PickleDB::load_python_compat()](#method.load_python_compat) and decrypted if it
+    /// was loaded by [PickleDB::load_encrypted()](#method.load_encrypted). No change events are sent for the data
+    /// that was replaced.
+    /// 
+    /// If the file can't be read this method returns the same errors as [PickleDB::load()](#method.load) and the
+    /// data of the DB isn't changed. The DB can't be reloaded while a transaction is in progress, in that case
+    /// [PickleDbError::TransactionInProgress](enum.PickleDbError.html#variant.TransactionInProgress) is returned,
+    /// and it can't be reloaded while it has changes that weren't dumped yet (please see [is_dirty()](#method.is_dirty)),
+    /// since they would be lost, in that case [PickleDbError::UnsavedChanges](enum.PickleDbError.html#variant.UnsavedChanges)
+    /// is returned.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let mut readonly_db = PickleDb::load_read_only("example.db", SerializationMethod::Json)?;
+    /// 
+    /// // another process changes example.db
+    /// 
+    /// readonly_db.reload()?;
+    /// ```
+    pub fn reload(&mut self) -> Result<(), PickleDbError> {
+        if self.in_transaction() {
+            return Err(PickleDbError::TransactionInProgress);
+        }
+        if self.dirty {
+            return Err(PickleDbError::UnsavedChanges);
+        }
+        let (contents, file_signature) = read_file(&self.db_file_path)?;
+        // the DB file may have been encrypted again with the same passphrase but another salt
+        if let Some(cipher) = &self.cipher {
+            self.cipher = Some(cipher.for_contents(&contents)?);
+        }
+        self.replace_data(&contents, file_signature)
+    }
+
+    /// Reload the DB from its file if the file changed.
+    /// 
+    /// This method is similar to [reload()](#method.reload) but it checks first whether the DB file or its append-only
+    /// log changed since the DB was loaded, last reloaded or last dumped, by their size, modification time and, on Unix,
+    /// their inode, so the dumps of this DB itself don't count as changes. Calling it every now and then lets a read-only
+    /// DB follow the changes of a DB in another process. This method returns whether the DB was reloaded, or the same
+    /// errors as [reload()](#method.reload).
+    /// Please see [SharedPickleDb::start_watcher()](struct.SharedPickleDb.html#method.start_watcher) for a DB that is
+    /// reloaded in a background thread.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let mut readonly_db = PickleDb::load_read_only("example.db", SerializationMethod::Json)?;
+    /// 
+    /// loop {
+    ///     if readonly_db.reload_if_changed()? {
+    ///         println!("key1 is now {:?}", readonly_db.get::<i32>("key1"));
+    ///     }
+    ///     thread::sleep(Duration::from_secs(1));
+    /// }
+    /// ```
+    pub fn reload_if_changed(&mut self) -> Result<bool, PickleDbError> {
+        if !self.file_changed()? {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Whether the DB file or its log changed since the DB was loaded, last reloaded or last dumped
+    pub(crate) fn file_changed(&self) -> Result<bool, PickleDbError> {
+        Ok(self.file_signature != Some(FileSignature::read(&self.db_file_path)?))
+    }
+
+    /// Load a DB from a file in read-only mode.
+    ///
+    /// This method is similar to the [PickleDB::load()](#method.load) method with the only difference
+    /// that the file is loaded from DB with a dump policy of 
+    /// [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump), meaning
+    /// changes will not be saved to the file, even when calling [dump()](#method.dump).
+    /// 
+    /// The DB file isn't locked, so a writer may replace it while it's being read. Since the file is replaced
+    /// atomically the DB still reads a complete file, but it may be missing changes that are in the middle of being
+    /// dumped. Please see [PickleDB::load_read_only_locked()](#method.load_read_only_locked) for a read-only DB
+    /// that holds a shared lock of its file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `location` - a path where the DB is loaded from
+    /// * `serialization_method` - the serialization method the file was dumped with
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// use pickledb::{PickleDb, SerializationMethod};
+    /// 
+    /// let mut readonly_db = PickleDb::load_read_only("example.db", SerializationMethod::Json)?;
+    /// 
+    /// // nothing is written by calling this method, PickleDbError::ReadOnly is returned
+    /// readonly_db.dump();
+    /// ```
+    /// 
+    pub fn load_read_only(location: &str, serialization_method: SerializationMethod) -> Result<PickleDb, PickleDbError> {
+        PickleDb::load(location, PickleDbDumpPolicy::NeverDump, serialization_method)
+    }
+
+    /// Load a DB from a file in read-only mode and take a shared lock of it.
+    /// 
+    /// This method is similar to [PickleDB::load_read_only()](#method.load_read_only) but it takes a shared lock of the
+    /// DB file, which is held until the DB is dropped. Other read-only DBs can hold the lock at the same time, while
+    /// DBs that may write the file can't lock it until all the readers are dropped. It's the same as calling
+    /// [PickleDB::load_locked()](#method.load_locked) with [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `location` - a path where the DB is loaded from
+    /// * `serialization_method` - the serialization method the file was dumped with
+    /// * `lock_policy` - what to do if the DB file is locked by a writer. Please see
+    ///   [PickleDbLockPolicy](enum.PickleDbLockPolicy.html)
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// use pickledb::{PickleDb, PickleDbLockPolicy, SerializationMethod};
+    /// 
+    /// let readonly_db = PickleDb::load_read_only_locked("example.db", SerializationMethod::Json, PickleDbLockPolicy::NoWait)?;
+    /// ```
+    pub fn load_read_only_locked(
+        location: &str,
+        serialization_method: SerializationMethod,
+        lock_policy: PickleDbLockPolicy,
+    ) -> Result<PickleDb, PickleDbError> {
+        PickleDb::load_locked(location, PickleDbDumpPolicy::NeverDump, serialization_method, lock_policy)
+    }
+
+    /// Load a DB from a file and lock it.
+    /// 
+    /// Several DBs, in the same process or in different processes, that change the same DB file overwrite each other's
+    /// changes. This method is similar to [PickleDB::load()](#method.load) but it takes an advisory lock of the DB file
+    /// before loading it, and holds it until the DB is dropped. A DB loaded with
+    /// [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump) only reads the file, so it takes a
+    /// shared lock that other readers can hold at the same time. With any other dump policy an exclusive lock is taken,
+    /// so while it's held no other DB can lock the file, either for reading or for writing.
+    /// 
+    /// If the lock can't be taken according to the lock policy [PickleDbError::Locked](enum.PickleDbError.html#variant.Locked)
+    /// is returned. The lock is kept in a file whose path is the DB file path with a `.lock` suffix, which is left in place
+    /// when the lock is released. The lock is advisory, so it only protects the DB file from DBs that are locked too.
+    /// This method returns the same errors as [PickleDB::load()](#method.load) as well.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `location` - a path where the DB is loaded from
+    /// * `dump_policy` - an enum value that determines the policy of dumping DB changes into the file. Please see
+    ///   [PickleDB::load()](#method.load) to understand the different policy options
+    /// * `serialization_method` - the serialization method the file was dumped with
+    /// * `lock_policy` - what to do if the DB file is locked by another DB. Please see
+    ///   [PickleDbLockPolicy](enum.PickleDbLockPolicy.html)
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbLockPolicy, SerializationMethod};
+    /// use std::time::Duration;
+    /// 
+    /// // wait up to a second for other processes to release the DB file
+    /// let mut db = PickleDb::load_locked("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json,
+    ///                                    PickleDbLockPolicy::Wait(Duration::from_secs(1)))?;
+    /// 
+    /// // several readers can load the DB file at the same time
+    /// let readonly_db = PickleDb::load_locked("example.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json,
+    ///                                         PickleDbLockPolicy::NoWait)?;
+    /// ```
+    pub fn load_locked(
+        location: &str,
+        dump_policy: PickleDbDumpPolicy,
+        serialization_method: SerializationMethod,
+        lock_policy: PickleDbLockPolicy,
+    ) -> Result<PickleDb, PickleDbError> {
+        let lock_file = file_lock::lock(location, !matches!(dump_policy, PickleDbDumpPolicy::NeverDump), lock_policy)?;
+        let mut db = PickleDb::load(location, dump_policy, serialization_method)?;
+        db.lock_file = Some(lock_file);
+        Ok(db)
+    }
+
+    /// Dump the data to the file.
+    /// 
+    /// Calling this method is necessary only if the DB is loaded or created with a dump policy other than
+    /// [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump), otherwise the data
+    /// is dumped to the file upon every change.
+    /// If the DB has no changes since it was loaded or last dumped (please see [is_dirty()](#method.is_dirty))
+    /// the file isn't written at all.
+    /// Keys that have expired are purged from the DB before it is dumped.
+    /// This method returns `Ok(())` if dump is successful. Otherwise it returns
+    /// [PickleDbError::Serialization](enum.PickleDbError.html#variant.Serialization) if the DB couldn't be serialized,
+    /// [PickleDbError::Io](enum.PickleDbError.html#variant.Io) if the file couldn't be written or
+    /// [PickleDbError::ReadOnly](enum.PickleDbError.html#variant.ReadOnly) if the DB was loaded with
+    /// [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump).
+    /// The DB can't be dumped while a transaction is in progress, in that case
+    /// [PickleDbError::TransactionInProgress](enum.PickleDbError.html#variant.TransactionInProgress) is returned.
+    /// 
+    pub fn dump(&mut self) -> Result<(), PickleDbError> {
+        self.check_dump_allowed()?;
+        if !self.dirty {
+            return Ok(());
+        }
+        self.write_db_file()
+    }
+
+    /// Check if the DB has changes that weren't dumped to the file yet.
+    /// 
+    /// A new DB is dirty until it's dumped for the first time, and a DB loaded from a file is clean until
+    /// it's changed. Dumping the DB, either explicitly or according to the dump policy, makes it clean again.
+    /// 
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn check_dump_allowed(&self) -> Result<(), PickleDbError> {
+        if let PickleDbDumpPolicy::NeverDump = self.dump_policy {
+            return Err(PickleDbError::ReadOnly);
+        }
+        if self.in_transaction() {
+            return Err(PickleDbError::TransactionInProgress);
+        }
+        Ok(())
+    }
+
+    fn write_db_file(&mut self) -> Result<(), PickleDbError> {
+        self.purge_expired();
+        let ser_db = self.serialize_db()?;
+        db_file::write(&self.db_file_path, &ser_db, self.durable_dumps, self.backup_policy)?;
+
+        // the DB file now contains all the changes in the log
+        let append_only = matches!(self.dump_policy, PickleDbDumpPolicy::AppendOnly);
+        append_log::reset(&append_log::log_file_path(&self.db_file_path), append_only)?;
+        self.log_started = append_only;
+        self.log_chain = self.log_chain_start(&ser_db);
+        self.changed_keys.clear();
+        self.dirty = false;
+        self.refresh_file_signature();
+        if let PickleDbDumpPolicy::PeriodicDump(_dur) = self.dump_policy {
+            self.last_dump = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Take the state of the DB file and its log after this DB wrote them, so its own dumps aren't taken for changes
+    /// of another DB. If it can't be read the file is taken as changed
+    fn refresh_file_signature(&mut self) {
+        self.file_signature = FileSignature::read(&self.db_file_path).ok();
+    }
+
+    /// Serialize the DB the way it's written to the DB file
+    fn serialize_db(&self) -> Result<Vec<u8>, PickleDbError> {
+        let ser_db = if self.python_compat {
+            python_compat::write(self)
+        } else if let (Some(options), SerializationMethod::Json) = (self.readable_dump, self.serialization_method) {
+            json_format::write(self, options)
+        } else {
+            self.serialization_method.serialize_data(&(&self.map, &self.list_map, &self.expiry, &self.dict_map, &self.set_map, &self.sorted_set_map))
+        }.map_err(PickleDbError::Serialization)?;
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt_file(&ser_db),
+            None => Ok(ser_db),
+        }
+    }
+
+    /// Write all the data of the DB to a file other than the DB file, the same way the DB file is written
+    fn write_copy(&self, path: &str) -> Result<(), PickleDbError> {
+        // replacing the DB file this way would leave the log and the encryption chain of the DB out of sync with it
+        if db_file::same_file(path, &self.db_file_path) {
+            return Err(PickleDbError::Io(io::Error::new(io::ErrorKind::InvalidInput, "the copy can't be written to the DB file")));
+        }
+        // expired keys aren't written, so if there are any they are left out of a copy of the data
+        let ser_db = if self.expiry.values().any(|expires_at| *expires_at <= now_millis()) {
+            self.copy_data().serialize_db()?
+        } else {
+            self.serialize_db()?
+        };
+        db_file::write(path, &ser_db, self.durable_dumps, None)?;
+        // a log left behind by another DB with the same file would be replayed when the copy is loaded
+        append_log::reset(&append_log::log_file_path(path), false)
+    }
+
+    /// Write a copy of the DB to another file.
+    /// 
+    /// This method writes all the data of the DB to the given path the same way [dump()](#method.dump) writes the DB
+    /// file: with the same serialization method and layout, encrypted with the same key if the DB is encrypted, and
+    /// through a temp file so the copy is never partially written. Unlike copying the DB file, the copy holds the data
+    /// as it is in memory, also changes that weren't dumped yet, and a dump that is in progress can't corrupt it.
+    /// The DB file path of the DB isn't changed, and neither the DB file nor its dirty state are affected,
+    /// so a read-only DB can be saved as well. Keys that expired are left out of the copy but aren't removed from the DB.
+    /// The copy can be loaded like any other DB file.
+    /// 
+    /// This method returns [PickleDbError::TransactionInProgress](enum.PickleDbError.html#variant.TransactionInProgress)
+    /// in the middle of a transaction, [PickleDbError::Io](enum.PickleDbError.html#variant.Io) if the file couldn't be
+    /// written or if the path is the DB file itself, and [PickleDbError::Serialization](enum.PickleDbError.html#variant.Serialization)
+    /// if the data couldn't be serialized.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - the path of the copy. An existing file is replaced, except the DB file, which is written by [dump()](#method.dump)
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.save_as("export.db")?;
+    /// 
+    /// let copy = PickleDb::load_read_only("export.db", SerializationMethod::Json)?;
+    /// ```
+    pub fn save_as(&self, path: &str) -> Result<(), PickleDbError> {
+        if self.in_transaction() {
+            return Err(PickleDbError::TransactionInProgress);
+        }
+        self.write_copy(path)
+    }
+
+    /// Get a point-in-time snapshot of the DB.
+    /// 
+    /// The snapshot is a read-only copy of the data of the DB as it is when this method is called, including the changes
+    /// of a transaction in progress. Changes made to the DB later on don't affect the snapshot, so it can be read while
+    /// the DB keeps changing, for example by a report that should see consistent data. Taking a snapshot copies the
+    /// data of the DB, but the snapshot itself is cheap to clone and can be sent to other threads. A snapshot can be
+    /// written to a file by calling [PickleDbSnapshot::write_to()](struct.PickleDbSnapshot.html#method.write_to).
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.set("key1", &1)?;
+    /// let snapshot = db.snapshot();
+    /// db.set("key1", &2)?;
+    /// 
+    /// assert_eq!(snapshot.get::<i32>("key1"), Some(1));
+    /// snapshot.write_to("key1_was_1.db")?;
+    /// ```
+    pub fn snapshot(&self) -> PickleDbSnapshot {
+        PickleDbSnapshot::new(self.copy_data())
+    }
+
+    /// A read-only copy of the data of the DB without the keys that expired, which is written the same way as the DB
+    fn copy_data(&self) -> PickleDb {
+        let mut db = PickleDb::new(&self.db_file_path, PickleDbDumpPolicy::NeverDump, self.serialization_method);
+        db.map = self.map.clone();
+        db.list_map = self.list_map.clone();
+        db.dict_map = self.dict_map.clone();
+        db.set_map = self.set_map.clone();
+        db.sorted_set_map = self.sorted_set_map.clone();
+        db.expiry = self.expiry.clone();
+        db.python_compat = self.python_compat;
+        db.readable_dump = self.readable_dump;
+        db.durable_dumps = self.durable_dumps;
+        db.cipher = self.cipher.clone();
+        db.purge_expired();
+        db.dirty = false;
+        db
+    }
+
+    /// Get the backups of the DB file, from the most recent one to the oldest one.
+    /// 
+    /// Please see [with_backups()](#method.with_backups). Backups that were kept by earlier runs are listed too,
+    /// whatever the backup policy of this DB is. This method returns [PickleDbError::Io](enum.PickleDbError.html#variant.Io)
+    /// if the directory of the DB file couldn't be read.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// for backup in db.backups()? {
+    ///     println!("backup {} at {} was dumped at {:?}", backup.number(), backup.path(), backup.modified());
+    /// }
+    /// ```
+    pub fn backups(&self) -> Result<Vec<PickleDbBackup>, PickleDbError> {
+        Ok(backup::list(&self.db_file_path)?)
+    }
+
+    /// Restore a backup of the DB file.
+    /// 
+    /// This method replaces all the data of the DB with the data of a backup (please see [with_backups()](#method.with_backups))
+    /// and dumps it to the DB file right away. The backup is read the same way the DB file is, so a backup of an
+    /// encrypted DB is decrypted with its key. If the DB keeps backups, the DB file that is replaced is backed up
+    /// like upon any other dump, so the restore can be undone. No change events are sent for the data that was replaced.
+    /// 
+    /// This method returns [PickleDbError::Io](enum.PickleDbError.html#variant.Io) if the backup doesn't exist or
+    /// couldn't be read, the errors of [PickleDB::load()](#method.load) if it couldn't be deserialized, in which case
+    /// the data of the DB isn't changed, and the errors of [dump()](#method.dump).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `number` - the number of the backup, where 1 is the most recent one. Please see [backups()](#method.backups)
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // a bad deploy wrote wrong data, so go back to the version before the last dump
+    /// db.restore_backup(1)?;
+    /// ```
+    pub fn restore_backup(&mut self, number: usize) -> Result<(), PickleDbError> {
+        self.check_dump_allowed()?;
+        let contents = fs::read(backup::backup_file_path(&self.db_file_path, number))?;
+        // the backup may have been encrypted with the same passphrase but another salt
+        if let Some(cipher) = &self.cipher {
+            self.cipher = Some(cipher.for_contents(&contents)?);
+        }
+        let map_from_file = self.decode_file_contents(&contents)?;
+        self.set_data(map_from_file);
+        // if the dump fails the whole DB is dumped again later on, also under PickleDbDumpPolicy::AppendOnly
+        self.dirty = true;
+        self.log_started = false;
+        self.write_db_file()
+    }
+
+    /// Compact the append-only log.
+    /// 
+    /// Under [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) every change is
+    /// appended to the log, so it keeps growing and makes loading the DB slower. This method writes the whole DB to
+    /// the DB file and truncates the log. Unlike [dump()](#method.dump) the DB file is written even if the DB has no
+    /// changes that weren't dumped yet. This method returns the same errors as [dump()](#method.dump).
+    /// 
+    pub fn compact(&mut self) -> Result<(), PickleDbError> {
+        self.check_dump_allowed()?;
+        self.write_db_file()
+    }
+
+    /// Dump the changes that weren't dumped yet, whatever the dump policy is. Used for flushing the DB in the background
+    fn flush(&mut self) -> Result<(), PickleDbError> {
+        // changes made in a transaction are dumped when it's committed, and never if it's rolled back
+        if !self.dirty || self.in_transaction() {
+            return Ok(());
+        }
+        match self.dump_policy {
+            PickleDbDumpPolicy::NeverDump => Ok(()),
+            PickleDbDumpPolicy::AppendOnly => self.append_changes(),
+            _ => self.dump(),
+        }
+    }
+
+    fn dumpdb(&mut self, key: &str) -> Result<(), PickleDbError> {
+        self.dumpdb_keys(&[key])
+    }
+
+    /// Like dumpdb() for a change of several keys, which are dumped together
+    fn dumpdb_keys(&mut self, keys: &[&str]) -> Result<(), PickleDbError> {
+        if !self.subscribers.is_empty() {
+            for key in keys {
+                self.notify(self.change_event(key));
+            }
+        }
+        self.dirty = true;
+        if let PickleDbDumpPolicy::AppendOnly = self.dump_policy {
+            self.changed_keys.extend(keys.iter().map(|key| String::from(*key)));
+        }
+        self.dump_changes()
+    }
+
+    fn dump_changes(&mut self) -> Result<(), PickleDbError> {
+        // changes made in a transaction are dumped when it's committed
+        if self.in_transaction() {
+            return Ok(());
+        }
+        match self.dump_policy {
+            PickleDbDumpPolicy::AutoDump => self.dump(),
+            PickleDbDumpPolicy::PeriodicDump(duration) => {
+                let now = Instant::now();
+                if now.duration_since(self.last_dump) > duration {
+                    self.last_dump = Instant::now();
+                    self.dump()?;
+                }
+                Ok(())
+            },
+            PickleDbDumpPolicy::AppendOnly => self.append_changes(),
+
+            _ => Ok(()),
+        }
+    }
+
+    fn append_changes(&mut self) -> Result<(), PickleDbError> {
+        if self.changed_keys.is_empty() {
+            return Ok(());
+        }
+        // a new DB may be created where an older DB file and log exist, so they're replaced before appending
+        if !self.log_started {
+            return self.write_db_file();
+        }
+        let records: Vec<LogRecord> = self.changed_keys.iter()
+            .map(|key| self.log_record(key))
+            .collect();
+        append_log::append(&append_log::log_file_path(&self.db_file_path), &records, self.serialization_method,
+                           self.cipher.as_ref(), &mut self.log_chain, self.durable_dumps)?;
+        self.changed_keys.clear();
+        self.dirty = false;
+        self.refresh_file_signature();
+        Ok(())
+    }
+
+    fn log_record(&self, key: &str) -> LogRecord {
+        let expires_at = self.expiry.get(key).copied();
+        if let Some(value) = self.map.get(key) {
+            LogRecord::Value(String::from(key), value.clone(), expires_at)
+        } else if let Some(list) = self.list_map.get(key) {
+            LogRecord::List(String::from(key), list.clone(), expires_at)
+        } else if let Some(dict) = self.dict_map.get(key) {
+            LogRecord::Dict(String::from(key), dict.clone(), expires_at)
+        } else if let Some(set) = self.set_map.get(key) {
+            LogRecord::Set(String::from(key), set.clone(), expires_at)
+        } else if let Some(sorted_set) = self.sorted_set_map.get(key) {
+            LogRecord::SortedSet(String::from(key), sorted_set.clone(), expires_at)
+        } else {
+            LogRecord::Removed(String::from(key))
+        }
+    }
+
+    /// The event of a change of a key, according to what the key holds after the change
+    fn change_event(&self, key: &str) -> PickleDbEvent {
+        let key_name = String::from(key);
+        if self.map.contains_key(key) {
+            PickleDbEvent::Set(key_name)
+        } else if self.list_map.contains_key(key) {
+            PickleDbEvent::ListChanged(key_name)
+        } else if self.dict_map.contains_key(key) {
+            PickleDbEvent::DictChanged(key_name)
+        } else if self.set_map.contains_key(key) {
+            PickleDbEvent::SetChanged(key_name)
+        } else if self.sorted_set_map.contains_key(key) {
+            PickleDbEvent::SortedSetChanged(key_name)
+        } else {
+            PickleDbEvent::Removed(key_name)
+        }
+    }
+
+    fn notify(&mut self, event: PickleDbEvent) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+        match &mut self.tx_snapshot {
+            // events of a transaction are sent when it's committed
+            Some(snapshot) => snapshot.events.push(event),
+            None => self.subscribers.notify(&event),
+        }
+    }
+
+    fn apply_log_record(&mut self, record: LogRecord) {
+        let (key, expires_at) = match record {
+            LogRecord::Value(key, value, expires_at) => {
+                self.remove_key(&key);
+                self.map.insert(key.clone(), value);
+                (key, expires_at)
+            },
+            LogRecord::List(key, list, expires_at) => {
+                self.remove_key(&key);
+                self.list_map.insert(key.clone(), list);
+                (key, expires_at)
+            },
+            LogRecord::Dict(key, dict, expires_at) => {
+                self.remove_key(&key);
+                self.dict_map.insert(key.clone(), dict);
+                (key, expires_at)
+            },
+            LogRecord::Set(key, set, expires_at) => {
+                self.remove_key(&key);
+                self.set_map.insert(key.clone(), set);
+                (key, expires_at)
+            },
+            LogRecord::SortedSet(key, sorted_set, expires_at) => {
+                self.remove_key(&key);
+                self.sorted_set_map.insert(key.clone(), sorted_set);
+                (key, expires_at)
+            },
+            LogRecord::Removed(key) => {
+                self.remove_key(&key);
+                (key, None)
+            },
+        };
+        match expires_at {
+            Some(expires_at) => self.expiry.insert(key, expires_at),
+            None => self.expiry.remove(&key),
+        };
+    }
+
+    /// Run a closure in a transaction.
+    /// 
+    /// The closure gets the DB and can make any number of changes to it. None of them is dumped while the closure
+    /// runs. If the closure returns `Ok` the transaction is committed and the changes are dumped once according to the
+    /// dump policy (please see [commit()](#method.commit)). If the closure returns an error or panics the transaction is
+    /// rolled back and the DB returns to the state it was in before the closure was called.
+    /// The error type of the closure can be any type that can be converted from `PickleDbError`, so errors of
+    /// PickleDB APIs can be propagated from the closure with `?`.
+    /// This method returns the value the closure returned, the error the closure returned,
+    /// [PickleDbError::TransactionInProgress](enum.PickleDbError.html#variant.TransactionInProgress) if a transaction
+    /// is already in progress, or an error if dumping the changes failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `f` - a closure that makes the changes of the transaction
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // move an item from one list to another. If any of the steps fails neither list is changed
+    /// db.transaction(|tx| -> Result<(), PickleDbError> {
+    ///     let item = tx.lpop::<i32>("list1", 0)?.unwrap_or_default();
+    ///     tx.ladd("list2", &item)?;
+    ///     Ok(())
+    /// })?;
+    /// ```
+    /// 
+    pub fn transaction<F, R, E>(&mut self, f: F) -> Result<R, E>
+        where
+            F: FnOnce(&mut PickleDb) -> Result<R, E>,
+            E: From<PickleDbError>
+    {
+        self.begin()?;
+        let guard = TransactionGuard(self);
+        let res = f(&mut *guard.0)?;
+        guard.0.commit()?;
+        Ok(res)
+    }
+
+    /// Start a transaction.
+    /// 
+    /// Changes made after this method is called aren't dumped until [commit()](#method.commit) is called, and
+    /// can be undone by calling [rollback()](#method.rollback). If the DB is dropped before the transaction is
+    /// committed the transaction is rolled back.
+    /// This method returns [PickleDbError::TransactionInProgress](enum.PickleDbError.html#variant.TransactionInProgress)
+    /// if a transaction is already in progress.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.begin()?;
+    /// db.set("key1", &100)?;
+    /// db.set("key2", &200)?;
+    /// 
+    /// // both keys are dumped together
+    /// db.commit()?;
+    /// ```
+    /// 
+    pub fn begin(&mut self) -> Result<(), PickleDbError> {
+        if self.in_transaction() {
+            return Err(PickleDbError::TransactionInProgress);
+        }
+        self.tx_snapshot = Some(DbSnapshot {
+            map: self.map.clone(),
+            list_map: self.list_map.clone(),
+            dict_map: self.dict_map.clone(),
+            set_map: self.set_map.clone(),
+            sorted_set_map: self.sorted_set_map.clone(),
+            expiry: self.expiry.clone(),
+            dirty: self.dirty,
+            changed_keys: self.changed_keys.clone(),
+            events: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Commit the transaction in progress.
+    /// 
+    /// The changes made in the transaction are dumped once according to the dump policy, for example under
+    /// [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump) they're dumped immediately.
+    /// This method returns [PickleDbError::NoTransaction](enum.PickleDbError.html#variant.NoTransaction) if no
+    /// transaction is in progress, or an error if dumping the changes failed. In the latter case the transaction
+    /// is still committed and the changes are kept in memory.
+    /// 
+    pub fn commit(&mut self) -> Result<(), PickleDbError> {
+        let snapshot = self.tx_snapshot.take().ok_or(PickleDbError::NoTransaction)?;
+        for event in &snapshot.events {
+            self.subscribers.notify(event);
+        }
+        self.dump_changes()
+    }
+
+    /// Roll back the transaction in progress.
+    /// 
+    /// All the changes made in the transaction are undone and the DB returns to the state it was in when
+    /// the transaction started. This method returns [PickleDbError::NoTransaction](enum.PickleDbError.html#variant.NoTransaction)
+    /// if no transaction is in progress.
+    /// 
+    pub fn rollback(&mut self) -> Result<(), PickleDbError> {
+        let snapshot = self.tx_snapshot.take().ok_or(PickleDbError::NoTransaction)?;
+        self.map = snapshot.map;
+        self.list_map = snapshot.list_map;
+        self.dict_map = snapshot.dict_map;
+        self.set_map = snapshot.set_map;
+        self.sorted_set_map = snapshot.sorted_set_map;
+        self.expiry = snapshot.expiry;
+        self.dirty = snapshot.dirty;
+        self.changed_keys = snapshot.changed_keys;
+        Ok(())
+    }
+
+    /// Check if a transaction is in progress.
+    /// 
+    pub fn in_transaction(&self) -> bool {
+        self.tx_snapshot.is_some()
+    }
+
+    /// Subscribe to the changes of keys in the DB.
+    /// 
+    /// The callback is called with a [PickleDbEvent](enum.PickleDbEvent.html) every time a key whose name starts with
+    /// `prefix` changes, which is useful for invalidating caches or updating a UI. An empty prefix subscribes to all keys.
+    /// The event tells what the key holds after the change, for example [PickleDbEvent::Set](enum.PickleDbEvent.html#variant.Set)
+    /// when a value is set or [PickleDbEvent::ListChanged](enum.PickleDbEvent.html#variant.ListChanged) when an item is added
+    /// to a list, and [PickleDbEvent::Removed](enum.PickleDbEvent.html#variant.Removed) when the key is removed. Changing the
+    /// TTL of a key sends the event of the data it holds. A key that expired is removed from the DB, sending
+    /// [PickleDbEvent::Expired](enum.PickleDbEvent.html#variant.Expired), when the DB is dumped or when an API such as
+    /// [ladd()](#method.ladd) or [incr()](#method.incr) changes the key, so that event may come some time after the key
+    /// expired. An expired key that is overridden, for example by [set()](#method.set), only sends the event of the new data.
+    /// 
+    /// Events are sent after the change is made in memory and before it's dumped, so they're sent even if dumping
+    /// the change fails. The events of a transaction are sent when it's committed and are discarded if it's rolled back.
+    /// The callback runs in the thread that changed the DB while the DB is borrowed, so it can't access the DB itself,
+    /// and a panic of the callback is propagated to the API that changed the DB.
+    /// 
+    /// This method returns a [PickleDbSubscription](struct.PickleDbSubscription.html) that can be passed to
+    /// [unsubscribe()](#method.unsubscribe). Subscriptions aren't stored in the DB file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `prefix` - the prefix of the keys to get events of
+    /// * `callback` - a closure that is called with every event
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let subscription = db.subscribe("user:", |event| println!("{} changed: {:?}", event.key(), event));
+    /// 
+    /// // prints "user:1 changed: Set("user:1")"
+    /// db.set("user:1", &String::from("alice"))?;
+    /// 
+    /// // no event, the key doesn't match the prefix
+    /// db.set("session:1", &100)?;
+    /// 
+    /// db.unsubscribe(subscription);
+    /// ```
+    /// 
+    pub fn subscribe<F>(&mut self, prefix: &str, callback: F) -> PickleDbSubscription
+        where
+            F: FnMut(&PickleDbEvent) + Send + Sync + 'static
+    {
+        self.subscribers.add_closure(prefix, callback)
+    }
+
+    /// Subscribe to the changes of keys in the DB through a channel.
+    /// 
+    /// This method is similar to [subscribe()](#method.subscribe) but the events are sent to the returned channel
+    /// rather than to a callback, so they can be received in another thread. The subscription ends when the receiver
+    /// is dropped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `prefix` - the prefix of the keys to get events of
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let events = db.subscribe_channel("");
+    /// db.set("key1", &100)?;
+    /// 
+    /// assert_eq!(events.try_recv().unwrap(), PickleDbEvent::Set(String::from("key1")));
+    /// ```
+    /// 
+    pub fn subscribe_channel(&mut self, prefix: &str) -> Receiver<PickleDbEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.add_channel(prefix, sender);
+        receiver
+    }
+
+    /// Cancel a subscription to the changes of keys in the DB.
+    /// 
+    /// This method returns `true` if the subscription was cancelled or `false` if it was already cancelled.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `subscription` - the subscription returned in [subscribe()](#method.subscribe)
+    /// 
+    pub fn unsubscribe(&mut self, subscription: PickleDbSubscription) -> bool {
+        self.subscribers.remove(subscription)
+    }
+
+    /// Set a key-value pair.
+    /// 
+    /// The key has to be a string but the value can be of any type that is serializable.
+    /// That includes all primitive types, vectors, tuples and every struct that has the 
+    /// `#[derive(Serialize, Deserialize)` attribute.
+    /// The method returns an error if the value couldn't be serialized or if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - a string key
+    /// * `value` - a value of any serializable type
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // set a number
+    /// db.set("key1", &100)?;
+    /// 
+    /// // set a floating point number
+    /// db.set("key2", &1.234)?;
+    /// 
+    /// // set a String
+    /// db.set("key3", &String::from("hello world"))?;
+    /// 
+    /// // set a Vec
+    /// db.set("key4", &vec![1,2,3])?;
+    /// 
+    /// // set a struct
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Coor {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    /// let mycoor = Coor { x: 1, y : 2 };
+    /// db.set("key5", &mycoor)?;
+    /// ```
+    /// 
+    pub fn set<V>(&mut self, key: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.set_value(key, value, None)
+    }
+
+    /// Set a key-value pair that expires after a certain time.
+    /// 
+    /// This method is similar to [set()](#method.set) but the key expires once `ttl` has passed,
+    /// and from then on it's no longer visible, as if it was removed.
+    /// Setting the key again with [set()](#method.set) removes the TTL.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - a string key
+    /// * `value` - a value of any serializable type
+    /// * `ttl` - the time to live of the key
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // set a session token that expires in 30 minutes
+    /// db.set_with_ttl("session1", &String::from("token"), Duration::from_secs(30 * 60))?;
+    /// ```
+    /// 
+    pub fn set_with_ttl<V>(&mut self, key: &str, value: &V, ttl: Duration) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.set_value(key, value, Some(expiry_time(ttl)))
+    }
+
+    fn set_value<V>(&mut self, key: &str, value: &V, expires_at: Option<u64>) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        let ser_value = self.serialization_method.serialize_data(value)
+            .map_err(PickleDbError::Serialization)?;
+        self.set_serialized(key, SerializedValue(ser_value), expires_at)
+    }
+
+    fn set_serialized(&mut self, key: &str, value: SerializedValue, expires_at: Option<u64>) -> Result<(), PickleDbError> {
+        self.remove_key(key);
+        match expires_at {
+            Some(expires_at) => self.expiry.insert(String::from(key), expires_at),
+            None => self.expiry.remove(key),
+        };
+        self.map.insert(String::from(key), value);
+        self.dumpdb(key)
+    }
+
+    /// Get a value of a key.
+    /// 
+    /// The key is always a string but the value can be of any type. It's the user's
+    /// responsibility to know the value type and give it while calling this method.
+    /// If the key doesn't exist or if the type is wrong, `None` will be returned.
+    /// Otherwise `Some(V)` will be returned.
+    /// Since the values are stored in a serialized way the returned object is
+    /// not a reference to the value stored in a DB but actually a new instance of it.
+    /// In order to tell a missing key apart from a wrong type please use [try_get()](#method.try_get)
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - a string key
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // read a num
+    /// let num = db.get::<i32>("key1").unwrap();
+    /// 
+    /// // read a floating point number
+    /// let float_num = db.get::<f32>("key2").unwrap();
+    /// 
+    /// // read a String
+    /// let my_str = db.get::<String>("key3").unwrap();
+    /// 
+    /// // read a Vec
+    /// ley vec = db.get::<Vec<i32>>("key4").unwrap();
+    /// 
+    /// // read a struct
+    /// let coor = db.get::<Coor>("key5").unwrap();
+    /// ```
+    /// 
+    pub fn get<V>(&self, key: &str) -> Option<V> 
+        where 
+            V: DeserializeOwned
+    {
+        self.try_get(key).ok().flatten()
+    }
+
+    /// Get a value of a key, telling a missing key apart from a wrong type.
+    /// 
+    /// This method is similar to [get()](#method.get) but it reports why a value couldn't be retrieved:
+    /// * If the key doesn't exist `Ok(None)` is returned
+    /// * If the key holds a list rather than a value [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    ///   is returned
+    /// * If the value can't be deserialized to `V`, which usually means the value is of a different type,
+    ///   [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with the
+    ///   key name and the deserialization error
+    /// 
+    /// Otherwise `Ok(Some(V))` is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - a string key
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.set("key1", &100)?;
+    /// 
+    /// // read a num
+    /// assert_eq!(db.try_get::<i32>("key1")?, Some(100));
+    /// 
+    /// // read a key that doesn't exist
+    /// assert_eq!(db.try_get::<i32>("key2")?, None);
+    /// 
+    /// // read a num as a String
+    /// assert!(db.try_get::<String>("key1").is_err());
+    /// ```
+    /// 
+    pub fn try_get<V>(&self, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        match self.get_value_ref(key) {
+            Some(value) => self.serialization_method.deserialize_data::<V>(&value.0)
+                .map(Some)
+                .map_err(|message| PickleDbError::Deserialization { key: String::from(key), message }),
+            None if self.exists(key) => Err(PickleDbError::WrongType(String::from(key))),
+            None => Ok(None),
+        }
+    }
+
+    /// Check if a key exists.
+    /// 
+    /// This method returns `true` if the key exists and `false` otherwise.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key to check
+    /// 
+    pub fn exists(&self, key: &str) -> bool {
+        (self.map.contains_key(key) || self.list_map.contains_key(key) || self.dict_map.contains_key(key)
+            || self.set_map.contains_key(key) || self.sorted_set_map.contains_key(key))
+            && !self.is_expired(key)
+    }
+
+    /// Get a vector of all the keys in the DB.
+    /// 
+    /// The keys returned in the vector are not references to the actual key string
+    /// objects but rather a clone of them. If the DB has ordered keys (please see
+    /// [with_ordered_keys()](#method.with_ordered_keys)) the keys are sorted.
+    /// 
+    pub fn get_all(&self) -> Vec<String> {
+        let keys = self.map.keys()
+            .chain(self.list_map.keys())
+            .chain(self.dict_map.keys())
+            .chain(self.set_map.keys())
+            .chain(self.sorted_set_map.keys());
+        if self.map.is_ordered() {
+            return self.sorted_keys(keys);
+        }
+        keys.filter(|key| !self.is_expired(key))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the keys in a range, sorted.
+    /// 
+    /// This method returns the keys of values, lists, dicts, sets and sorted sets that are within the given range of
+    /// strings, for example `db.range("a".."c")` or `db.range("user:100"..)`. Keys are compared by their bytes, like `str` is.
+    /// The range can be used with any DB, but only a DB with ordered keys (please see
+    /// [with_ordered_keys()](#method.with_ordered_keys)) finds the keys without going over all the keys in the DB.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `range` - the range of keys to return
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let mut db = PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_ordered_keys();
+    /// db.set("a", &1)?;
+    /// db.set("b", &2)?;
+    /// db.lcreate("c")?;
+    /// 
+    /// assert_eq!(db.range("a".."c"), vec!["a", "b"]);
+    /// assert_eq!(db.range("b"..), vec!["b", "c"]);
+    /// ```
+    pub fn range<'r, R>(&self, range: R) -> Vec<String>
+        where
+            R: RangeBounds<&'r str>
+    {
+        let (start, end) = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.sorted_keys(self.map.range_keys(start, end)
+            .chain(self.list_map.range_keys(start, end))
+            .chain(self.dict_map.range_keys(start, end))
+            .chain(self.set_map.range_keys(start, end))
+            .chain(self.sorted_set_map.range_keys(start, end)))
+    }
+
+    /// Get the keys that start with a prefix, sorted.
+    /// 
+    /// This method is useful for keys that are namespaced, for example `db.scan_prefix("session:")` returns all
+    /// keys of values, lists, dicts, sets and sorted sets that look like `session:<id>`. Like [range()](#method.range),
+    /// only a DB with ordered keys finds the keys without going over all the keys in the DB.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `prefix` - the prefix of the keys to return
+    /// 
+    pub fn scan_prefix(&self, prefix: &str) -> Vec<String> {
+        let start = Bound::Included(prefix);
+        let has_prefix = |key: &&String| key.starts_with(prefix);
+        self.sorted_keys(self.map.range_keys(start, Bound::Unbounded).take_while(has_prefix)
+            .chain(self.list_map.range_keys(start, Bound::Unbounded).take_while(has_prefix))
+            .chain(self.dict_map.range_keys(start, Bound::Unbounded).take_while(has_prefix))
+            .chain(self.set_map.range_keys(start, Bound::Unbounded).take_while(has_prefix))
+            .chain(self.sorted_set_map.range_keys(start, Bound::Unbounded).take_while(has_prefix)))
+    }
+
+    /// Get the total number of keys in the DB.
+    /// 
+    pub fn total_keys(&self) -> usize {
+        self.map.keys()
+            .chain(self.list_map.keys())
+            .chain(self.dict_map.keys())
+            .chain(self.set_map.keys())
+            .chain(self.sorted_set_map.keys())
+            .filter(|key| !self.is_expired(key))
+            .count()
+    }
+
+    /// Remove a key-value pair, a list, a dict, a set or a sorted set from the DB.
+    /// 
+    /// This methods returns `Ok(true)` if the key was found in the DB or `Ok(false)` if it wasn't found.
+    /// An error is returned if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key or the name of the list, dict, set or sorted set to remove
+    /// 
+    pub fn rem(&mut self, key: &str) -> Result<bool, PickleDbError> {
+        self.remove_if_expired(key);
+        // nothing changed, so there's nothing to dump
+        if !self.remove_key(key) {
+            return Ok(false);
+        }
+        self.expiry.remove(key);
+        self.dumpdb(key)?;
+        Ok(true)
+    }
+
+    /// Set a time to live for an existing key or list.
+    /// 
+    /// Once `ttl` has passed the key expires and it's no longer visible, as if it was removed.
+    /// If the key already has a TTL it's replaced by the new one.
+    /// This method returns `Ok(true)` if the key was found in the DB or `Ok(false)` if it wasn't found.
+    /// An error is returned if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key or list name
+    /// * `ttl` - the time to live of the key
+    /// 
+    pub fn expire(&mut self, key: &str, ttl: Duration) -> Result<bool, PickleDbError> {
+        if !self.exists(key) {
+            return Ok(false);
+        }
+        self.expiry.insert(String::from(key), expiry_time(ttl));
+        self.dumpdb(key)?;
+        Ok(true)
+    }
+
+    /// Get the remaining time to live of a key or list.
+    /// 
+    /// This method returns `Ok(Some(Duration))` with the time left until the key expires, `Ok(None)` if the key
+    /// doesn't have a TTL, or [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound) if the key
+    /// doesn't exist (or has already expired).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key or list name
+    /// 
+    pub fn ttl(&self, key: &str) -> Result<Option<Duration>, PickleDbError> {
+        if !self.exists(key) {
+            return Err(PickleDbError::KeyNotFound(String::from(key)));
+        }
+        Ok(self.expiry.get(key).map(|expires_at| Duration::from_millis(expires_at.saturating_sub(now_millis()))))
+    }
+
+    /// Remove the time to live of a key or list, so it never expires.
+    /// 
+    /// This method returns `Ok(true)` if the key had a TTL that was removed, or `Ok(false)` if the key doesn't
+    /// exist or doesn't have a TTL. An error is returned if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key or list name
+    /// 
+    pub fn persist(&mut self, key: &str) -> Result<bool, PickleDbError> {
+        self.remove_if_expired(key);
+        if self.expiry.remove(key).is_none() {
+            return Ok(false);
+        }
+        self.dumpdb(key)?;
+        Ok(true)
+    }
+
+    /// Increment the integer value of a key by one.
+    /// 
+    /// Please see [incr_by()](#method.incr_by) for more details.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key of the counter
+    /// 
+    pub fn incr(&mut self, key: &str) -> Result<i64, PickleDbError> {
+        self.incr_by(key, 1)
+    }
+
+    /// Decrement the integer value of a key by one.
+    /// 
+    /// Please see [incr_by()](#method.incr_by) for more details.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key of the counter
+    /// 
+    pub fn decr(&mut self, key: &str) -> Result<i64, PickleDbError> {
+        self.incr_by(key, -1)
+    }
+
+    /// Increment the integer value of a key.
+    /// 
+    /// The value is read as an `i64`, incremented and stored back in a single step, like Redis' `INCRBY`.
+    /// If the key doesn't exist it's created with the value 0 before it's incremented. The TTL of the key, if
+    /// it has one, is kept. This method returns the new value, or
+    /// [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) if the key holds a list, a dict or a
+    /// value that isn't an integer, or [PickleDbError::Overflow](enum.PickleDbError.html#variant.Overflow) if the
+    /// new value doesn't fit in an `i64`. In both cases the value isn't changed.
+    /// Please note that binary serialization methods like Bincode don't store the type of a value, so a value of
+    /// another type that has the same size as an `i64` may be read as an integer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key of the counter
+    /// * `by` - the amount to add to the value, which may be negative
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// assert_eq!(db.incr_by("counter", 10)?, 10);
+    /// assert_eq!(db.incr("counter")?, 11);
+    /// assert_eq!(db.decr("counter")?, 10);
+    /// assert_eq!(db.get::<i64>("counter"), Some(10));
+    /// ```
+    pub fn incr_by(&mut self, key: &str, by: i64) -> Result<i64, PickleDbError> {
+        self.update_number(key, |value: i64| value.checked_add(by))
+    }
+
+    /// Increment the floating point value of a key.
+    /// 
+    /// This method is similar to [incr_by()](#method.incr_by) but the value is read as an `f64`, so it can be
+    /// either an integer or a floating point number, and it's stored back as an `f64`, like Redis' `INCRBYFLOAT`.
+    /// [PickleDbError::Overflow](enum.PickleDbError.html#variant.Overflow) is returned if the new value isn't a finite number.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - the key of the counter
+    /// * `by` - the amount to add to the value, which may be negative
+    /// 
+    pub fn incr_by_float(&mut self, key: &str, by: f64) -> Result<f64, PickleDbError> {
+        self.update_number(key, |value: f64| Some(value + by).filter(|new_value| new_value.is_finite()))
+    }
+
+    /// Read a number from a key, 0 if it doesn't exist, and store the number `update` returns, keeping the TTL of the key
+    fn update_number<N, F>(&mut self, key: &str, update: F) -> Result<N, PickleDbError>
+        where
+            N: Serialize + DeserializeOwned + Default,
+            F: FnOnce(N) -> Option<N>
+    {
+        self.remove_if_expired(key);
+        if self.list_map.contains_key(key) || self.dict_map.contains_key(key) || self.set_map.contains_key(key)
+            || self.sorted_set_map.contains_key(key) {
+            return Err(PickleDbError::WrongType(String::from(key)));
+        }
+        let value = match self.map.get(key) {
+            Some(value) => self.serialization_method.deserialize_data::<N>(&value.0)
+                .map_err(|_| PickleDbError::WrongType(String::from(key)))?,
+            None => N::default(),
+        };
+        let new_value = update(value).ok_or_else(|| PickleDbError::Overflow(String::from(key)))?;
+        let ser_value = self.serialization_method.serialize_data(&new_value).map_err(PickleDbError::Serialization)?;
+        self.map.insert(String::from(key), SerializedValue(ser_value));
+        self.dumpdb(key)?;
+        Ok(new_value)
+    }
+
+    /// Create a new list.
+    /// 
+    /// This method just creates a new list, it doesn't add any elements to it.
+    /// For adding elements to the list please call [ladd()](#method.ladd) or [lextend()](#method.lextend).
+    /// If another list, dict or value is already set under this key, they will be overridden,
+    /// meaning the new list will override the old list, dict or value.
+    /// The method returns an object of type `PickleDbListExtender` that enables to add items 
+    /// to the newly created list, or an error if dumping the change failed
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the key of the list that will be created
+    /// 
+    pub fn lcreate(&mut self, name: &str) -> Result<PickleDbListExtender<'_>, PickleDbError> {
+        let new_list: Vec<SerializedValue> = Vec::new();
+        self.remove_key(name);
+        self.expiry.remove(name);
+        self.list_map.insert(String::from(name), new_list);
+        self.dumpdb(name)?;
+        Ok(PickleDbListExtender { db: self, list_name: String::from(name) })
+    }
+
+    /// Check if a list exists.
+    /// 
+    /// This method returns `true` if the list name exists and `false` otherwise.
+    /// The difference between this method and [exists()](#method.exists) is that this methods checks only
+    /// for lists with that name (key) and [exists()](#method.exists) checks for values, lists, dicts, sets and sorted sets.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key to check
+    /// 
+    pub fn lexists(&self, name: &str) -> bool {
+        self.get_list_ref(name).is_some()
+    }
+
+    /// Add a single item to an existing list.
+    /// 
+    /// As mentioned before, the lists are heterogeneous, meaning a single list can contain 
+    /// items of different types. That means that the item can be of any type that is serializable.
+    /// That includes all primitive types, vectors, tuples and every struct that has the 
+    /// `#[derive(Serialize, Deserialize)` attribute.
+    /// The method returns a `PickleDbListExtender` object that enables to add more items to the list
+    /// if the item was added successfully. Otherwise it returns [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// if the list name isn't found in the DB, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    /// if the name holds a value rather than a list, or an error if the item couldn't be serialized or dumped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `value` - a reference of the item to add to the list
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // create a new list
+    /// db.lcreate("list1")?;
+    /// 
+    /// // add items of different types to the list
+    /// db.ladd("list1", &100)?
+    ///   .ladd(&String::from("my string"))?
+    ///   .ladd(&vec!["aa", "bb", "cc"])?;
+    /// ```
+    /// 
+    pub fn ladd<V>(&mut self, name: &str, value: &V) -> Result<PickleDbListExtender<'_>, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.lextend(name, &[value])
+    }
+
+    /// Add multiple items to an existing list.
+    /// 
+    /// As mentioned before, the lists are heterogeneous, meaning a single list can contain 
+    /// items of different types. That means that the item can be of any type that is serializable.
+    /// That includes all primitive types, vectors, tuples and every struct that has the 
+    /// `#[derive(Serialize, Deserialize)` attribute.
+    /// This method adds multiple items to the list, but since they're in a vector that means all
+    /// of them are of the same type. Of course it doesn't mean that the list cannot contain items
+    /// of other types as well, as you can see in the example below.
+    /// The method return a `PickleDbListExtender` that enables to add more items to the list 
+    /// if all items were added successfully. Otherwise it returns [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// if the list name isn't found in the DB, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    /// if the name holds a value rather than a list, or an error if the items couldn't be serialized or dumped.
+    /// If one of the items can't be serialized none of the items is added.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `seq` - a vector containing the new items to add to the list
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // create a new list
+    /// db.lcreate("list1")?;
+    /// 
+    /// // add a bunch of numbers to the list
+    /// db.lextend("list1", &vec![100, 200, 300])?
+    /// 
+    /// // add a String item to the list
+    ///   .ladd(&String::from("my string"))?
+    /// 
+    /// // add a vector item to the list
+    ///   .ladd(&vec!["aa", "bb", "cc"])?;
+    /// 
+    /// // now the list contains 5 items and looks like this: [100, 200, 300, "my string", ["aa, "bb", "cc"]]
+    /// ```
+    /// 
+    pub fn lextend<V>(&mut self, name: &str, seq: &[V]) -> Result<PickleDbListExtender<'_>, PickleDbError>
+        where
+            V: Serialize
+    {
+        let ser_method = self.serialization_method;
+        let serialized = seq.iter()
+            .map(|x| ser_method.serialize_data(x).map(SerializedValue))
+            .collect::<Result<Vec<SerializedValue>, String>>()
+            .map_err(PickleDbError::Serialization)?;
+        self.lextend_serialized(name, serialized)?;
+        Ok(PickleDbListExtender { db: self, list_name: String::from(name)})
+    }
+
+    fn lextend_serialized(&mut self, name: &str, serialized: Vec<SerializedValue>) -> Result<(), PickleDbError> {
+        self.get_list_mut(name)?.extend(serialized);
+        self.dumpdb(name)
+    }
+
+    /// Get an item of of a certain list in a certain position.
+    /// 
+    /// This method takes a list name and a position inside the list 
+    /// and retrieves the item in this position. It's the user's responsibility 
+    /// to know what is the correct type of the item and give it while calling this method.
+    /// Since the item in the lists are stored in a serialized way the returned object 
     /// is not a reference to the item stored in a DB but actually a new instance of it.
     /// If the list is not found in the DB or the given position is out of bounds
     /// of the list `None` will be returned. Otherwise `Some(V)` will be returned.
@@ -624,240 +2357,1637 @@ impl PickleDb {
     /// # Arguments
     /// 
     /// * `name` - the list key
-    /// * `pos` - the position of the item inside the list. Expected value is >= 0
+    /// * `pos` - the position of the item inside the list. Expected value is >= 0
+    /// 
+    /// # Examples
+    /// ```rust,ignore
+    /// // create a list
+    /// db.lcreate("list1");
+    /// 
+    /// // add a number to list1
+    /// db.ladd("list1", &100));
+    /// 
+    /// // add a string to list1
+    /// db.ladd("list1", &String::from("my string"));
+    /// 
+    /// // read the first item in the list - int
+    /// let x = db.lget::<i32>("list1", 0).unwrap();
+    /// 
+    /// // read the second item in the list - string
+    /// let s = db.lget::<String>("list1", 1).unwrap();
+    /// ```
+    pub fn lget<V>(&self, name: &str, pos: usize) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.try_lget(name, pos).ok().flatten()
+    }
+
+    /// Get an item of of a certain list in a certain position, telling a missing item apart from a wrong type.
+    /// 
+    /// This method is similar to [lget()](#method.lget) but it reports why an item couldn't be retrieved:
+    /// * If the list doesn't exist or the given position is out of bounds of the list `Ok(None)` is returned
+    /// * If the name holds a value rather than a list [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    ///   is returned
+    /// * If the item can't be deserialized to `V`, which usually means the item is of a different type,
+    ///   [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with the
+    ///   list name and the deserialization error
+    /// 
+    /// Otherwise `Ok(Some(V))` is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `pos` - the position of the item inside the list. Expected value is >= 0
+    /// 
+    pub fn try_lget<V>(&self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        match self.get_list_ref(name) {
+            Some(list) => match list.get(pos) {
+                Some(value) => self.serialization_method.deserialize_data::<V>(&value.0)
+                    .map(Some)
+                    .map_err(|message| PickleDbError::Deserialization { key: String::from(name), message }),
+                None => Ok(None),
+            }
+            None if self.exists(name) => Err(PickleDbError::WrongType(String::from(name))),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the length of a list.
+    /// 
+    /// If the list is empty or if it doesn't exist the value of 0 is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// 
+    pub fn llen(&self, name: &str) -> usize {
+        match self.get_list_ref(name) {
+            Some(list) => list.len(),
+            None => 0
+        }
+    }
+
+    /// Remove a list.
+    /// 
+    /// This method is somewhat similar to [rem()](#method.rem) but with 2 small differences:
+    /// * This method only removes lists and not key-value pairs
+    /// * The return value of this method is the number of items that were in 
+    ///   the list that was removed. If the list doesn't exist a value of 0 is
+    ///   returned
+    /// 
+    /// An error is returned if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key to remove
+    /// 
+    pub fn lrem_list(&mut self, name: &str) -> Result<usize, PickleDbError> {
+        // an expired list is like a missing one, so removing it isn't a change
+        self.remove_if_expired(name);
+        let res = self.llen(name);
+        if self.list_map.remove(name).is_none() {
+            return Ok(0);
+        }
+        self.expiry.remove(name);
+        self.dumpdb(name)?;
+        Ok(res)
+    }
+
+    /// Pop an item out of a list.
+    /// 
+    /// This method takes a list name and a position inside the list, removes the
+    /// item in this position and returns it to the user. It's the user's responsibility 
+    /// to know what is the correct type of the item and give it while calling this method.
+    /// Since the item in the lists are stored in a serialized way the returned object 
+    /// is not a reference to the item stored in a DB but actually a new instance of it.
+    /// If the given position is out of bounds no item will be removed and `Ok(None)` will be returned.
+    /// Otherwise the item will be removed and `Ok(Some(V))` will be returned.
+    /// If the list is not found in the DB [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// is returned, and if the item can't be deserialized to `V`
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned and the item
+    /// isn't removed.
+    /// This method is very similar to [lrem_value()](#method.lrem_value), the only difference is that this 
+    /// methods returns the value and [lrem_value()](#method.lrem_value) returns only an indication whether
+    /// the item was removed or not.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `pos` - the position of the item to remove
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // create a list
+    /// db.lcreate("list1");
+    /// 
+    /// // add 4 items to the list
+    /// db.lextend("list1", &vec![1,2,3,4])?;
+    /// 
+    /// // remove item in position 2
+    /// let item2 = db.lpop::<i32>("list1", 2)?;
+    /// 
+    /// // item2 contains Some(3) and the list now looks like this: [1, 2, 4]
+    /// 
+    /// // remove item in position 1
+    /// let item1 = db.lpop::<i32>("list1", 1)?;
+    /// 
+    /// // item1 contains Some(2) and the list now looks like this: [1, 4]
+    /// ```
+    /// 
+    pub fn lpop<V>(&mut self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        let ser_method = self.serialization_method;
+        let list = self.get_list_mut(name)?;
+        let res = match list.get(pos) {
+            Some(value) => ser_method.deserialize_data(&value.0)
+                .map_err(|message| PickleDbError::Deserialization { key: String::from(name), message })?,
+            None => return Ok(None),
+        };
+        list.remove(pos);
+        self.dumpdb(name)?;
+        Ok(Some(res))
+    }
+
+    /// Remove an item out of a list.
+    /// 
+    /// This method takes a list name and a value, removes the first item in the list
+    /// that equals to this value and returns an indication whether the item was removed or not.
+    /// Items are compared by their serialized form.
+    /// If no item in the list equals to the value no item will be removed and `Ok(false)` will be
+    /// returned. Otherwise the item will be removed and `Ok(true)` will be returned.
+    /// If the list is not found in the DB [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// is returned.
+    /// This method is very similar to [lpop()](#method.lpop), the only difference is that this 
+    /// methods returns an indication and [lpop()](#method.lpop) returns the actual item that was removed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `value` - the item to remove
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // create a list
+    /// db.lcreate("list1")?;
+    /// 
+    /// // add 4 items to the list
+    /// db.lextend("list1", &vec![1,2,3,4])?;
+    /// 
+    /// // remove 2
+    /// db.lrem_value("list1", &2)?;
+    /// 
+    /// // The list now looks like this: [1, 3, 4]
+    /// 
+    /// // remove 3
+    /// db.lrem_value("list1", &3)?;
+    /// 
+    /// // The list now looks like this: [1, 4]
+    /// ```
+    /// 
+    pub fn lrem_value<V>(&mut self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialization_method.serialize_data(&value)
+            .map(SerializedValue)
+            .map_err(PickleDbError::Serialization)?;
+        self.lrem_serialized(name, &serialized_value)
+    }
+
+    fn lrem_serialized(&mut self, name: &str, serialized_value: &SerializedValue) -> Result<bool, PickleDbError> {
+        let list = self.get_list_mut(name)?;
+        match list.iter().position(|x| x == serialized_value) {
+            Some(pos) => {
+                list.remove(pos);
+                self.dumpdb(name)?;
+                Ok(true)
+            },
+
+            None => Ok(false),
+        }
+    }
+
+    /// Add an item to the front of an existing list.
+    /// 
+    /// This method is similar to [ladd()](#method.ladd) but the item is added before the first item of the list,
+    /// like Redis' `LPUSH`. It returns the length of the list after the item was added, or the same errors as
+    /// [ladd()](#method.ladd).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `value` - a reference of the item to add to the list
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.lcreate("list1")?.lextend(&[2, 3])?;
+    /// 
+    /// // the list now looks like this: [1, 2, 3]
+    /// assert_eq!(db.lpush("list1", &1)?, 3);
+    /// ```
+    /// 
+    pub fn lpush<V>(&mut self, name: &str, value: &V) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.linsert(name, 0, value)
+    }
+
+    /// Insert an item into an existing list at a certain position.
+    /// 
+    /// The item is inserted before the item that is currently in position `index`, like Python's `list.insert()`.
+    /// A negative index counts from the end of the list, so -1 inserts the item before the last item.
+    /// An index that is out of bounds inserts the item at the front or at the back of the list.
+    /// This method returns the length of the list after the item was inserted, or the same errors as [ladd()](#method.ladd).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `index` - the position of the item after it's inserted
+    /// * `value` - a reference of the item to insert
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.lcreate("list1")?.lextend(&[1, 3])?;
+    /// 
+    /// // the list now looks like this: [1, 2, 3]
+    /// db.linsert("list1", 1, &2)?;
+    /// 
+    /// // the list now looks like this: [1, 2, 2.5, 3]
+    /// db.linsert("list1", -1, &2.5)?;
+    /// ```
+    /// 
+    pub fn linsert<V>(&mut self, name: &str, index: i64, value: &V) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.linsert_serialized(name, index, serialized_value)
+    }
+
+    fn linsert_serialized(&mut self, name: &str, index: i64, serialized_value: SerializedValue) -> Result<usize, PickleDbError> {
+        let list = self.get_list_mut(name)?;
+        let pos = if index < 0 {
+            list.len().saturating_sub(index.unsigned_abs() as usize)
+        } else {
+            list.len().min(index as usize)
+        };
+        list.insert(pos, serialized_value);
+        let len = list.len();
+        self.dumpdb(name)?;
+        Ok(len)
+    }
+
+    /// Insert an item into an existing list before another item.
+    /// 
+    /// This method finds the first item in the list that equals to `pivot` and inserts the item before it,
+    /// like Redis' `LINSERT BEFORE`. Items are compared by their serialized form.
+    /// It returns `Ok(true)` if the item was inserted or `Ok(false)` if no item equals to `pivot`,
+    /// otherwise it returns the same errors as [ladd()](#method.ladd).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `pivot` - the item to insert the item before
+    /// * `value` - a reference of the item to insert
+    /// 
+    pub fn linsert_before<P, V>(&mut self, name: &str, pivot: &P, value: &V) -> Result<bool, PickleDbError>
+        where
+            P: Serialize,
+            V: Serialize
+    {
+        let (serialized_pivot, serialized_value) = (self.serialize_item(pivot)?, self.serialize_item(value)?);
+        self.linsert_pivot_serialized(name, &serialized_pivot, serialized_value, false)
+    }
+
+    /// Insert an item into an existing list after another item.
+    /// 
+    /// This method is similar to [linsert_before()](#method.linsert_before) but the item is inserted after the
+    /// first item that equals to `pivot`, like Redis' `LINSERT AFTER`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `pivot` - the item to insert the item after
+    /// * `value` - a reference of the item to insert
+    /// 
+    pub fn linsert_after<P, V>(&mut self, name: &str, pivot: &P, value: &V) -> Result<bool, PickleDbError>
+        where
+            P: Serialize,
+            V: Serialize
+    {
+        let (serialized_pivot, serialized_value) = (self.serialize_item(pivot)?, self.serialize_item(value)?);
+        self.linsert_pivot_serialized(name, &serialized_pivot, serialized_value, true)
+    }
+
+    fn linsert_pivot_serialized(&mut self, name: &str, pivot: &SerializedValue, serialized_value: SerializedValue, after: bool) -> Result<bool, PickleDbError> {
+        let list = self.get_list_mut(name)?;
+        match list.iter().position(|x| x == pivot) {
+            Some(pos) => {
+                list.insert(if after { pos + 1 } else { pos }, serialized_value);
+                self.dumpdb(name)?;
+                Ok(true)
+            },
+            None => Ok(false),
+        }
+    }
+
+    /// Replace an item of an existing list.
+    /// 
+    /// This method replaces the item in position `index` with a new item, like Redis' `LSET`.
+    /// A negative index counts from the end of the list, so -1 is the last item.
+    /// It returns `Ok(true)` if the item was replaced or `Ok(false)` if the index is out of bounds,
+    /// otherwise it returns the same errors as [ladd()](#method.ladd).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `index` - the position of the item to replace
+    /// * `value` - a reference of the new item
+    /// 
+    pub fn lset<V>(&mut self, name: &str, index: i64, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.lset_serialized(name, index, serialized_value)
+    }
+
+    fn lset_serialized(&mut self, name: &str, index: i64, serialized_value: SerializedValue) -> Result<bool, PickleDbError> {
+        let list = self.get_list_mut(name)?;
+        match list_index(list.len(), index) {
+            Some(pos) => {
+                list[pos] = serialized_value;
+                self.dumpdb(name)?;
+                Ok(true)
+            },
+            None => Ok(false),
+        }
+    }
+
+    /// Get an item of a list by its index.
+    /// 
+    /// This method is similar to [lget()](#method.lget) but like Redis' `LINDEX` a negative index counts from the end
+    /// of the list, so -1 is the last item.
+    /// If the list is not found in the DB or the given index is out of bounds of the list `None` will be returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `index` - the position of the item
+    /// 
+    pub fn lindex<V>(&self, name: &str, index: i64) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        let list = self.get_list_ref(name)?;
+        let pos = list_index(list.len(), index)?;
+        self.serialization_method.deserialize_data(&list[pos].0).ok()
+    }
+
+    /// Get a range of items of a list.
+    /// 
+    /// This method returns the items from position `start` to position `end`, including both, like Redis' `LRANGE`.
+    /// A negative index counts from the end of the list, so `db.lrange("list1", 0, -1)` returns all of the items.
+    /// Indexes that are out of bounds are limited to the bounds of the list, and if `start` is after `end` no items are returned.
+    /// All of the items in the range should be of type `V`.
+    /// This method returns `Ok(vec![])` if the list doesn't exist, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    /// if the name holds a value or a dict, or [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization)
+    /// if one of the items can't be deserialized to `V`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `start` - the position of the first item
+    /// * `end` - the position of the last item
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.lcreate("list1")?.lextend(&[1, 2, 3, 4])?;
+    /// 
+    /// assert_eq!(db.lrange::<i32>("list1", 1, 2)?, vec![2, 3]);
+    /// assert_eq!(db.lrange::<i32>("list1", -2, -1)?, vec![3, 4]);
+    /// ```
+    /// 
+    pub fn lrange<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        let list = match self.get_list_ref(name) {
+            Some(list) => list,
+            None if self.exists(name) => return Err(PickleDbError::WrongType(String::from(name))),
+            None => return Ok(Vec::new()),
+        };
+        list[list_range(list.len(), start, end)].iter()
+            .map(|value| self.serialization_method.deserialize_data(&value.0)
+                .map_err(|message| PickleDbError::Deserialization { key: String::from(name), message }))
+            .collect()
+    }
+
+    /// Trim an existing list to a range of items.
+    /// 
+    /// This method keeps only the items from position `start` to position `end`, including both, and removes
+    /// all other items, like Redis' `LTRIM`. The indexes are treated like in [lrange()](#method.lrange).
+    /// It returns the number of items that were removed, or [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// if the list name isn't found in the DB, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    /// if the name holds a value or a dict, or an error if the change couldn't be dumped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `start` - the position of the first item to keep
+    /// * `end` - the position of the last item to keep
+    /// 
+    pub fn ltrim(&mut self, name: &str, start: i64, end: i64) -> Result<usize, PickleDbError> {
+        let list = self.get_list_mut(name)?;
+        let range = list_range(list.len(), start, end);
+        let removed = list.len() - range.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+        list.truncate(range.end);
+        list.drain(..range.start);
+        self.dumpdb(name)?;
+        Ok(removed)
+    }
+
+    /// Remove several items that equal to a value out of a list.
+    /// 
+    /// This method is similar to [lrem_value()](#method.lrem_value) but it may remove more than one item, like Redis' `LREM`:
+    /// * If `count` is positive, up to `count` items are removed starting from the front of the list
+    /// * If `count` is negative, up to `-count` items are removed starting from the back of the list
+    /// * If `count` is 0, all of the items that equal to the value are removed
+    /// 
+    /// It returns the number of items that were removed, or [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// if the list is not found in the DB.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list key
+    /// * `value` - the item to remove
+    /// * `count` - the maximal number of items to remove and the direction to remove them in
+    /// 
+    pub fn lrem_value_all<V>(&mut self, name: &str, value: &V, count: i64) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.lrem_all_serialized(name, &serialized_value, count)
+    }
+
+    fn lrem_all_serialized(&mut self, name: &str, serialized_value: &SerializedValue, count: i64) -> Result<usize, PickleDbError> {
+        let list = self.get_list_mut(name)?;
+        let limit = if count == 0 { usize::MAX } else { count.unsigned_abs() as usize };
+        let mut positions: Vec<usize> = list.iter().enumerate()
+            .filter(|(_, x)| *x == serialized_value)
+            .map(|(pos, _)| pos)
+            .collect();
+        if count < 0 {
+            positions.reverse();
+        }
+        positions.truncate(limit);
+        if positions.is_empty() {
+            return Ok(0);
+        }
+        positions.sort_unstable();
+        for pos in positions.iter().rev() {
+            list.remove(*pos);
+        }
+        self.dumpdb(name)?;
+        Ok(positions.len())
+    }
+
+    /// Move an item from one list to another.
+    /// 
+    /// This method pops an item from one end of the source list and adds it to one end of the destination list,
+    /// like Redis' `LMOVE`. The source and the destination may be the same list, which rotates it.
+    /// Both lists are changed at once, so the item can't get lost if the change is dumped in between, which makes
+    /// this method useful for moving work items from a queue to a list of items in progress.
+    /// It returns the item that was moved, or `Ok(None)` if the source list is empty.
+    /// If one of the lists is not found in the DB [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// is returned, and if the item can't be deserialized to `V`
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned and the item isn't moved.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `source` - the key of the list to move the item from
+    /// * `destination` - the key of the list to move the item to
+    /// * `from` - the end of the source list to pop the item from
+    /// * `to` - the end of the destination list to add the item to
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// use pickledb::ListEnd;
+    /// 
+    /// db.lcreate("queue")?.lextend(&[1, 2, 3])?;
+    /// db.lcreate("in_progress")?;
+    /// 
+    /// // take the first job from the queue
+    /// let job = db.lmove::<i32>("queue", "in_progress", ListEnd::Front, ListEnd::Back)?;
+    /// 
+    /// // job contains Some(1), queue looks like this: [2, 3] and in_progress looks like this: [1]
+    /// ```
+    /// 
+    pub fn lmove<V>(&mut self, source: &str, destination: &str, from: ListEnd, to: ListEnd) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.get_list_mut(destination)?;
+        let ser_method = self.serialization_method;
+        let source_list = self.get_list_mut(source)?;
+        let value = match from {
+            ListEnd::Front => source_list.first(),
+            ListEnd::Back => source_list.last(),
+        };
+        let res = match value {
+            Some(value) => ser_method.deserialize_data(&value.0)
+                .map_err(|message| PickleDbError::Deserialization { key: String::from(source), message })?,
+            None => return Ok(None),
+        };
+        let value = match from {
+            ListEnd::Front => source_list.remove(0),
+            ListEnd::Back => source_list.pop().unwrap(),
+        };
+        let destination_list = self.get_list_mut(destination)?;
+        match to {
+            ListEnd::Front => destination_list.insert(0, value),
+            ListEnd::Back => destination_list.push(value),
+        }
+        // rotating a list is a single change of it
+        if source == destination {
+            self.dumpdb_keys(&[source])?;
+        } else {
+            self.dumpdb_keys(&[source, destination])?;
+        }
+        Ok(Some(res))
+    }
+
+    fn serialize_item<V>(&self, value: &V) -> Result<SerializedValue, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.serialization_method.serialize_data(value)
+            .map(SerializedValue)
+            .map_err(PickleDbError::Serialization)
+    }
+
+    /// Return an iterator over the keys and values in the DB.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // iterate over all keys and values in the db
+    /// for kv in db.iter() {
+    ///     match kv.get_key() {
+    ///         "key1" => println!("Value of {} is: {}", kv.get_key(), kv.get_value::<String>().unwrap()),
+    ///         "key2" => println!("Value of {} is: {}", kv.get_key(), kv.get_value::<String>().unwrap()),
+    ///         "key3" => println!("Value of {} is: {:?}", kv.get_key(), kv.get_value::<Vec<i32>>().unwrap()),
+    ///         "key4" => println!("Value of {} is: {}", kv.get_key(), kv.get_value::<Rectangle>().unwrap()),
+    ///         _ => ()
+    ///     }
+    /// }
+    /// ```
+    /// 
+    pub fn iter(&self) -> PickleDbIterator<'_> {
+        PickleDbIterator {
+            map_iter: self.map.iter(),
+            expiry: &self.expiry,
+            now: now_millis(),
+            ser_method: self.serialization_method,
+        }
+    }
+
+    /// Return an iterator over the items in certain list.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the list name. If the list doesn't exist [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    ///   is returned
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // create a new list
+    /// db.lcreate("list1")?
+    ///   .lextend(&vec![1,2,3,4])?;
+    /// 
+    /// // iterate over the items in list1
+    /// for item_iter in db.liter("list1")? {
+    ///     println!("Current item is: {}", item_iter.get_item::<i32>().unwrap());
+    /// }
+    /// ```
+    /// 
+    pub fn liter<'a>(&'a self, name: &'a str) -> Result<PickleDbListIterator<'a>, PickleDbError> {
+        match self.get_list_ref(name) {
+            Some(list) => Ok(PickleDbListIterator {
+                list_name: name,
+                list_iter: list.iter(),
+                ser_method: self.serialization_method,
+            }),
+            None => Err(self.missing_key_error(name)),
+        }
+    }
+
+    /// Create a new dict.
+    /// 
+    /// This method just creates a new empty dict, for adding key-value pairs to it please call [dadd()](#method.dadd).
+    /// Like values in the DB, dicts are heterogeneous: the keys of a dict are strings but each value can be of a
+    /// different type.
+    /// If another dict, list or value is already set under this name, they will be overridden,
+    /// meaning the new dict will override the old dict, list or value.
+    /// An error is returned if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the key of the dict that will be created
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // create a new dict
+    /// db.dcreate("dict1")?;
+    /// 
+    /// // add values of different types to the dict
+    /// db.dadd("dict1", "num", &100)?;
+    /// db.dadd("dict1", "str", &String::from("my string"))?;
+    /// ```
+    /// 
+    pub fn dcreate(&mut self, name: &str) -> Result<(), PickleDbError> {
+        self.remove_key(name);
+        self.expiry.remove(name);
+        self.dict_map.insert(String::from(name), HashMap::new());
+        self.dumpdb(name)
+    }
+
+    /// Add a key-value pair to an existing dict.
+    /// 
+    /// The key has to be a string but the value can be of any type that is serializable.
+    /// If the key already exists in the dict its value is overridden.
+    /// This method returns [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound) if the dict
+    /// isn't found in the DB, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) if the name holds
+    /// a value or a list rather than a dict, or an error if the value couldn't be serialized or dumped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// * `key` - a string key inside the dict
+    /// * `value` - a value of any serializable type
+    /// 
+    pub fn dadd<V>(&mut self, name: &str, key: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.dadd_serialized(name, key, serialized_value)
+    }
+
+    fn dadd_serialized(&mut self, name: &str, key: &str, serialized_value: SerializedValue) -> Result<(), PickleDbError> {
+        self.get_dict_mut(name)?.insert(String::from(key), serialized_value);
+        self.dumpdb(name)
+    }
+
+    /// Get the value of a key inside a dict.
+    /// 
+    /// It's the user's responsibility to know the value type and give it while calling this method.
+    /// If the dict or the key inside it doesn't exist or if the type is wrong, `None` will be returned.
+    /// Otherwise `Some(V)` will be returned.
+    /// In order to tell a missing key apart from a wrong type please use [try_dget()](#method.try_dget)
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// * `key` - a string key inside the dict
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let num = db.dget::<i32>("dict1", "num").unwrap();
+    /// ```
+    /// 
+    pub fn dget<V>(&self, name: &str, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.try_dget(name, key).ok().flatten()
+    }
+
+    /// Get the value of a key inside a dict, telling a missing key apart from a wrong type.
+    /// 
+    /// This method is similar to [dget()](#method.dget) but it reports why a value couldn't be retrieved:
+    /// * If the dict or the key inside it doesn't exist `Ok(None)` is returned
+    /// * If the name holds a value or a list rather than a dict [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType)
+    ///   is returned
+    /// * If the value can't be deserialized to `V`, which usually means the value is of a different type,
+    ///   [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned with the
+    ///   dict name and the deserialization error
+    /// 
+    /// Otherwise `Ok(Some(V))` is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// * `key` - a string key inside the dict
+    /// 
+    pub fn try_dget<V>(&self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        match self.get_dict_ref(name) {
+            Some(dict) => match dict.get(key) {
+                Some(value) => self.serialization_method.deserialize_data::<V>(&value.0)
+                    .map(Some)
+                    .map_err(|message| PickleDbError::Deserialization { key: String::from(name), message }),
+                None => Ok(None),
+            }
+            None if self.exists(name) => Err(PickleDbError::WrongType(String::from(name))),
+            None => Ok(None),
+        }
+    }
+
+    /// Get all the key-value pairs of a dict.
+    /// 
+    /// Since a map can't hold values of different types, this method is useful for dicts whose values
+    /// are all of the same type. For dicts with values of different types please use [diter()](#method.diter).
+    /// If the dict doesn't exist or if one of the values can't be deserialized to `V`, `None` will be returned.
+    /// Otherwise `Some(HashMap<String, V>)` will be returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// 
+    pub fn dgetall<V>(&self, name: &str) -> Option<HashMap<String, V>>
+        where
+            V: DeserializeOwned
+    {
+        self.get_dict_ref(name)?.iter()
+            .map(|(key, value)| self.serialization_method.deserialize_data::<V>(&value.0).ok().map(|value| (key.clone(), value)))
+            .collect()
+    }
+
+    /// Get the keys of a dict.
+    /// 
+    /// If the dict doesn't exist `None` will be returned. Otherwise `Some(Vec<String>)` with a clone
+    /// of the keys will be returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// 
+    pub fn dkeys(&self, name: &str) -> Option<Vec<String>> {
+        self.get_dict_ref(name).map(|dict| dict.keys().cloned().collect())
+    }
+
+    /// Get the values of a dict.
+    /// 
+    /// Like [dgetall()](#method.dgetall) this method is useful for dicts whose values are all of the same type.
+    /// If the dict doesn't exist or if one of the values can't be deserialized to `V`, `None` will be returned.
+    /// Otherwise `Some(Vec<V>)` will be returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// 
+    pub fn dvals<V>(&self, name: &str) -> Option<Vec<V>>
+        where
+            V: DeserializeOwned
+    {
+        self.get_dict_ref(name)?.values()
+            .map(|value| self.serialization_method.deserialize_data::<V>(&value.0).ok())
+            .collect()
+    }
+
+    /// Check if a key exists in a dict.
+    /// 
+    /// This method returns `true` if the dict exists and contains the key, and `false` otherwise.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// * `key` - the key to check
+    /// 
+    pub fn dexists(&self, name: &str, key: &str) -> bool {
+        self.get_dict_ref(name).is_some_and(|dict| dict.contains_key(key))
+    }
+
+    /// Get the number of key-value pairs in a dict.
+    /// 
+    /// If the dict is empty or if it doesn't exist the value of 0 is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// 
+    pub fn dlen(&self, name: &str) -> usize {
+        self.get_dict_ref(name).map_or(0, |dict| dict.len())
+    }
+
+    /// Pop a key-value pair out of a dict.
+    /// 
+    /// This method removes the key from the dict and returns its value. It's the user's responsibility
+    /// to know what is the correct type of the value and give it while calling this method.
+    /// If the key doesn't exist in the dict `Ok(None)` will be returned. Otherwise the key will be removed
+    /// and `Ok(Some(V))` will be returned.
+    /// If the dict is not found in the DB [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    /// is returned, and if the value can't be deserialized to `V`
+    /// [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization) is returned and the key
+    /// isn't removed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// * `key` - the key to remove
+    /// 
+    pub fn dpop<V>(&mut self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        let ser_method = self.serialization_method;
+        let dict = self.get_dict_mut(name)?;
+        let res = match dict.get(key) {
+            Some(value) => ser_method.deserialize_data(&value.0)
+                .map_err(|message| PickleDbError::Deserialization { key: String::from(name), message })?,
+            None => return Ok(None),
+        };
+        dict.remove(key);
+        self.dumpdb(name)?;
+        Ok(Some(res))
+    }
+
+    /// Remove a dict.
+    /// 
+    /// This method is somewhat similar to [rem()](#method.rem) but it only removes dicts, and it returns
+    /// the number of key-value pairs that were in the dict that was removed. If the dict doesn't exist
+    /// a value of 0 is returned.
+    /// An error is returned if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name
+    /// 
+    pub fn drem(&mut self, name: &str) -> Result<usize, PickleDbError> {
+        // an expired dict is like a missing one, so removing it isn't a change
+        self.remove_if_expired(name);
+        let res = self.dlen(name);
+        if self.dict_map.remove(name).is_none() {
+            return Ok(0);
+        }
+        self.expiry.remove(name);
+        self.dumpdb(name)?;
+        Ok(res)
+    }
+
+    /// Merge a dict into another dict.
+    /// 
+    /// All the key-value pairs of the dict `other` are added to the dict `name`, overriding the values of keys
+    /// that exist in both. The dict `other` isn't changed.
+    /// This method returns [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound) if one of the
+    /// dicts isn't found in the DB, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) if one of the
+    /// names holds a value or a list rather than a dict, or an error if dumping the change failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the name of the dict to merge into
+    /// * `other` - the name of the dict whose key-value pairs are merged
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// db.dcreate("dict1")?;
+    /// db.dadd("dict1", "a", &1)?;
+    /// db.dcreate("dict2")?;
+    /// db.dadd("dict2", "b", &2)?;
+    /// 
+    /// // dict1 now contains both "a" and "b"
+    /// db.dmerge("dict1", "dict2")?;
+    /// ```
+    /// 
+    pub fn dmerge(&mut self, name: &str, other: &str) -> Result<(), PickleDbError> {
+        let other_dict = match self.get_dict_ref(other) {
+            Some(dict) => dict.clone(),
+            None => return Err(self.missing_key_error(other)),
+        };
+        self.get_dict_mut(name)?.extend(other_dict);
+        self.dumpdb(name)
+    }
+
+    /// Return an iterator over the key-value pairs of a certain dict.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the dict name. If the dict doesn't exist [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    ///   is returned
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// // iterate over the key-value pairs of dict1
+    /// for kv in db.diter("dict1")? {
+    ///     println!("Value of {} is: {}", kv.get_key(), kv.get_value::<i32>().unwrap());
+    /// }
+    /// ```
+    /// 
+    pub fn diter<'a>(&'a self, name: &'a str) -> Result<PickleDbDictIterator<'a>, PickleDbError> {
+        match self.get_dict_ref(name) {
+            Some(dict) => Ok(PickleDbDictIterator {
+                dict_name: name,
+                dict_iter: dict.iter(),
+                ser_method: self.serialization_method,
+            }),
+            None => Err(self.missing_key_error(name)),
+        }
+    }
+
+    /// Add a member to a set.
+    /// 
+    /// If the set doesn't exist it's created, like Redis' `SADD`. Like lists, sets are heterogeneous and each member
+    /// can be of a different type. Members are compared by their serialized form, so a member is only added if no
+    /// member of the set serializes to the same data.
+    /// This method returns `true` if the member was added and `false` if it was already in the set, or
+    /// [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) if the name holds a value, a list or a dict
+    /// rather than a set, or an error if the member couldn't be serialized or dumped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the set name
+    /// * `value` - a reference of the member to add
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// assert!(db.sadd("set1", &1)?);
+    /// assert!(db.sadd("set1", &String::from("a string"))?);
+    /// 
+    /// // 1 is already in the set
+    /// assert!(!db.sadd("set1", &1)?);
+    /// ```
+    /// 
+    pub fn sadd<V>(&mut self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.sadd_serialized(name, serialized_value)
+    }
+
+    fn sadd_serialized(&mut self, name: &str, serialized_value: SerializedValue) -> Result<bool, PickleDbError> {
+        self.remove_if_expired(name);
+        if !self.set_map.contains_key(name) {
+            if self.exists(name) {
+                return Err(PickleDbError::WrongType(String::from(name)));
+            }
+            self.set_map.insert(String::from(name), HashSet::new());
+        }
+        if !self.set_map.get_mut(name).unwrap().insert(serialized_value) {
+            return Ok(false);
+        }
+        self.dumpdb(name)?;
+        Ok(true)
+    }
+
+    /// Remove a member from a set.
+    /// 
+    /// The member is compared by its serialized form, like in [lrem_value()](#method.lrem_value).
+    /// This method returns `true` if the member was removed and `false` if it wasn't in the set or if the set doesn't
+    /// exist. The set remains in the DB even if it becomes empty, please use [rem()](#method.rem) to remove it.
+    /// [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) is returned if the name holds a value,
+    /// a list or a dict rather than a set, or an error if the member couldn't be serialized or the change couldn't be dumped.
+    /// 
+    /// # Arguments
     /// 
-    /// # Examples
-    /// ```rust,ignore
-    /// // create a list
-    /// db.lcreate("list1");
+    /// * `name` - the set name
+    /// * `value` - a reference of the member to remove
     /// 
-    /// // add a number to list1
-    /// db.ladd("list1", &100));
+    pub fn srem<V>(&mut self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.srem_serialized(name, &serialized_value)
+    }
+
+    fn srem_serialized(&mut self, name: &str, serialized_value: &SerializedValue) -> Result<bool, PickleDbError> {
+        if self.try_get_set_ref(name)?.is_none() {
+            return Ok(false);
+        }
+        if !self.set_map.get_mut(name).unwrap().remove(serialized_value) {
+            return Ok(false);
+        }
+        self.dumpdb(name)?;
+        Ok(true)
+    }
+
+    /// Check if a member is in a set.
     /// 
-    /// // add a string to list1
-    /// db.ladd("list1", &String::from("my string"));
+    /// This method returns `true` if the set exists and contains a member that serializes to the same data as `value`,
+    /// and `false` otherwise.
     /// 
-    /// // read the first item in the list - int
-    /// let x = db.lget::<i32>("list1", 0).unwrap();
+    /// # Arguments
     /// 
-    /// // read the second item in the list - string
-    /// let s = db.lget::<String>("list1", 1).unwrap();
-    /// ```
-    pub fn lget<V>(&self, name: &str, pos: usize) -> Option<V>
+    /// * `name` - the set name
+    /// * `value` - a reference of the member to check
+    /// 
+    pub fn sismember<V>(&self, name: &str, value: &V) -> bool
         where
-            V: DeserializeOwned
+            V: Serialize
     {
-        match self.list_map.get(name) {
-            Some(list) => match list.get(pos) {
-                Some(val_as_string) => deserialize_data::<V>(&val_as_string),
-                None => None,
-            }
-            None => None,
+        match (self.get_set_ref(name), self.serialize_item(value)) {
+            (Some(set), Ok(serialized_value)) => set.contains(&serialized_value),
+            _ => false,
         }
     }
 
-    /// Get the length of a list.
+    /// Get all the members of a set.
     /// 
-    /// If the list is empty or if it doesn't exist the value of 0 is returned.
+    /// Like [dgetall()](#method.dgetall) this method is useful for sets whose members are all of the same type. For sets
+    /// with members of different types please use [siter()](#method.siter). The members are returned in an arbitrary order.
+    /// If the set doesn't exist an empty vector is returned.
+    /// [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) is returned if the name holds a value,
+    /// a list or a dict rather than a set, and [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization)
+    /// is returned if one of the members can't be deserialized to `V`.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key
+    /// * `name` - the set name
     /// 
-    pub fn llen(&self, name: &str) -> usize {
-        match self.list_map.get(name) {
-            Some(list) => list.len(),
-            None => 0
+    pub fn smembers<V>(&self, name: &str) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        match self.try_get_set_ref(name)? {
+            Some(set) => set.iter().map(|member| self.deserialize_member(name, member)).collect(),
+            None => Ok(Vec::new()),
         }
     }
 
-    /// Remove a list.
+    /// Get the number of members in a set.
     /// 
-    /// This method is somewhat similar to [rem()](#method.rem) but with 2 small differences:
-    /// * This method only removes lists and not key-value pairs
-    /// * The return value of this method is the number of items that were in 
-    ///   the list that was removed. If the list doesn't exist a value of 0 is
-    ///   returned
+    /// If the set is empty or if it doesn't exist the value of 0 is returned.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key to remove
+    /// * `name` - the set name
     /// 
-    pub fn lrem_list(&mut self, name: &str) -> usize {
-        let res = self.llen(name);
-        self.list_map.remove(name);
-        self.dumpdb();
-        res
+    pub fn scard(&self, name: &str) -> usize {
+        self.get_set_ref(name).map_or(0, |set| set.len())
     }
 
-    /// Pop an item out of a list.
+    /// Get the union of several sets.
     /// 
-    /// This method takes a list name and a position inside the list, removes the
-    /// item in this position and returns it to the user. It's the user's responsibility 
-    /// to know what is the correct type of the item and give it while calling this method.
-    /// Since the item in the lists are stored in a serialized way the returned object 
-    /// is not a reference to the item stored in a DB but actually a new instance of it.
-    /// If the list is not found in the DB or the given position is out of bounds
-    /// no item will be removed and `None` will be returned. Otherwise the item will be
-    /// removed and `Some(V)` will be returned.
-    /// This method is very similar to [lrem_value()](#method.lrem_value), the only difference is that this 
-    /// methods returns the value and [lrem_value()](#method.lrem_value) returns only an indication whether
-    /// the item was removed or not.
+    /// This method returns the members that are in at least one of the sets, like Redis' `SUNION`, in an arbitrary
+    /// order. Sets that don't exist are treated as empty sets.
+    /// [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) is returned if one of the names holds
+    /// a value, a list or a dict rather than a set, and [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization)
+    /// is returned if one of the members can't be deserialized to `V`.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key
-    /// * `pos` - the position of the item to remove
+    /// * `names` - the set names
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // create a list
-    /// db.lcreate("list1");
+    /// db.sadd("set1", &1)?;
+    /// db.sadd("set1", &2)?;
+    /// db.sadd("set2", &2)?;
+    /// db.sadd("set2", &3)?;
+    /// 
+    /// // [1, 2, 3] in an arbitrary order
+    /// let union = db.sunion::<i32>(&["set1", "set2"])?;
+    /// // [2]
+    /// let inter = db.sinter::<i32>(&["set1", "set2"])?;
+    /// // [1]
+    /// let diff = db.sdiff::<i32>(&["set1", "set2"])?;
+    /// ```
     /// 
-    /// // add 4 items to the list
-    /// db.lextend("list1", &vec![1,2,3,4]);
+    pub fn sunion<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        let sets = self.try_get_set_refs(names)?;
+        let mut seen = HashSet::new();
+        let mut res = Vec::new();
+        for (name, set) in sets {
+            for member in set.into_iter().flatten() {
+                if seen.insert(member) {
+                    res.push(self.deserialize_member(name, member)?);
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    /// Get the intersection of several sets.
     /// 
-    /// // remove item in position 2
-    /// let item2 = db.lpop::<i32>("list1", 2);
+    /// This method returns the members that are in all of the sets, like Redis' `SINTER`, in an arbitrary order.
+    /// Sets that don't exist are treated as empty sets, so if one of them doesn't exist the intersection is empty.
+    /// The same errors as in [sunion()](#method.sunion) are returned.
+    /// 
+    /// # Arguments
     /// 
-    /// // item2 contains 3 and the list now looks like this: [1, 2, 4]
+    /// * `names` - the set names
     /// 
-    /// // remove item in position 1
-    /// let item1 = db.lpop::<i32>("list1", 1);
+    pub fn sinter<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        let sets = self.try_get_set_refs(names)?;
+        let Some(((name, Some(first)), others)) = sets.split_first() else {
+            return Ok(Vec::new());
+        };
+        first.iter()
+            .filter(|member| others.iter().all(|(_, set)| set.is_some_and(|set| set.contains(*member))))
+            .map(|member| self.deserialize_member(name, member))
+            .collect()
+    }
+
+    /// Get the difference between a set and other sets.
     /// 
-    /// // item1 contains 2 and the list now looks like this: [1, 4]
-    /// ```
+    /// This method returns the members of the first set that aren't in any of the other sets, like Redis' `SDIFF`,
+    /// in an arbitrary order. Sets that don't exist are treated as empty sets.
+    /// The same errors as in [sunion()](#method.sunion) are returned.
     /// 
-    pub fn lpop<V>(&mut self, name: &str, pos: usize) -> Option<V> 
+    /// # Arguments
+    /// 
+    /// * `names` - the set names. The first one is the set whose members are returned
+    /// 
+    pub fn sdiff<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
         where
             V: DeserializeOwned
     {
-        match self.list_map.get_mut(name) {
-            Some(list) => {
-                if pos < list.len() {
-                    let res = list.remove(pos);
-                    self.dumpdb();
-                    deserialize_data(&res)
-                } else {
-                    None
-                }
-            },
-                
-            None => None,
-        }
+        let sets = self.try_get_set_refs(names)?;
+        let Some(((name, Some(first)), others)) = sets.split_first() else {
+            return Ok(Vec::new());
+        };
+        first.iter()
+            .filter(|member| !others.iter().any(|(_, set)| set.is_some_and(|set| set.contains(*member))))
+            .map(|member| self.deserialize_member(name, member))
+            .collect()
     }
 
-    /// Remove an item out of a list.
+    /// Return an iterator over the members of a certain set.
     /// 
-    /// This method takes a list name and a position inside the list, removes the
-    /// item in this position and returns an indication whether the item was removed or not.
-    /// If the list is not found in the DB or the given position is out of bounds
-    /// no item will be removed and `false` will be returned. Otherwise the item will be
-    /// removed and `true` will be returned.
-    /// This method is very similar to [lpop()](#method.lpop), the only difference is that this 
-    /// methods returns an indication and [lpop()](#method.lpop) returns the actual item that was removed.
+    /// The members are iterated in an arbitrary order.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list key
-    /// * `pos` - the position of the item to remove
+    /// * `name` - the set name. If the set doesn't exist [PickleDbError::KeyNotFound](enum.PickleDbError.html#variant.KeyNotFound)
+    ///   is returned
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // create a list
-    /// db.lcreate("list1");
+    /// // iterate over the members of set1
+    /// for member in db.siter("set1")? {
+    ///     println!("Current member is: {}", member.get_item::<i32>().unwrap());
+    /// }
+    /// ```
     /// 
-    /// // add 4 items to the list
-    /// db.lextend("list1", &vec![1,2,3,4]);
+    pub fn siter<'a>(&'a self, name: &'a str) -> Result<PickleDbSetIterator<'a>, PickleDbError> {
+        match self.get_set_ref(name) {
+            Some(set) => Ok(PickleDbSetIterator {
+                set_name: name,
+                set_iter: set.iter(),
+                ser_method: self.serialization_method,
+            }),
+            None => Err(self.missing_key_error(name)),
+        }
+    }
+
+    /// Add a member to a sorted set or update its score.
+    /// 
+    /// If the sorted set doesn't exist it's created, like Redis' `ZADD`. Every member of a sorted set has a score and
+    /// the members are kept ordered by their score, so they can be queried by rank with [zrange_by_rank()](#method.zrange_by_rank)
+    /// or by score with [zrange_by_score()](#method.zrange_by_score). Members with the same score are ordered by their
+    /// serialized form. Like in [sadd()](#method.sadd), members can be of different types and are compared by their
+    /// serialized form.
+    /// This method returns `true` if the member was added and `false` if it was already in the sorted set and only its
+    /// score was updated. [PickleDbError::InvalidScore](enum.PickleDbError.html#variant.InvalidScore) is returned if the
+    /// score is NaN or infinite, [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) if the name holds
+    /// another kind of data rather than a sorted set, or an error if the member couldn't be serialized or dumped.
     /// 
-    /// // remove item in position 2
-    /// db.lrem_value("list1", 2);
+    /// # Arguments
     /// 
-    /// // The list now looks like this: [1, 2, 4]
+    /// * `name` - the sorted set name
+    /// * `score` - the score of the member
+    /// * `value` - a reference of the member to add
     /// 
-    /// // remove item in position 1
-    /// db.lrem_value("list1", 1);
+    /// # Examples
     /// 
-    /// // The list now looks like this: [1, 4]
+    /// ```rust,ignore
+    /// db.zadd("leaderboard", 120.0, &String::from("alice"))?;
+    /// db.zadd("leaderboard", 95.5, &String::from("bob"))?;
+    /// 
+    /// // the member is already in the sorted set, so only its score is updated
+    /// assert!(!db.zadd("leaderboard", 130.0, &String::from("bob"))?);
     /// ```
     /// 
-    pub fn lrem_value<V>(&mut self, name: &str, value: &V) -> bool 
+    pub fn zadd<V>(&mut self, name: &str, score: f64, value: &V) -> Result<bool, PickleDbError>
         where
             V: Serialize
     {
-        match self.list_map.get_mut(name) {
-            Some(list) => {
-                let serialized_value = serialize_data(&value).unwrap();
-                match list.iter().position(|x| *x == serialized_value) {
-                    Some(pos) => {
-                        list.remove(pos);
-                        self.dumpdb();
-                        true
-                    },
-
-                    None => false,
-                }
-            },
+        let serialized_value = self.serialize_item(value)?;
+        self.zadd_serialized(name, score, serialized_value)
+    }
 
-            None => false,
+    fn zadd_serialized(&mut self, name: &str, score: f64, serialized_value: SerializedValue) -> Result<bool, PickleDbError> {
+        if !score.is_finite() {
+            return Err(PickleDbError::InvalidScore(String::from(name)));
         }
+        let added = self.get_or_create_sorted_set(name)?.insert(serialized_value, score);
+        self.dumpdb(name)?;
+        Ok(added)
     }
 
-    /// Return an iterator over the keys and values in the DB.
+    /// Remove a member from a sorted set.
+    /// 
+    /// This method works like [srem()](#method.srem): it returns `true` if the member was removed and `false` if it
+    /// wasn't in the sorted set or if the sorted set doesn't exist, and the sorted set remains in the DB even if
+    /// it becomes empty. [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) is returned if the name
+    /// holds another kind of data rather than a sorted set, or an error if the member couldn't be serialized or the
+    /// change couldn't be dumped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the sorted set name
+    /// * `value` - a reference of the member to remove
+    /// 
+    pub fn zrem<V>(&mut self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.zrem_serialized(name, &serialized_value)
+    }
+
+    fn zrem_serialized(&mut self, name: &str, serialized_value: &SerializedValue) -> Result<bool, PickleDbError> {
+        if self.try_get_sorted_set_ref(name)?.is_none() {
+            return Ok(false);
+        }
+        if !self.sorted_set_map.get_mut(name).unwrap().remove(serialized_value) {
+            return Ok(false);
+        }
+        self.dumpdb(name)?;
+        Ok(true)
+    }
+
+    /// Get the score of a member of a sorted set.
+    /// 
+    /// If the sorted set doesn't exist or the member isn't in it, `None` is returned. Otherwise `Some(f64)` with the
+    /// score of the member is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the sorted set name
+    /// * `value` - a reference of the member
+    /// 
+    pub fn zscore<V>(&self, name: &str, value: &V) -> Option<f64>
+        where
+            V: Serialize
+    {
+        self.get_sorted_set_ref(name)?.score(&self.serialize_item(value).ok()?)
+    }
+
+    /// Get a range of members of a sorted set by their rank.
+    /// 
+    /// The rank of a member is its position in the sorted set, where the member with the lowest score has a rank of 0.
+    /// This method returns the members from rank `start` to rank `end`, including both, with their scores, like
+    /// Redis' `ZRANGE` with `WITHSCORES`. The ranks are treated like the indexes of [lrange()](#method.lrange): negative
+    /// ranks count from the end, so `zrange_by_rank(name, -3, -1)` returns the 3 members with the highest scores.
+    /// If the sorted set doesn't exist an empty vector is returned.
+    /// [PickleDbError::WrongType](enum.PickleDbError.html#variant.WrongType) is returned if the name holds another kind
+    /// of data rather than a sorted set, and [PickleDbError::Deserialization](enum.PickleDbError.html#variant.Deserialization)
+    /// is returned if one of the members can't be deserialized to `V`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the sorted set name
+    /// * `start` - the rank of the first member
+    /// * `end` - the rank of the last member
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // iterate over all keys and values in the db
-    /// for kv in db.iter() {
-    ///     match kv.get_key() {
-    ///         "key1" => println!("Value of {} is: {}", kv.get_key(), kv.get_value::<String>().unwrap()),
-    ///         "key2" => println!("Value of {} is: {}", kv.get_key(), kv.get_value::<String>().unwrap()),
-    ///         "key3" => println!("Value of {} is: {:?}", kv.get_key(), kv.get_value::<Vec<i32>>().unwrap()),
-    ///         "key4" => println!("Value of {} is: {}", kv.get_key(), kv.get_value::<Rectangle>().unwrap()),
-    ///         _ => ()
-    ///     }
+    /// db.zadd("tasks", 2.0, &String::from("write docs"))?;
+    /// db.zadd("tasks", 1.0, &String::from("fix bug"))?;
+    /// 
+    /// // take the task with the lowest score out of the queue
+    /// if let Some((task, _)) = db.zrange_by_rank::<String>("tasks", 0, 0)?.pop() {
+    ///     db.zrem("tasks", &task)?;
     /// }
     /// ```
     /// 
-    pub fn iter(&self) -> PickleDbIterator {
-        PickleDbIterator { map_iter: self.map.iter() }
+    pub fn zrange_by_rank<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        let sorted_set = match self.try_get_sorted_set_ref(name)? {
+            Some(sorted_set) => sorted_set,
+            None => return Ok(Vec::new()),
+        };
+        let range = list_range(sorted_set.len(), start, end);
+        sorted_set.iter()
+            .skip(range.start)
+            .take(range.len())
+            .map(|(member, score)| self.deserialize_member(name, member).map(|member| (member, score)))
+            .collect()
     }
 
-    /// Return an iterator over the items in certain list.
+    /// Get the members of a sorted set whose score is within a range.
+    /// 
+    /// This method returns the members whose score is between `min` and `max`, including both, with their scores and
+    /// ordered by score, like Redis' `ZRANGEBYSCORE` with `WITHSCORES`. If the sorted set doesn't exist an empty vector
+    /// is returned. [PickleDbError::InvalidScore](enum.PickleDbError.html#variant.InvalidScore) is returned if `min` or
+    /// `max` is NaN, and otherwise the same errors as in [zrange_by_rank()](#method.zrange_by_rank) are returned.
     /// 
     /// # Arguments
     /// 
-    /// * `name` - the list name. If the list doesn't exist an exception is thrown
+    /// * `name` - the sorted set name
+    /// * `min` - the lowest score to return
+    /// * `max` - the highest score to return
     /// 
     /// # Examples
     /// 
     /// ```rust,ignore
-    /// // create a new list
-    /// db.lcreate("list1")
-    ///   .lextend(&vec![1,2,3,4]);
+    /// // all players with a score of at least 100
+    /// let top_players = db.zrange_by_score::<String>("leaderboard", 100.0, f64::INFINITY)?;
+    /// ```
     /// 
-    /// // iterate over the items in list1
-    /// for item_iter in db.liter("list1") {
-    ///     println!("Current item is: {}", item_iter.get_item::<i32>().unwrap());
-    /// }
+    pub fn zrange_by_score<V>(&self, name: &str, min: f64, max: f64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        if min.is_nan() || max.is_nan() {
+            return Err(PickleDbError::InvalidScore(String::from(name)));
+        }
+        let sorted_set = match self.try_get_sorted_set_ref(name)? {
+            Some(sorted_set) => sorted_set,
+            None => return Ok(Vec::new()),
+        };
+        sorted_set.range_by_score(min, max)
+            .map(|(member, score)| self.deserialize_member(name, member).map(|member| (member, score)))
+            .collect()
+    }
+
+    /// Increment the score of a member of a sorted set.
+    /// 
+    /// This method adds `by` to the score of the member and returns the new score, like Redis' `ZINCRBY`. A negative
+    /// value decrements the score. If the member isn't in the sorted set it's added with a score of `by`, and if the
+    /// sorted set doesn't exist it's created.
+    /// [PickleDbError::Overflow](enum.PickleDbError.html#variant.Overflow) is returned if the new score isn't a finite
+    /// number, and otherwise the same errors as in [zadd()](#method.zadd) are returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the sorted set name
+    /// * `by` - the value to add to the score
+    /// * `value` - a reference of the member
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// assert_eq!(db.zincrby("leaderboard", 10.0, &String::from("carol"))?, 10.0);
+    /// assert_eq!(db.zincrby("leaderboard", 5.0, &String::from("carol"))?, 15.0);
     /// ```
     /// 
-    pub fn liter(&self, name: &str) -> PickleDbListIterator {
-        match self.list_map.get(name) {
-            Some(list) => PickleDbListIterator { list_iter: list.iter() },
-            None => panic!("List '{}' doesn't exist", name)
+    pub fn zincrby<V>(&mut self, name: &str, by: f64, value: &V) -> Result<f64, PickleDbError>
+        where
+            V: Serialize
+    {
+        let serialized_value = self.serialize_item(value)?;
+        self.zincrby_serialized(name, by, serialized_value)
+    }
+
+    fn zincrby_serialized(&mut self, name: &str, by: f64, serialized_value: SerializedValue) -> Result<f64, PickleDbError> {
+        if !by.is_finite() {
+            return Err(PickleDbError::InvalidScore(String::from(name)));
+        }
+        // the new score is checked before a missing sorted set is created, so a rejected change leaves nothing behind
+        let current = self.try_get_sorted_set_ref(name)?.and_then(|sorted_set| sorted_set.score(&serialized_value));
+        let score = current.unwrap_or(0.0) + by;
+        if !score.is_finite() {
+            return Err(PickleDbError::Overflow(String::from(name)));
+        }
+        self.get_or_create_sorted_set(name)?.insert(serialized_value, score);
+        self.dumpdb(name)?;
+        Ok(score)
+    }
+
+    /// Get the number of members in a sorted set.
+    /// 
+    /// If the sorted set is empty or if it doesn't exist the value of 0 is returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - the sorted set name
+    /// 
+    pub fn zcard(&self, name: &str) -> usize {
+        self.get_sorted_set_ref(name).map_or(0, |sorted_set| sorted_set.len())
+    }
+
+    fn get_list_mut(&mut self, name: &str) -> Result<&mut Vec<SerializedValue>, PickleDbError> {
+        self.remove_if_expired(name);
+        if !self.list_map.contains_key(name) {
+            return Err(self.missing_key_error(name));
+        }
+        Ok(self.list_map.get_mut(name).unwrap())
+    }
+
+    fn missing_key_error(&self, name: &str) -> PickleDbError {
+        if self.exists(name) {
+            PickleDbError::WrongType(String::from(name))
+        } else {
+            PickleDbError::KeyNotFound(String::from(name))
+        }
+    }
+
+    fn get_dict_mut(&mut self, name: &str) -> Result<&mut HashMap<String, SerializedValue>, PickleDbError> {
+        self.remove_if_expired(name);
+        if !self.dict_map.contains_key(name) {
+            return Err(self.missing_key_error(name));
+        }
+        Ok(self.dict_map.get_mut(name).unwrap())
+    }
+
+    fn get_dict_ref(&self, name: &str) -> Option<&HashMap<String, SerializedValue>> {
+        self.dict_map.get(name).filter(|_| !self.is_expired(name))
+    }
+
+    fn get_value_ref(&self, key: &str) -> Option<&SerializedValue> {
+        self.map.get(key).filter(|_| !self.is_expired(key))
+    }
+
+    fn get_list_ref(&self, name: &str) -> Option<&Vec<SerializedValue>> {
+        self.list_map.get(name).filter(|_| !self.is_expired(name))
+    }
+
+    fn get_set_ref(&self, name: &str) -> Option<&HashSet<SerializedValue>> {
+        self.set_map.get(name).filter(|_| !self.is_expired(name))
+    }
+
+    /// The set of a name, or `None` if the set doesn't exist. Returns an error if the name holds another type
+    fn try_get_set_ref(&self, name: &str) -> Result<Option<&HashSet<SerializedValue>>, PickleDbError> {
+        match self.get_set_ref(name) {
+            Some(set) => Ok(Some(set)),
+            None if self.exists(name) => Err(PickleDbError::WrongType(String::from(name))),
+            None => Ok(None),
+        }
+    }
+
+    fn get_sorted_set_ref(&self, name: &str) -> Option<&SortedSet> {
+        self.sorted_set_map.get(name).filter(|_| !self.is_expired(name))
+    }
+
+    /// The sorted set of a name, or `None` if the sorted set doesn't exist. Returns an error if the name holds another type
+    fn try_get_sorted_set_ref(&self, name: &str) -> Result<Option<&SortedSet>, PickleDbError> {
+        match self.get_sorted_set_ref(name) {
+            Some(sorted_set) => Ok(Some(sorted_set)),
+            None if self.exists(name) => Err(PickleDbError::WrongType(String::from(name))),
+            None => Ok(None),
+        }
+    }
+
+    /// The sorted set of a name, which is created if it doesn't exist. Returns an error if the name holds another type
+    fn get_or_create_sorted_set(&mut self, name: &str) -> Result<&mut SortedSet, PickleDbError> {
+        self.remove_if_expired(name);
+        if !self.sorted_set_map.contains_key(name) {
+            if self.exists(name) {
+                return Err(PickleDbError::WrongType(String::from(name)));
+            }
+            self.sorted_set_map.insert(String::from(name), SortedSet::new());
+        }
+        Ok(self.sorted_set_map.get_mut(name).unwrap())
+    }
+
+    fn try_get_set_refs<'a>(&'a self, names: &[&'a str]) -> Result<Vec<NamedSet<'a>>, PickleDbError> {
+        names.iter().map(|name| self.try_get_set_ref(name).map(|set| (*name, set))).collect()
+    }
+
+    fn deserialize_member<V>(&self, name: &str, member: &SerializedValue) -> Result<V, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.serialization_method.deserialize_data(&member.0)
+            .map_err(|message| PickleDbError::Deserialization { key: String::from(name), message })
+    }
+
+    fn is_expired(&self, key: &str) -> bool {
+        match self.expiry.get(key) {
+            Some(expires_at) => *expires_at <= now_millis(),
+            None => false,
+        }
+    }
+
+    fn remove_if_expired(&mut self, key: &str) {
+        if self.is_expired(key) {
+            self.remove_key(key);
+            self.expiry.remove(key);
+            self.notify(PickleDbEvent::Expired(String::from(key)));
+        }
+    }
+
+    /// The keys that didn't expire, sorted. The keys of each keyspace are usually sorted already, so sorting them only merges the keyspaces
+    fn sorted_keys<'a>(&self, keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+        let mut keys: Vec<String> = keys.filter(|key| !self.is_expired(key)).cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Remove a key from all keyspaces, without its expiry time. Returns whether the key was found
+    fn remove_key(&mut self, key: &str) -> bool {
+        let in_map = self.map.remove(key).is_some();
+        let in_list_map = self.list_map.remove(key).is_some();
+        let in_dict_map = self.dict_map.remove(key).is_some();
+        let in_set_map = self.set_map.remove(key).is_some();
+        let in_sorted_set_map = self.sorted_set_map.remove(key).is_some();
+        in_map || in_list_map || in_dict_map || in_set_map || in_sorted_set_map
+    }
+
+    fn purge_expired(&mut self) {
+        let now = now_millis();
+        let expired_keys: Vec<String> = self.expiry.iter()
+            .filter(|(_, expires_at)| **expires_at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired_keys {
+            self.remove_if_expired(&key);
         }
     }
 }
 
+/// The position of an item in a list of length `len`, where a negative index counts from the end of the list
+fn list_index(len: usize, index: i64) -> Option<usize> {
+    let pos = if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+    Some(pos).filter(|pos| *pos < len)
+}
+
+/// The positions of the items from `start` to `end`, including both, in a list of length `len`.
+/// Negative indexes count from the end of the list and indexes out of bounds are limited to the bounds of the list
+fn list_range(len: usize, start: i64, end: i64) -> Range<usize> {
+    let to_pos = |index: i64| if index < 0 {
+        len as i64 + index
+    } else {
+        index
+    };
+    let start = to_pos(start).max(0);
+    let end = to_pos(end).min(len as i64 - 1);
+    if start > end {
+        return 0..0;
+    }
+    start as usize..end as usize + 1
+}
+
+/// Read a DB file along with its signature, which is taken first so a change made while the file is read is detected later
+fn read_file(location: &str) -> Result<(Vec<u8>, FileSignature), PickleDbError> {
+    let file_signature = FileSignature::read(location)?;
+    let contents = fs::read(location)?;
+    Ok((contents, file_signature))
+}
+
+/// Deserialize the content of a DB file, which may have been written by a readable dump
+fn read_db_file_contents(contents: &[u8], serialization_method: SerializationMethod) -> Result<DbFileContents, PickleDbError> {
+    if serialization_method == SerializationMethod::Json && json_format::is_readable(contents) {
+        json_format::read(contents)
+    } else {
+        serialization_method.deserialize_data(contents)
+    }.map_err(PickleDbError::Corrupted)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn expiry_time(ttl: Duration) -> u64 {
+    now_millis().saturating_add(ttl.as_millis() as u64)
+}
+
 impl Drop for PickleDb {
     fn drop(&mut self) {
-        if let PickleDbDumpPolicy::NeverDump = self.dump_policy {
-        } else {
-            self.dump();
+        // changes of a transaction that wasn't committed are discarded
+        let _ = self.rollback();
+        // errors can't be reported from drop, the user should call dump() to get them
+        match self.dump_policy {
+            PickleDbDumpPolicy::NeverDump => (),
+            PickleDbDumpPolicy::AppendOnly => { let _ = self.append_changes(); },
+            _ => { let _ = self.dump(); },
         }
     }
 }
\ No newline at end of file
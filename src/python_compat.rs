@@ -0,0 +1,120 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{DbFileContents, PickleDb};
+use crate::json_format::{self, from_serialized, scored_members, sorted_members, to_serialized, OrderedValue};
+use crate::sorted_set::SortedSet;
+
+/// Python's pickleDB has no sets, so a set is written as a JSON object with this single key holding its members
+const SET_TAG: &str = "$set";
+/// Like `SET_TAG` for a sorted set, whose key holds its member and score pairs
+const SORTED_SET_TAG: &str = "$sorted_set";
+
+/// Read a DB file in the layout of Python's pickleDB: a single JSON object in which every key holds
+/// its value as is. JSON arrays are read as lists, JSON objects as dicts, except for the objects sets and sorted
+/// sets are written as, and any other value as a value
+pub(crate) fn read(contents: &[u8]) -> Result<DbFileContents, String> {
+    let object = match serde_json::from_slice(contents).map_err(|err| err.to_string())? {
+        OrderedValue::Object(object) => object,
+        _ => return Err(String::from("the DB file doesn't hold a JSON object")),
+    };
+
+    let mut map = HashMap::new();
+    let mut list_map = HashMap::new();
+    let mut dict_map = HashMap::new();
+    let mut set_map = HashMap::new();
+    let mut sorted_set_map = HashMap::new();
+    for (key, value) in object {
+        match value {
+            OrderedValue::Array(items) => {
+                let items = items.iter().map(to_serialized).collect::<Result<_, _>>()?;
+                list_map.insert(key, items);
+            },
+            OrderedValue::Object(pairs) => {
+                if let Some(members) = tagged_members(&pairs, SET_TAG) {
+                    set_map.insert(key, members.iter().map(to_serialized).collect::<Result<_, _>>()?);
+                } else if let Some(members) = tagged_members(&pairs, SORTED_SET_TAG) {
+                    let members = members.iter()
+                        .map(|member| match member {
+                            OrderedValue::Array(pair) => match pair.as_slice() {
+                                [member, OrderedValue::Scalar(score)] if score.is_number() =>
+                                    to_serialized(member).map(|member| (member, score.as_f64().unwrap())),
+                                _ => Err(format!("invalid member of sorted set {}", key)),
+                            },
+                            _ => Err(format!("invalid member of sorted set {}", key)),
+                        })
+                        .collect::<Result<SortedSet, _>>()?;
+                    sorted_set_map.insert(key, members);
+                } else {
+                    let pairs = pairs.into_iter()
+                        .map(|(dict_key, value)| to_serialized(&value).map(|value| (dict_key, value)))
+                        .collect::<Result<_, _>>()?;
+                    dict_map.insert(key, pairs);
+                }
+            },
+            value => {
+                map.insert(key, to_serialized(&value)?);
+            },
+        }
+    }
+    Ok(DbFileContents(map, list_map, HashMap::new(), dict_map, set_map, sorted_set_map))
+}
+
+/// The members of a set or a sorted set if a JSON object is the one it was written as: an object whose single key is
+/// the tag and holds an array
+fn tagged_members<'a>(pairs: &'a [(String, OrderedValue)], tag: &str) -> Option<&'a Vec<OrderedValue>> {
+    match pairs {
+        [(key, OrderedValue::Array(members))] if key == tag => Some(members),
+        _ => None,
+    }
+}
+
+/// Write the DB in the layout of Python's pickleDB. Values, list items and dict values are stored in the DB
+/// as JSON, so they're written to the file as nested JSON rather than as strings. Python's pickleDB has no sets,
+/// so a set is written as a JSON object whose single key `$set` holds its sorted members, and a sorted set as
+/// a JSON object whose single key `$sorted_set` holds its member and score pairs, ordered by score. A value that is
+/// a JSON array or object would be read back as a list or a dict, and a dict that would be read back as a set, so
+/// neither of them can be written.
+/// With a readable dump every key is written on its own line. Keys and dict keys are sorted, so the same data is
+/// always written the same way
+pub(crate) fn write(db: &PickleDb) -> Result<Vec<u8>, String> {
+    let mut object = BTreeMap::new();
+    for (key, value) in db.map.iter() {
+        let value = from_serialized(value)?;
+        if !matches!(value, OrderedValue::Scalar(_)) {
+            return Err(format!("value {} can't be written in the layout of Python's PickleDB since it would be read as a list or a dict", key));
+        }
+        object.insert(key.as_str(), value);
+    }
+    for (name, items) in db.list_map.iter() {
+        let items = items.iter().map(from_serialized).collect::<Result<_, _>>()?;
+        object.insert(name.as_str(), OrderedValue::Array(items));
+    }
+    for (name, pairs) in db.dict_map.iter() {
+        if pairs.len() == 1 && [SET_TAG, SORTED_SET_TAG].iter().any(|tag| pairs.contains_key(*tag)) {
+            let value = pairs.values().next().map(from_serialized).transpose()?;
+            if let Some(OrderedValue::Array(_)) = value {
+                return Err(format!("dict {} can't be written in the layout of Python's PickleDB since it would be read as a set", name));
+            }
+        }
+        let pairs = pairs.iter()
+            .map(|(dict_key, value)| from_serialized(value).map(|value| (dict_key.as_str(), value)))
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        let pairs = pairs.into_iter().map(|(dict_key, value)| (String::from(dict_key), value)).collect();
+        object.insert(name.as_str(), OrderedValue::Object(pairs));
+    }
+    for (name, members) in db.set_map.iter() {
+        object.insert(name.as_str(), tagged(SET_TAG, sorted_members(members)?));
+    }
+    for (name, sorted_set) in db.sorted_set_map.iter() {
+        object.insert(name.as_str(), tagged(SORTED_SET_TAG, scored_members(sorted_set)?));
+    }
+    match db.readable_dump {
+        Some(options) => json_format::to_vec(&object, 1, options),
+        None => serde_json::to_vec(&object).map_err(|err| err.to_string()),
+    }
+}
+
+/// A set or a sorted set as the JSON object it's written as
+fn tagged(tag: &str, members: Vec<OrderedValue>) -> OrderedValue {
+    OrderedValue::Object(vec![(String::from(tag), OrderedValue::Array(members))])
+}
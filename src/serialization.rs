@@ -1,22 +1,134 @@
-use serde::{de::DeserializeOwned, Serialize};
-use serde_json;
+use std::fmt;
+use serde::{de::{self, DeserializeOwned, Visitor, SeqAccess}, Deserialize, Deserializer, Serialize, Serializer};
 
-pub(crate) fn deserialize_data<V>(ser_data: &str) -> Option<V> 
-    where 
-        V: DeserializeOwned    
-{
-    match serde_json::from_str(&ser_data) {
-        Ok(val) => Some(val),
-        Err(_) => None
+/// An enum for specifying the serialization method to use when creating a new PickleDB DB or loading one from a file.
+///
+/// The serialization method is used both for the values and list items stored in the DB and for the DB file itself.
+/// Please note that a DB file can only be loaded with the same serialization method it was dumped with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerializationMethod {
+    /// [JSON serialization](https://crates.io/crates/serde_json)
+    Json,
+    /// [Bincode serialization](https://crates.io/crates/bincode)
+    Bin,
+    /// [YAML serialization](https://crates.io/crates/serde_yaml)
+    Yaml,
+    /// [CBOR serialization](https://crates.io/crates/serde_cbor)
+    Cbor,
+    /// [MessagePack serialization](https://crates.io/crates/rmp-serde)
+    MsgPack,
+}
+
+impl fmt::Display for SerializationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SerializationMethod::Json => "JSON",
+            SerializationMethod::Bin => "Bincode",
+            SerializationMethod::Yaml => "YAML",
+            SerializationMethod::Cbor => "CBOR",
+            SerializationMethod::MsgPack => "MessagePack",
+        };
+        write!(f, "{}", name)
     }
 }
 
-pub(crate) fn serialize_data<V>(data: &V) -> Result<String, String>
+impl SerializationMethod {
+    pub(crate) fn deserialize_data<V>(&self, ser_data: &[u8]) -> Result<V, String>
+        where
+            V: DeserializeOwned
+    {
+        match self {
+            SerializationMethod::Json => serde_json::from_slice(ser_data).map_err(|err| err.to_string()),
+            SerializationMethod::Bin => bincode::deserialize(ser_data).map_err(|err| err.to_string()),
+            SerializationMethod::Yaml => serde_yaml::from_slice(ser_data).map_err(|err| err.to_string()),
+            SerializationMethod::Cbor => serde_cbor::from_slice(ser_data).map_err(|err| err.to_string()),
+            SerializationMethod::MsgPack => rmp_serde::from_slice(ser_data).map_err(|err| err.to_string()),
+        }
+    }
+
+    pub(crate) fn serialize_data<V>(&self, data: &V) -> Result<Vec<u8>, String>
         where
             V: Serialize
-{
-    match serde_json::to_string(data) {
-        Ok(ser_data) => Ok(ser_data),
-        Err(err) => Err(err.to_string())
+    {
+        match self {
+            SerializationMethod::Json => serde_json::to_vec(data).map_err(|err| err.to_string()),
+            SerializationMethod::Bin => bincode::serialize(data).map_err(|err| err.to_string()),
+            SerializationMethod::Yaml => serde_yaml::to_string(data)
+                .map(String::into_bytes)
+                .map_err(|err| err.to_string()),
+            SerializationMethod::Cbor => serde_cbor::to_vec(data).map_err(|err| err.to_string()),
+            SerializationMethod::MsgPack => rmp_serde::to_vec(data).map_err(|err| err.to_string()),
+        }
     }
-}
\ No newline at end of file
+}
+
+/// A value or list item as it's stored in the DB, serialized with the DB's serialization method.
+///
+/// Text based formats (JSON and YAML) write it to the DB file as a string, which keeps the file
+/// readable, while binary formats write it as raw bytes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct SerializedValue(pub(crate) Vec<u8>);
+
+impl Serialize for SerializedValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer
+    {
+        if serializer.is_human_readable() {
+            match std::str::from_utf8(&self.0) {
+                Ok(as_str) => serializer.serialize_str(as_str),
+                Err(err) => Err(serde::ser::Error::custom(err)),
+            }
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SerializedValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>
+    {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer).map(|as_str| SerializedValue(as_str.into_bytes()))
+        } else {
+            deserializer.deserialize_byte_buf(SerializedValueVisitor)
+        }
+    }
+}
+
+struct SerializedValueVisitor;
+
+impl<'de> Visitor<'de> for SerializedValueVisitor {
+    type Value = SerializedValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a serialized value")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: de::Error
+    {
+        Ok(SerializedValue(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+        where
+            E: de::Error
+    {
+        Ok(SerializedValue(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(SerializedValue(bytes))
+    }
+}
@@ -0,0 +1,872 @@
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::PickleDbError;
+use crate::{ListEnd, PickleDb, PickleDbEvent, PickleDbSnapshot, PickleDbSubscription};
+
+/// A thread-safe handle to a PickleDB object that can be shared between threads.
+///
+/// `SharedPickleDb` is cheap to clone and all clones refer to the same DB. Reading APIs such as
+/// [get()](#method.get), [lget()](#method.lget) and [exists()](#method.exists) can run concurrently
+/// from different threads, while APIs that change the DB are serialized: each of them waits until
+/// the readers and writers that are currently running are done.
+/// The dump policy of the DB works exactly the same as it does with `PickleDb`. The DB is dropped,
+/// and dumped if its dump policy requires that, when the last clone of the handle is dropped.
+///
+/// Only the most common APIs are provided directly. Any other API of `PickleDb`, such as iterating over
+/// the DB, can be used in [read()](#method.read) or [write()](#method.write).
+///
+/// If a thread panics while it changes the DB, the DB remains usable by the other threads. Please note
+/// that in that case the change may be applied partially, unless it's made in a
+/// [transaction()](#method.transaction).
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod, SharedPickleDb};
+/// use std::thread;
+///
+/// let db = SharedPickleDb::new(PickleDb::new("example.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json));
+///
+/// let handles: Vec<_> = (0..4).map(|i| {
+///     let db = db.clone();
+///     thread::spawn(move || db.set(&format!("key{}", i), &i))
+/// }).collect();
+///
+/// for handle in handles {
+///     handle.join().unwrap()?;
+/// }
+/// ```
+///
+#[derive(Clone)]
+pub struct SharedPickleDb {
+    db: Arc<RwLock<PickleDb>>,
+}
+
+impl SharedPickleDb {
+    /// Constructs a new `SharedPickleDb` that owns the given DB.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - the DB to share, created by [PickleDb::new()](struct.PickleDb.html#method.new) or loaded by
+    ///   [PickleDb::load()](struct.PickleDb.html#method.load)
+    ///
+    pub fn new(db: PickleDb) -> SharedPickleDb {
+        SharedPickleDb { db: Arc::new(RwLock::new(db)) }
+    }
+
+    /// Run a closure that reads from the DB.
+    ///
+    /// Closures of different threads that read from the DB can run concurrently.
+    /// This method returns the value the closure returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let sum: i32 = db.read(|db| db.iter().filter_map(|kv| kv.get_value::<i32>()).sum());
+    /// ```
+    ///
+    pub fn read<F, R>(&self, f: F) -> R
+        where
+            F: FnOnce(&PickleDb) -> R
+    {
+        f(&self.read_lock())
+    }
+
+    /// Run a closure that changes the DB.
+    ///
+    /// Only one closure that changes the DB runs at a time, and no other thread reads from the DB while it runs.
+    /// This method returns the value the closure returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// db.write(|db| -> Result<(), PickleDbError> {
+    ///     db.lcreate("list1")?.lextend(&[1, 2, 3])?;
+    ///     Ok(())
+    /// })?;
+    /// ```
+    ///
+    pub fn write<F, R>(&self, f: F) -> R
+        where
+            F: FnOnce(&mut PickleDb) -> R
+    {
+        f(&mut self.write_lock())
+    }
+
+    /// Run a closure in a transaction. Please see [PickleDb::transaction()](struct.PickleDb.html#method.transaction).
+    ///
+    /// No other thread reads from the DB while the transaction runs, so the changes of the transaction are never
+    /// seen partially by other threads.
+    ///
+    pub fn transaction<F, R, E>(&self, f: F) -> Result<R, E>
+        where
+            F: FnOnce(&mut PickleDb) -> Result<R, E>,
+            E: From<PickleDbError>
+    {
+        self.write_lock().transaction(f)
+    }
+
+    /// Dump the data to the file. Please see [PickleDb::dump()](struct.PickleDb.html#method.dump).
+    ///
+    pub fn dump(&self) -> Result<(), PickleDbError> {
+        self.write_lock().dump()
+    }
+
+    /// Reload the DB from its file. Please see [PickleDb::reload()](struct.PickleDb.html#method.reload).
+    ///
+    pub fn reload(&self) -> Result<(), PickleDbError> {
+        self.write_lock().reload()
+    }
+
+    /// Reload the DB from its file if the file changed. Please see
+    /// [PickleDb::reload_if_changed()](struct.PickleDb.html#method.reload_if_changed).
+    ///
+    /// Whether the file changed is checked while readers can still use the DB, so they're blocked only if it's reloaded.
+    ///
+    pub fn reload_if_changed(&self) -> Result<bool, PickleDbError> {
+        if !self.read_lock().file_changed()? {
+            return Ok(false);
+        }
+        self.write_lock().reload_if_changed()
+    }
+
+    /// Write a copy of the DB to another file. Please see [PickleDb::save_as()](struct.PickleDb.html#method.save_as).
+    ///
+    pub fn save_as(&self, path: &str) -> Result<(), PickleDbError> {
+        self.read_lock().save_as(path)
+    }
+
+    /// Get a point-in-time snapshot of the DB. Please see [PickleDb::snapshot()](struct.PickleDb.html#method.snapshot).
+    ///
+    /// Other threads can read from the DB while the snapshot is taken, and once it's taken it can be read without
+    /// blocking the threads that change the DB.
+    ///
+    pub fn snapshot(&self) -> PickleDbSnapshot {
+        self.read_lock().snapshot()
+    }
+
+    /// Restore a backup of the DB file. Please see [PickleDb::restore_backup()](struct.PickleDb.html#method.restore_backup).
+    ///
+    pub fn restore_backup(&self, number: usize) -> Result<(), PickleDbError> {
+        self.write_lock().restore_backup(number)
+    }
+
+    /// Subscribe to the changes of keys in the DB. Please see [PickleDb::subscribe()](struct.PickleDb.html#method.subscribe).
+    ///
+    /// The callback runs in the thread that changed the DB while the DB is locked, so it must not use the DB itself,
+    /// otherwise it deadlocks. A channel from [subscribe_channel()](#method.subscribe_channel) can be used to handle
+    /// the events in another thread.
+    ///
+    pub fn subscribe<F>(&self, prefix: &str, callback: F) -> PickleDbSubscription
+        where
+            F: FnMut(&PickleDbEvent) + Send + Sync + 'static
+    {
+        self.write_lock().subscribe(prefix, callback)
+    }
+
+    /// Subscribe to the changes of keys in the DB through a channel. Please see
+    /// [PickleDb::subscribe_channel()](struct.PickleDb.html#method.subscribe_channel).
+    ///
+    pub fn subscribe_channel(&self, prefix: &str) -> Receiver<PickleDbEvent> {
+        self.write_lock().subscribe_channel(prefix)
+    }
+
+    /// Cancel a subscription to the changes of keys in the DB. Please see
+    /// [PickleDb::unsubscribe()](struct.PickleDb.html#method.unsubscribe).
+    ///
+    pub fn unsubscribe(&self, subscription: PickleDbSubscription) -> bool {
+        self.write_lock().unsubscribe(subscription)
+    }
+
+    /// Set a key-value pair. Please see [PickleDb::set()](struct.PickleDb.html#method.set).
+    ///
+    pub fn set<V>(&self, key: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().set(key, value)
+    }
+
+    /// Set a key-value pair that expires after a certain time. Please see
+    /// [PickleDb::set_with_ttl()](struct.PickleDb.html#method.set_with_ttl).
+    ///
+    pub fn set_with_ttl<V>(&self, key: &str, value: &V, ttl: Duration) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().set_with_ttl(key, value, ttl)
+    }
+
+    /// Get a value of a key. Please see [PickleDb::get()](struct.PickleDb.html#method.get).
+    ///
+    pub fn get<V>(&self, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().get(key)
+    }
+
+    /// Get a value of a key, telling a missing key apart from a wrong type. Please see
+    /// [PickleDb::try_get()](struct.PickleDb.html#method.try_get).
+    ///
+    pub fn try_get<V>(&self, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().try_get(key)
+    }
+
+    /// Check if a key exists. Please see [PickleDb::exists()](struct.PickleDb.html#method.exists).
+    ///
+    pub fn exists(&self, key: &str) -> bool {
+        self.read_lock().exists(key)
+    }
+
+    /// Get a vector of all the keys in the DB. Please see [PickleDb::get_all()](struct.PickleDb.html#method.get_all).
+    ///
+    pub fn get_all(&self) -> Vec<String> {
+        self.read_lock().get_all()
+    }
+
+    /// Get the keys in a range, sorted. Please see [PickleDb::range()](struct.PickleDb.html#method.range).
+    ///
+    pub fn range<'r, R>(&self, range: R) -> Vec<String>
+        where
+            R: RangeBounds<&'r str>
+    {
+        self.read_lock().range(range)
+    }
+
+    /// Get the keys that start with a prefix, sorted. Please see [PickleDb::scan_prefix()](struct.PickleDb.html#method.scan_prefix).
+    ///
+    pub fn scan_prefix(&self, prefix: &str) -> Vec<String> {
+        self.read_lock().scan_prefix(prefix)
+    }
+
+    /// Get the total number of keys in the DB. Please see [PickleDb::total_keys()](struct.PickleDb.html#method.total_keys).
+    ///
+    pub fn total_keys(&self) -> usize {
+        self.read_lock().total_keys()
+    }
+
+    /// Remove a key-value pair, a list, a dict, a set or a sorted set from the DB. Please see [PickleDb::rem()](struct.PickleDb.html#method.rem).
+    ///
+    pub fn rem(&self, key: &str) -> Result<bool, PickleDbError> {
+        self.write_lock().rem(key)
+    }
+
+    /// Set a time to live for an existing key or list. Please see [PickleDb::expire()](struct.PickleDb.html#method.expire).
+    ///
+    pub fn expire(&self, key: &str, ttl: Duration) -> Result<bool, PickleDbError> {
+        self.write_lock().expire(key, ttl)
+    }
+
+    /// Get the remaining time to live of a key or list. Please see [PickleDb::ttl()](struct.PickleDb.html#method.ttl).
+    ///
+    pub fn ttl(&self, key: &str) -> Result<Option<Duration>, PickleDbError> {
+        self.read_lock().ttl(key)
+    }
+
+    /// Remove the time to live of a key or list. Please see [PickleDb::persist()](struct.PickleDb.html#method.persist).
+    ///
+    pub fn persist(&self, key: &str) -> Result<bool, PickleDbError> {
+        self.write_lock().persist(key)
+    }
+
+    /// Increment the integer value of a key by one. Please see [PickleDb::incr()](struct.PickleDb.html#method.incr).
+    ///
+    pub fn incr(&self, key: &str) -> Result<i64, PickleDbError> {
+        self.write_lock().incr(key)
+    }
+
+    /// Decrement the integer value of a key by one. Please see [PickleDb::decr()](struct.PickleDb.html#method.decr).
+    ///
+    pub fn decr(&self, key: &str) -> Result<i64, PickleDbError> {
+        self.write_lock().decr(key)
+    }
+
+    /// Increment the integer value of a key. Please see [PickleDb::incr_by()](struct.PickleDb.html#method.incr_by).
+    ///
+    pub fn incr_by(&self, key: &str, by: i64) -> Result<i64, PickleDbError> {
+        self.write_lock().incr_by(key, by)
+    }
+
+    /// Increment the floating point value of a key. Please see [PickleDb::incr_by_float()](struct.PickleDb.html#method.incr_by_float).
+    ///
+    pub fn incr_by_float(&self, key: &str, by: f64) -> Result<f64, PickleDbError> {
+        self.write_lock().incr_by_float(key, by)
+    }
+
+    /// Create a new list. Please see [PickleDb::lcreate()](struct.PickleDb.html#method.lcreate).
+    ///
+    /// Unlike `PickleDb::lcreate()` this method doesn't return an object for adding items to the list,
+    /// please use [lextend()](#method.lextend) for adding them.
+    ///
+    pub fn lcreate(&self, name: &str) -> Result<(), PickleDbError> {
+        self.write_lock().lcreate(name).map(|_| ())
+    }
+
+    /// Check if a list exists. Please see [PickleDb::lexists()](struct.PickleDb.html#method.lexists).
+    ///
+    pub fn lexists(&self, name: &str) -> bool {
+        self.read_lock().lexists(name)
+    }
+
+    /// Add a single item to an existing list. Please see [PickleDb::ladd()](struct.PickleDb.html#method.ladd).
+    ///
+    pub fn ladd<V>(&self, name: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().ladd(name, value).map(|_| ())
+    }
+
+    /// Add multiple items to an existing list. Please see [PickleDb::lextend()](struct.PickleDb.html#method.lextend).
+    ///
+    pub fn lextend<V>(&self, name: &str, seq: &[V]) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().lextend(name, seq).map(|_| ())
+    }
+
+    /// Get an item of of a certain list in a certain position. Please see [PickleDb::lget()](struct.PickleDb.html#method.lget).
+    ///
+    pub fn lget<V>(&self, name: &str, pos: usize) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().lget(name, pos)
+    }
+
+    /// Get an item of of a certain list in a certain position, telling a missing item apart from a wrong type.
+    /// Please see [PickleDb::try_lget()](struct.PickleDb.html#method.try_lget).
+    ///
+    pub fn try_lget<V>(&self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().try_lget(name, pos)
+    }
+
+    /// Get the length of a list. Please see [PickleDb::llen()](struct.PickleDb.html#method.llen).
+    ///
+    pub fn llen(&self, name: &str) -> usize {
+        self.read_lock().llen(name)
+    }
+
+    /// Remove a list. Please see [PickleDb::lrem_list()](struct.PickleDb.html#method.lrem_list).
+    ///
+    pub fn lrem_list(&self, name: &str) -> Result<usize, PickleDbError> {
+        self.write_lock().lrem_list(name)
+    }
+
+    /// Pop an item out of a list. Please see [PickleDb::lpop()](struct.PickleDb.html#method.lpop).
+    ///
+    pub fn lpop<V>(&self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.write_lock().lpop(name, pos)
+    }
+
+    /// Remove an item out of a list. Please see [PickleDb::lrem_value()](struct.PickleDb.html#method.lrem_value).
+    ///
+    pub fn lrem_value<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().lrem_value(name, value)
+    }
+
+    /// Add an item to the front of an existing list. Please see [PickleDb::lpush()](struct.PickleDb.html#method.lpush).
+    ///
+    pub fn lpush<V>(&self, name: &str, value: &V) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().lpush(name, value)
+    }
+
+    /// Insert an item into an existing list at a certain position. Please see [PickleDb::linsert()](struct.PickleDb.html#method.linsert).
+    ///
+    pub fn linsert<V>(&self, name: &str, index: i64, value: &V) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().linsert(name, index, value)
+    }
+
+    /// Insert an item into an existing list before another item. Please see
+    /// [PickleDb::linsert_before()](struct.PickleDb.html#method.linsert_before).
+    ///
+    pub fn linsert_before<P, V>(&self, name: &str, pivot: &P, value: &V) -> Result<bool, PickleDbError>
+        where
+            P: Serialize,
+            V: Serialize
+    {
+        self.write_lock().linsert_before(name, pivot, value)
+    }
+
+    /// Insert an item into an existing list after another item. Please see
+    /// [PickleDb::linsert_after()](struct.PickleDb.html#method.linsert_after).
+    ///
+    pub fn linsert_after<P, V>(&self, name: &str, pivot: &P, value: &V) -> Result<bool, PickleDbError>
+        where
+            P: Serialize,
+            V: Serialize
+    {
+        self.write_lock().linsert_after(name, pivot, value)
+    }
+
+    /// Replace an item of an existing list. Please see [PickleDb::lset()](struct.PickleDb.html#method.lset).
+    ///
+    pub fn lset<V>(&self, name: &str, index: i64, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().lset(name, index, value)
+    }
+
+    /// Get an item of a list by its index. Please see [PickleDb::lindex()](struct.PickleDb.html#method.lindex).
+    ///
+    pub fn lindex<V>(&self, name: &str, index: i64) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().lindex(name, index)
+    }
+
+    /// Get a range of items of a list. Please see [PickleDb::lrange()](struct.PickleDb.html#method.lrange).
+    ///
+    pub fn lrange<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().lrange(name, start, end)
+    }
+
+    /// Trim an existing list to a range of items. Please see [PickleDb::ltrim()](struct.PickleDb.html#method.ltrim).
+    ///
+    pub fn ltrim(&self, name: &str, start: i64, end: i64) -> Result<usize, PickleDbError> {
+        self.write_lock().ltrim(name, start, end)
+    }
+
+    /// Remove several items that equal to a value out of a list. Please see
+    /// [PickleDb::lrem_value_all()](struct.PickleDb.html#method.lrem_value_all).
+    ///
+    pub fn lrem_value_all<V>(&self, name: &str, value: &V, count: i64) -> Result<usize, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().lrem_value_all(name, value, count)
+    }
+
+    /// Move an item from one list to another. Please see [PickleDb::lmove()](struct.PickleDb.html#method.lmove).
+    ///
+    pub fn lmove<V>(&self, source: &str, destination: &str, from: ListEnd, to: ListEnd) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.write_lock().lmove(source, destination, from, to)
+    }
+
+    /// Create a new dict. Please see [PickleDb::dcreate()](struct.PickleDb.html#method.dcreate).
+    ///
+    pub fn dcreate(&self, name: &str) -> Result<(), PickleDbError> {
+        self.write_lock().dcreate(name)
+    }
+
+    /// Add a key-value pair to an existing dict. Please see [PickleDb::dadd()](struct.PickleDb.html#method.dadd).
+    ///
+    pub fn dadd<V>(&self, name: &str, key: &str, value: &V) -> Result<(), PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().dadd(name, key, value)
+    }
+
+    /// Get the value of a key inside a dict. Please see [PickleDb::dget()](struct.PickleDb.html#method.dget).
+    ///
+    pub fn dget<V>(&self, name: &str, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().dget(name, key)
+    }
+
+    /// Get the value of a key inside a dict, telling a missing key apart from a wrong type. Please see
+    /// [PickleDb::try_dget()](struct.PickleDb.html#method.try_dget).
+    ///
+    pub fn try_dget<V>(&self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().try_dget(name, key)
+    }
+
+    /// Get all the key-value pairs of a dict. Please see [PickleDb::dgetall()](struct.PickleDb.html#method.dgetall).
+    ///
+    pub fn dgetall<V>(&self, name: &str) -> Option<HashMap<String, V>>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().dgetall(name)
+    }
+
+    /// Get the keys of a dict. Please see [PickleDb::dkeys()](struct.PickleDb.html#method.dkeys).
+    ///
+    pub fn dkeys(&self, name: &str) -> Option<Vec<String>> {
+        self.read_lock().dkeys(name)
+    }
+
+    /// Get the values of a dict. Please see [PickleDb::dvals()](struct.PickleDb.html#method.dvals).
+    ///
+    pub fn dvals<V>(&self, name: &str) -> Option<Vec<V>>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().dvals(name)
+    }
+
+    /// Check if a key exists inside a dict. Please see [PickleDb::dexists()](struct.PickleDb.html#method.dexists).
+    ///
+    pub fn dexists(&self, name: &str, key: &str) -> bool {
+        self.read_lock().dexists(name, key)
+    }
+
+    /// Get the number of key-value pairs in a dict. Please see [PickleDb::dlen()](struct.PickleDb.html#method.dlen).
+    ///
+    pub fn dlen(&self, name: &str) -> usize {
+        self.read_lock().dlen(name)
+    }
+
+    /// Pop a key-value pair out of a dict. Please see [PickleDb::dpop()](struct.PickleDb.html#method.dpop).
+    ///
+    pub fn dpop<V>(&self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.write_lock().dpop(name, key)
+    }
+
+    /// Remove a dict. Please see [PickleDb::drem()](struct.PickleDb.html#method.drem).
+    ///
+    pub fn drem(&self, name: &str) -> Result<usize, PickleDbError> {
+        self.write_lock().drem(name)
+    }
+
+    /// Merge a dict into another one. Please see [PickleDb::dmerge()](struct.PickleDb.html#method.dmerge).
+    ///
+    pub fn dmerge(&self, name: &str, other: &str) -> Result<(), PickleDbError> {
+        self.write_lock().dmerge(name, other)
+    }
+
+    /// Add a member to a set. Please see [PickleDb::sadd()](struct.PickleDb.html#method.sadd).
+    ///
+    pub fn sadd<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().sadd(name, value)
+    }
+
+    /// Remove a member from a set. Please see [PickleDb::srem()](struct.PickleDb.html#method.srem).
+    ///
+    pub fn srem<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().srem(name, value)
+    }
+
+    /// Check if a member is in a set. Please see [PickleDb::sismember()](struct.PickleDb.html#method.sismember).
+    ///
+    pub fn sismember<V>(&self, name: &str, value: &V) -> bool
+        where
+            V: Serialize
+    {
+        self.read_lock().sismember(name, value)
+    }
+
+    /// Get all the members of a set. Please see [PickleDb::smembers()](struct.PickleDb.html#method.smembers).
+    ///
+    pub fn smembers<V>(&self, name: &str) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().smembers(name)
+    }
+
+    /// Get the number of members in a set. Please see [PickleDb::scard()](struct.PickleDb.html#method.scard).
+    ///
+    pub fn scard(&self, name: &str) -> usize {
+        self.read_lock().scard(name)
+    }
+
+    /// Get the union of several sets. Please see [PickleDb::sunion()](struct.PickleDb.html#method.sunion).
+    ///
+    pub fn sunion<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().sunion(names)
+    }
+
+    /// Get the intersection of several sets. Please see [PickleDb::sinter()](struct.PickleDb.html#method.sinter).
+    ///
+    pub fn sinter<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().sinter(names)
+    }
+
+    /// Get the difference between a set and other sets. Please see [PickleDb::sdiff()](struct.PickleDb.html#method.sdiff).
+    ///
+    pub fn sdiff<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().sdiff(names)
+    }
+
+    /// Add a member to a sorted set or update its score. Please see [PickleDb::zadd()](struct.PickleDb.html#method.zadd).
+    ///
+    pub fn zadd<V>(&self, name: &str, score: f64, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().zadd(name, score, value)
+    }
+
+    /// Remove a member from a sorted set. Please see [PickleDb::zrem()](struct.PickleDb.html#method.zrem).
+    ///
+    pub fn zrem<V>(&self, name: &str, value: &V) -> Result<bool, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().zrem(name, value)
+    }
+
+    /// Get the score of a member of a sorted set. Please see [PickleDb::zscore()](struct.PickleDb.html#method.zscore).
+    ///
+    pub fn zscore<V>(&self, name: &str, value: &V) -> Option<f64>
+        where
+            V: Serialize
+    {
+        self.read_lock().zscore(name, value)
+    }
+
+    /// Get a range of members of a sorted set by their rank. Please see
+    /// [PickleDb::zrange_by_rank()](struct.PickleDb.html#method.zrange_by_rank).
+    ///
+    pub fn zrange_by_rank<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().zrange_by_rank(name, start, end)
+    }
+
+    /// Get the members of a sorted set whose score is within a range. Please see
+    /// [PickleDb::zrange_by_score()](struct.PickleDb.html#method.zrange_by_score).
+    ///
+    pub fn zrange_by_score<V>(&self, name: &str, min: f64, max: f64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.read_lock().zrange_by_score(name, min, max)
+    }
+
+    /// Increment the score of a member of a sorted set. Please see [PickleDb::zincrby()](struct.PickleDb.html#method.zincrby).
+    ///
+    pub fn zincrby<V>(&self, name: &str, by: f64, value: &V) -> Result<f64, PickleDbError>
+        where
+            V: Serialize
+    {
+        self.write_lock().zincrby(name, by, value)
+    }
+
+    /// Get the number of members in a sorted set. Please see [PickleDb::zcard()](struct.PickleDb.html#method.zcard).
+    ///
+    pub fn zcard(&self, name: &str) -> usize {
+        self.read_lock().zcard(name)
+    }
+
+    /// Start dumping the DB periodically in a background thread.
+    ///
+    /// Under all dump policies but [PickleDbDumpPolicy::AutoDump](enum.PickleDbDumpPolicy.html#variant.AutoDump)
+    /// changes may stay in memory until the next change or until the DB is dropped. For example with
+    /// [PickleDbDumpPolicy::PeriodicDump(Duration)](enum.PickleDbDumpPolicy.html#variant.PeriodicDump) the time since
+    /// the last dump is only checked when the DB is changed. This method starts a thread that wakes up every `interval`
+    /// and dumps the DB if it has changes that weren't dumped yet, so no more than one interval of changes may be lost.
+    /// Under [PickleDbDumpPolicy::AppendOnly](enum.PickleDbDumpPolicy.html#variant.AppendOnly) the changes are appended
+    /// to the log rather than dumped, and a DB loaded with [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump)
+    /// is never dumped.
+    ///
+    /// The thread runs until the returned [PickleDbFlusher](struct.PickleDbFlusher.html) is stopped or dropped, or until
+    /// the DB itself is dropped. Errors of dumps in the background are ignored and the dump is retried in the next interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - the time between dumps
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let db = SharedPickleDb::new(PickleDb::new("example.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json));
+    /// let flusher = db.start_flusher(Duration::from_secs(5));
+    ///
+    /// db.set("key1", &100)?;
+    ///
+    /// // stop the thread and dump the last changes
+    /// flusher.stop()?;
+    /// ```
+    ///
+    pub fn start_flusher(&self, interval: Duration) -> PickleDbFlusher {
+        let db = Arc::downgrade(&self.db);
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let thread_db = Weak::clone(&db);
+        let thread = thread::spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+                match thread_db.upgrade() {
+                    Some(db) => { let _ = SharedPickleDb { db }.write_lock().flush(); },
+                    None => break,
+                }
+            }
+        });
+        PickleDbFlusher { db, stop_sender, thread: Some(thread) }
+    }
+
+    /// Start reloading the DB in a background thread whenever its file changes.
+    ///
+    /// This method starts a thread that wakes up every `interval` and reloads the DB if its file or its append-only log
+    /// changed (please see [PickleDb::reload_if_changed()](struct.PickleDb.html#method.reload_if_changed)), so a DB
+    /// loaded with [PickleDbDumpPolicy::NeverDump](enum.PickleDbDumpPolicy.html#variant.NeverDump) follows the changes
+    /// that another process dumps to the same file. The data is replaced while the DB is locked for writing, so readers
+    /// see either the data before the reload or the data after it.
+    ///
+    /// The thread runs until the returned [PickleDbWatcher](struct.PickleDbWatcher.html) is stopped or dropped, or until
+    /// the DB itself is dropped. Errors of reloads in the background, for example of a DB file that is being replaced, are
+    /// ignored and the DB keeps its data until the reload succeeds in a later interval. A DB that has changes that weren't
+    /// dumped yet isn't reloaded, so they aren't lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - the time between checks of the file
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let db = SharedPickleDb::new(PickleDb::load_read_only("example.db", SerializationMethod::Json)?);
+    /// let watcher = db.start_watcher(Duration::from_millis(500));
+    ///
+    /// // the value that another process dumped most recently
+    /// let value = db.get::<i32>("key1");
+    ///
+    /// watcher.stop();
+    /// ```
+    ///
+    pub fn start_watcher(&self, interval: Duration) -> PickleDbWatcher {
+        let db = Arc::downgrade(&self.db);
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+                match db.upgrade() {
+                    Some(db) => { let _ = SharedPickleDb { db }.reload_if_changed(); },
+                    None => break,
+                }
+            }
+        });
+        PickleDbWatcher { stop_sender, thread: Some(thread) }
+    }
+
+    fn read_lock(&self) -> RwLockReadGuard<'_, PickleDb> {
+        // a panic of another thread doesn't leave the DB in an invalid state, so the DB remains usable
+        self.db.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_lock(&self) -> RwLockWriteGuard<'_, PickleDb> {
+        self.db.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl From<PickleDb> for SharedPickleDb {
+    fn from(db: PickleDb) -> SharedPickleDb {
+        SharedPickleDb::new(db)
+    }
+}
+
+/// A handle to a thread that dumps a [SharedPickleDb](struct.SharedPickleDb.html) periodically.
+/// Returned in [SharedPickleDb::start_flusher()](struct.SharedPickleDb.html#method.start_flusher).
+///
+/// The thread is stopped when this object is dropped or when [stop()](#method.stop) is called.
+pub struct PickleDbFlusher {
+    db: Weak<RwLock<PickleDb>>,
+    stop_sender: mpsc::Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PickleDbFlusher {
+    /// Stop the thread and dump the changes that weren't dumped yet.
+    ///
+    /// This method waits until the thread exits, and then dumps the DB once more so no changes are left in memory.
+    /// It returns an error if this last dump failed.
+    ///
+    pub fn stop(mut self) -> Result<(), PickleDbError> {
+        self.stop_thread();
+        match self.db.upgrade() {
+            Some(db) => SharedPickleDb { db }.write_lock().flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn stop_thread(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.stop_sender.send(());
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for PickleDbFlusher {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+/// A handle to a thread that reloads a [SharedPickleDb](struct.SharedPickleDb.html) whenever its file changes.
+/// Returned in [SharedPickleDb::start_watcher()](struct.SharedPickleDb.html#method.start_watcher).
+///
+/// The thread is stopped when this object is dropped or when [stop()](#method.stop) is called.
+pub struct PickleDbWatcher {
+    stop_sender: mpsc::Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PickleDbWatcher {
+    /// Stop the thread and wait until it exits.
+    ///
+    pub fn stop(mut self) {
+        self.stop_thread();
+    }
+
+    fn stop_thread(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.stop_sender.send(());
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for PickleDbWatcher {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
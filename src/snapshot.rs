@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::PickleDbError;
+use crate::iterators::{PickleDbDictIterator, PickleDbIterator, PickleDbListIterator, PickleDbSetIterator};
+use crate::PickleDb;
+
+/// A read-only point-in-time copy of a DB. Returned in [PickleDb::snapshot()](struct.PickleDb.html#method.snapshot)
+///
+/// Changes made to the DB after the snapshot was taken don't affect it. `PickleDbSnapshot` is cheap to clone and
+/// all clones share the same data, so it can be handed over to other threads. Keys that expire after the snapshot
+/// was taken aren't visible in it anymore, like in the DB.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let snapshot = db.snapshot();
+///
+/// let handle = thread::spawn(move || {
+///     for kv in snapshot.iter() {
+///         println!("{} was {}", kv.get_key(), kv.get_value::<i32>().unwrap());
+///     }
+///     snapshot.write_to("report.db")
+/// });
+///
+/// // changing the DB doesn't change the report
+/// db.set("key1", &100)?;
+/// handle.join().unwrap()?;
+/// ```
+///
+#[derive(Clone)]
+pub struct PickleDbSnapshot {
+    db: Arc<PickleDb>,
+}
+
+impl PickleDbSnapshot {
+    /// A snapshot that owns a read-only DB which nothing else refers to
+    pub(crate) fn new(db: PickleDb) -> PickleDbSnapshot {
+        PickleDbSnapshot { db: Arc::new(db) }
+    }
+
+    /// Write the snapshot to a file.
+    ///
+    /// The file is written the same way the DB file is written by [PickleDb::save_as()](struct.PickleDb.html#method.save_as),
+    /// so it can be loaded like the DB file. This method returns [PickleDbError::Io](enum.PickleDbError.html#variant.Io)
+    /// if the file couldn't be written or if it's the file of the DB the snapshot was taken of, and [PickleDbError::Serialization](enum.PickleDbError.html#variant.Serialization)
+    /// if the data couldn't be serialized.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the path of the file. An existing file other than the DB file is replaced
+    ///
+    pub fn write_to(&self, path: &str) -> Result<(), PickleDbError> {
+        self.db.write_copy(path)
+    }
+
+    /// Get a value of a key. Please see [PickleDb::get()](struct.PickleDb.html#method.get).
+    ///
+    pub fn get<V>(&self, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.db.get(key)
+    }
+
+    /// Get a value of a key, telling a missing key apart from a wrong type. Please see
+    /// [PickleDb::try_get()](struct.PickleDb.html#method.try_get).
+    ///
+    pub fn try_get<V>(&self, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.try_get(key)
+    }
+
+    /// Check if a key exists. Please see [PickleDb::exists()](struct.PickleDb.html#method.exists).
+    ///
+    pub fn exists(&self, key: &str) -> bool {
+        self.db.exists(key)
+    }
+
+    /// Get a vector of all the keys. Please see [PickleDb::get_all()](struct.PickleDb.html#method.get_all).
+    ///
+    pub fn get_all(&self) -> Vec<String> {
+        self.db.get_all()
+    }
+
+    /// Get the total number of keys. Please see [PickleDb::total_keys()](struct.PickleDb.html#method.total_keys).
+    ///
+    pub fn total_keys(&self) -> usize {
+        self.db.total_keys()
+    }
+
+    /// Check if a list exists. Please see [PickleDb::lexists()](struct.PickleDb.html#method.lexists).
+    ///
+    pub fn lexists(&self, name: &str) -> bool {
+        self.db.lexists(name)
+    }
+
+    /// Get an item of of a certain list in a certain position. Please see [PickleDb::lget()](struct.PickleDb.html#method.lget).
+    ///
+    pub fn lget<V>(&self, name: &str, pos: usize) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.db.lget(name, pos)
+    }
+
+    /// Get an item of of a certain list in a certain position, telling a missing item apart from a wrong type.
+    /// Please see [PickleDb::try_lget()](struct.PickleDb.html#method.try_lget).
+    ///
+    pub fn try_lget<V>(&self, name: &str, pos: usize) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.try_lget(name, pos)
+    }
+
+    /// Get the length of a list. Please see [PickleDb::llen()](struct.PickleDb.html#method.llen).
+    ///
+    pub fn llen(&self, name: &str) -> usize {
+        self.db.llen(name)
+    }
+
+    /// Return an iterator over the keys and values. Please see [PickleDb::iter()](struct.PickleDb.html#method.iter).
+    ///
+    pub fn iter(&self) -> PickleDbIterator<'_> {
+        self.db.iter()
+    }
+
+    /// Return an iterator over the items in certain list. Please see [PickleDb::liter()](struct.PickleDb.html#method.liter).
+    ///
+    pub fn liter<'a>(&'a self, name: &'a str) -> Result<PickleDbListIterator<'a>, PickleDbError> {
+        self.db.liter(name)
+    }
+
+    /// Get the value of a key in a dict. Please see [PickleDb::dget()](struct.PickleDb.html#method.dget).
+    ///
+    pub fn dget<V>(&self, name: &str, key: &str) -> Option<V>
+        where
+            V: DeserializeOwned
+    {
+        self.db.dget(name, key)
+    }
+
+    /// Get the value of a key in a dict, telling a missing key apart from a wrong type. Please see
+    /// [PickleDb::try_dget()](struct.PickleDb.html#method.try_dget).
+    ///
+    pub fn try_dget<V>(&self, name: &str, key: &str) -> Result<Option<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.try_dget(name, key)
+    }
+
+    /// Get all the keys and values of a dict. Please see [PickleDb::dgetall()](struct.PickleDb.html#method.dgetall).
+    ///
+    pub fn dgetall<V>(&self, name: &str) -> Option<HashMap<String, V>>
+        where
+            V: DeserializeOwned
+    {
+        self.db.dgetall(name)
+    }
+
+    /// Get the keys of a dict. Please see [PickleDb::dkeys()](struct.PickleDb.html#method.dkeys).
+    ///
+    pub fn dkeys(&self, name: &str) -> Option<Vec<String>> {
+        self.db.dkeys(name)
+    }
+
+    /// Get the values of a dict. Please see [PickleDb::dvals()](struct.PickleDb.html#method.dvals).
+    ///
+    pub fn dvals<V>(&self, name: &str) -> Option<Vec<V>>
+        where
+            V: DeserializeOwned
+    {
+        self.db.dvals(name)
+    }
+
+    /// Check if a key exists in a dict. Please see [PickleDb::dexists()](struct.PickleDb.html#method.dexists).
+    ///
+    pub fn dexists(&self, name: &str, key: &str) -> bool {
+        self.db.dexists(name, key)
+    }
+
+    /// Get the number of keys in a dict. Please see [PickleDb::dlen()](struct.PickleDb.html#method.dlen).
+    ///
+    pub fn dlen(&self, name: &str) -> usize {
+        self.db.dlen(name)
+    }
+
+    /// Return an iterator over the keys and values of a dict. Please see [PickleDb::diter()](struct.PickleDb.html#method.diter).
+    ///
+    pub fn diter<'a>(&'a self, name: &'a str) -> Result<PickleDbDictIterator<'a>, PickleDbError> {
+        self.db.diter(name)
+    }
+
+    /// Check if a value is a member of a set. Please see [PickleDb::sismember()](struct.PickleDb.html#method.sismember).
+    ///
+    pub fn sismember<V>(&self, name: &str, value: &V) -> bool
+        where
+            V: Serialize
+    {
+        self.db.sismember(name, value)
+    }
+
+    /// Get the members of a set. Please see [PickleDb::smembers()](struct.PickleDb.html#method.smembers).
+    ///
+    pub fn smembers<V>(&self, name: &str) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.smembers(name)
+    }
+
+    /// Get the number of members of a set. Please see [PickleDb::scard()](struct.PickleDb.html#method.scard).
+    ///
+    pub fn scard(&self, name: &str) -> usize {
+        self.db.scard(name)
+    }
+
+    /// Get the union of several sets. Please see [PickleDb::sunion()](struct.PickleDb.html#method.sunion).
+    ///
+    pub fn sunion<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.sunion(names)
+    }
+
+    /// Get the intersection of several sets. Please see [PickleDb::sinter()](struct.PickleDb.html#method.sinter).
+    ///
+    pub fn sinter<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.sinter(names)
+    }
+
+    /// Get the difference between a set and other sets. Please see [PickleDb::sdiff()](struct.PickleDb.html#method.sdiff).
+    ///
+    pub fn sdiff<V>(&self, names: &[&str]) -> Result<Vec<V>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.sdiff(names)
+    }
+
+    /// Return an iterator over the members of a set. Please see [PickleDb::siter()](struct.PickleDb.html#method.siter).
+    ///
+    pub fn siter<'a>(&'a self, name: &'a str) -> Result<PickleDbSetIterator<'a>, PickleDbError> {
+        self.db.siter(name)
+    }
+
+    /// Get the score of a member of a sorted set. Please see [PickleDb::zscore()](struct.PickleDb.html#method.zscore).
+    ///
+    pub fn zscore<V>(&self, name: &str, value: &V) -> Option<f64>
+        where
+            V: Serialize
+    {
+        self.db.zscore(name, value)
+    }
+
+    /// Get the members of a sorted set by their rank. Please see [PickleDb::zrange_by_rank()](struct.PickleDb.html#method.zrange_by_rank).
+    ///
+    pub fn zrange_by_rank<V>(&self, name: &str, start: i64, end: i64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.zrange_by_rank(name, start, end)
+    }
+
+    /// Get the members of a sorted set by their score. Please see [PickleDb::zrange_by_score()](struct.PickleDb.html#method.zrange_by_score).
+    ///
+    pub fn zrange_by_score<V>(&self, name: &str, min: f64, max: f64) -> Result<Vec<(V, f64)>, PickleDbError>
+        where
+            V: DeserializeOwned
+    {
+        self.db.zrange_by_score(name, min, max)
+    }
+
+    /// Get the number of members of a sorted set. Please see [PickleDb::zcard()](struct.PickleDb.html#method.zcard).
+    ///
+    pub fn zcard(&self, name: &str) -> usize {
+        self.db.zcard(name)
+    }
+}
@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::iter::FromIterator;
+use std::ops::Bound;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::serialization::SerializedValue;
+
+/// A score of a sorted set member. Scores are finite numbers, so they can be totally ordered
+#[derive(Clone, Copy)]
+struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Score) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Score) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Score) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The members of a sorted set and their scores. Members are ordered by their score and members with
+/// the same score are ordered by their serialized form, so ranges by rank and by score don't need sorting.
+/// Please see [PickleDb::zadd()](struct.PickleDb.html#method.zadd)
+#[derive(Clone, Default)]
+pub(crate) struct SortedSet {
+    scores: HashMap<SerializedValue, f64>,
+    order: BTreeSet<(Score, SerializedValue)>,
+}
+
+impl SortedSet {
+    pub(crate) fn new() -> SortedSet {
+        SortedSet::default()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub(crate) fn score(&self, member: &SerializedValue) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Set the score of a member, adding the member if it isn't in the set. Returns whether the member was added
+    pub(crate) fn insert(&mut self, member: SerializedValue, score: f64) -> bool {
+        let score = normalize(score);
+        let added = match self.scores.insert(member.clone(), score) {
+            Some(old_score) => {
+                self.order.remove(&(Score(old_score), member.clone()));
+                false
+            },
+            None => true,
+        };
+        self.order.insert((Score(score), member));
+        added
+    }
+
+    /// Remove a member. Returns whether the member was in the set
+    pub(crate) fn remove(&mut self, member: &SerializedValue) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => self.order.remove(&(Score(score), member.clone())),
+            None => false,
+        }
+    }
+
+    /// The members and their scores, from the lowest score to the highest
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&SerializedValue, f64)> {
+        self.order.iter().map(|(score, member)| (member, score.0))
+    }
+
+    /// The members whose score is between `min` and `max`, including both, from the lowest score to the highest
+    pub(crate) fn range_by_score(&self, min: f64, max: f64) -> impl Iterator<Item = (&SerializedValue, f64)> {
+        // an empty serialized value comes before all members, so the range starts at the first member with the min score
+        let start = (Score(normalize(min)), SerializedValue(Vec::new()));
+        let max = Score(normalize(max));
+        self.order.range((Bound::Included(start), Bound::Unbounded))
+            .take_while(move |(score, _)| *score <= max)
+            .map(|(score, member)| (member, score.0))
+    }
+}
+
+impl FromIterator<(SerializedValue, f64)> for SortedSet {
+    fn from_iter<I: IntoIterator<Item = (SerializedValue, f64)>>(iter: I) -> SortedSet {
+        let mut set = SortedSet::new();
+        for (member, score) in iter {
+            set.insert(member, score);
+        }
+        set
+    }
+}
+
+/// A sorted set is written as a sequence of member and score pairs, ordered by score
+impl Serialize for SortedSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for SortedSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>
+    {
+        let pairs = Vec::<(SerializedValue, f64)>::deserialize(deserializer)?;
+        // scores that aren't finite can't be ordered, so they're never written by a DB
+        if pairs.iter().any(|(_, score)| !score.is_finite()) {
+            return Err(de::Error::custom("a score of a sorted set isn't a finite number"));
+        }
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+/// Zero and negative zero are the same score
+fn normalize(score: f64) -> f64 {
+    if score == 0.0 {
+        0.0
+    } else {
+        score
+    }
+}
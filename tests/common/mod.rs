@@ -13,9 +13,14 @@ impl TestResources {
 
 impl Drop for TestResources {
     fn drop(&mut self) {
-        let path = Path::new(&self.file); 
-        if path.exists() {
-            let _ignore = fs::remove_file(path);
+        let log_file = format!("{}.log", self.file);
+        let lock_file = format!("{}.lock", self.file);
+        let backup_time_file = format!("{}.bak.time", self.file);
+        for file in &[&self.file, &log_file, &lock_file, &backup_time_file] {
+            let path = Path::new(file); 
+            if path.exists() {
+                let _ignore = fs::remove_file(path);
+            }
         }
     }
 }
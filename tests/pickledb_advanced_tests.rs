@@ -1,4 +1,5 @@
-use pickledb::{PickleDb, PickleDbDumpPolicy};
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::fs;
 use std::iter;
 use std::collections::HashMap;
 use rand::{Rng, thread_rng};
@@ -13,24 +14,24 @@ extern crate serde_derive;
 fn lists_and_values() {
     set_test_rsc!("lists_and_values.db");
 
-    let mut db = PickleDb::new("lists_and_values.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("lists_and_values.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // set a few values
-    db.set("key1", &String::from("val1"));
-    db.set("key2", &1);
-    db.set("key3", &vec![1,2,3]);
+    db.set("key1", &String::from("val1")).unwrap();
+    db.set("key2", &1).unwrap();
+    db.set("key3", &vec![1,2,3]).unwrap();
 
     // create a few lists and add values to them
-    db.lcreate("list1")
-      .lextend(&vec![1,2,3]);
+    db.lcreate("list1").unwrap()
+      .lextend(&[1,2,3]).unwrap();
 
-    db.lcreate("list2")
-      .ladd(&1.1)
-      .ladd(&String::from("some val"));
+    db.lcreate("list2").unwrap()
+      .ladd(&1.1).unwrap()
+      .ladd(&String::from("some val")).unwrap();
 
     // read keys and lists
     {
-        let read_db = PickleDb::load_read_only("lists_and_values.db").unwrap();
+        let read_db = PickleDb::load_read_only("lists_and_values.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.get::<String>("key1").unwrap(), String::from("val1"));
         assert_eq!(read_db.get::<i32>("key2").unwrap(), 1);
         assert_eq!(read_db.get::<Vec<i32>>("key3").unwrap(), vec![1,2,3]);
@@ -41,15 +42,15 @@ fn lists_and_values() {
     }
 
     // create key and list with the same name, make sure they override one another
-    db.set("key_or_list1", &1);
-    db.lcreate("key_or_list1");
+    db.set("key_or_list1", &1).unwrap();
+    db.lcreate("key_or_list1").unwrap();
 
     assert!(db.exists("key_or_list1"));
     assert!(db.get::<i32>("key_or_list1").is_none());
     assert!(db.lexists("key_or_list1"));
 
     // now set the key again and verify list is removed
-    db.set("key_or_list1", &2);
+    db.set("key_or_list1", &2).unwrap();
 
     assert!(db.exists("key_or_list1"));
     assert_eq!(db.get::<i32>("key_or_list1").unwrap(), 2);
@@ -68,7 +69,7 @@ fn gen_random_string<T: Rng>(rng: &mut T, size: usize) -> String {
 fn load_test() {
     set_test_rsc!("load_test.db");
 
-    let mut db = PickleDb::new("load_test.db", PickleDbDumpPolicy::DumpUponRequest);
+    let mut db = PickleDb::new("load_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
 
     // number of keys to generate
     let generate_keys = 1000;
@@ -85,7 +86,7 @@ fn load_test() {
         let mut key: String = gen_random_string(&mut rng, key_len as usize);
 
         // if key already exists, generate another one
-        while map.get(&key).is_some() {
+        while map.contains_key(&key) {
             key_len = rng.gen_range(3, 15);
             key = gen_random_string(&mut rng, key_len as usize);
         }
@@ -96,17 +97,17 @@ fn load_test() {
         // randomly choose a type
         match possible_value_types.choose(&mut rng).unwrap() {
             1 => { // add a i32 value
-                db.set(&key, &rng.gen::<i32>());
-                map.insert(String::from(key), "i32");
+                db.set(&key, &rng.gen::<i32>()).unwrap();
+                map.insert(key, "i32");
             },
             2 => { // add a f32 value
-                db.set(&key, &rng.gen::<f32>());
-                map.insert(String::from(key), "f32");
+                db.set(&key, &rng.gen::<f32>()).unwrap();
+                map.insert(key, "f32");
             },
             3 => { // add a String value
                 let val_size = rng.gen_range(1, 50);
-                db.set(&key, &gen_random_string(&mut rng, val_size));
-                map.insert(String::from(key), "string");
+                db.set(&key, &gen_random_string(&mut rng, val_size)).unwrap();
+                map.insert(key, "string");
             },
             4 => { // add a Vec<i32> value
                 // randomize vec size 1..10
@@ -117,8 +118,8 @@ fn load_test() {
                 for _ in 1..vec_size {
                     vec.push(rng.gen::<i32>());
                 }
-                db.set(&key, &vec);
-                map.insert(String::from(key), "vec");
+                db.set(&key, &vec).unwrap();
+                map.insert(key, "vec");
             },
             5 => { // add a List value
 
@@ -127,9 +128,9 @@ fn load_test() {
                 list_key.insert_str(0, "list_");
 
                 // create the list
-                db.lcreate(&list_key);
+                db.lcreate(&list_key).unwrap();
 
-                map.insert(String::from(list_key.clone()), "list");
+                map.insert(list_key.clone(), "list");
 
                 // randomize list size 1..50
                 let list_size: u32 = rng.gen_range(1, 50);
@@ -140,14 +141,14 @@ fn load_test() {
                     // randomly choose an element type: [i32, f32, String, Vec]
                     match possible_value_types[..4].choose(&mut rng).unwrap() {
                         1 => { // add a i32 element to the list
-                            assert!(db.ladd(&list_key, &rng.gen::<i32>()).is_some());
+                            assert!(db.ladd(&list_key, &rng.gen::<i32>()).is_ok());
                         },
                         2 => { // add a f32 element to the list
-                            assert!(db.ladd(&list_key, &rng.gen::<f32>()).is_some());
+                            assert!(db.ladd(&list_key, &rng.gen::<f32>()).is_ok());
                         }, 
                         3 => { // add a String element to the list
                             let val_size = rng.gen_range(1, 50);
-                            assert!(db.ladd(&list_key, &gen_random_string(&mut rng, val_size)).is_some());
+                            assert!(db.ladd(&list_key, &gen_random_string(&mut rng, val_size)).is_ok());
                         },
                         4 => { // add a Vec<i32> element to the list
                             // randomize vec size 1..10
@@ -160,7 +161,7 @@ fn load_test() {
                             }
 
                             // add vec as an element to the list
-                            assert!(db.ladd(&list_key, &vec).is_some());
+                            assert!(db.ladd(&list_key, &vec).is_ok());
                         },
                         _ => panic!("Cannot add list inside a list!"),
                     }
@@ -174,28 +175,60 @@ fn load_test() {
     assert_eq!(map.iter().len(), generate_keys);
 
     // dump DB to file
-    db.dump();
+    db.dump().unwrap();
     
     // read again from file
-    let read_db = PickleDb::load_read_only("load_test.db").unwrap();
+    let read_db = PickleDb::load_read_only("load_test.db", SerializationMethod::Json).unwrap();
 
     // iterate every key/value_type in map saved before
     for (key, val_type) in map.iter() {
         
         // verify key exists in db
-        assert!(read_db.exists(&key), format!("Key {} of type {} isn't found", key, val_type));
+        assert!(read_db.exists(key), "Key {} of type {} isn't found", key, val_type);
 
         // get the value according to the value_type saved
-        match val_type {
-            &"i32" => assert!(read_db.get::<i32>(&key).is_some()),
-            &"f32" => assert!(read_db.get::<f32>(&key).is_some()),
-            &"string" => assert!(read_db.get::<String>(&key).is_some()),
-            &"vec" => assert!(read_db.get::<Vec<i32>>(&key).is_some()),
-            &"list" => assert!(read_db.lexists(&key)),
+        match *val_type {
+            "i32" => assert!(read_db.get::<i32>(key).is_some()),
+            "f32" => assert!(read_db.get::<f32>(key).is_some()),
+            "string" => assert!(read_db.get::<String>(key).is_some()),
+            "vec" => assert!(read_db.get::<Vec<i32>>(key).is_some()),
+            "list" => assert!(read_db.lexists(key)),
             _ => (),
         }
     }
 
     // check that the total number of keys in db equals to number of keys generated
     assert_eq!(read_db.total_keys(), generate_keys);
-}
\ No newline at end of file
+}
+
+#[test]
+fn load_errors() {
+    set_test_rsc!("load_errors.db");
+
+    // loading a file that doesn't exist is an I/O error
+    assert!(matches!(PickleDb::load_read_only("load_errors.db", SerializationMethod::Json), Err(PickleDbError::Io(_))));
+
+    // loading a file that isn't a DB is reported as a corrupted file
+    fs::write("load_errors.db", "this is not a DB").unwrap();
+    assert!(matches!(PickleDb::load_read_only("load_errors.db", SerializationMethod::Json), Err(PickleDbError::Corrupted(_))));
+
+    // loading a DB with the wrong serialization method is reported as a corrupted file as well
+    {
+        let mut db = PickleDb::new("load_errors.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+        db.set("key1", &1).unwrap();
+    }
+    assert!(matches!(PickleDb::load_read_only("load_errors.db", SerializationMethod::Bin), Err(PickleDbError::Corrupted(_))));
+}
+
+#[test]
+fn dump_error() {
+    // the directory of the DB file doesn't exist, so dumping fails
+    let mut db = PickleDb::new("dump_error_no_such_dir/dump_error.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    assert!(matches!(db.dump(), Err(PickleDbError::Io(_))));
+
+    // under AutoDump the error is returned by the API that made the change, but the change is kept
+    let mut db = PickleDb::new("dump_error_no_such_dir/dump_error.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    assert!(matches!(db.set("key1", &1), Err(PickleDbError::Io(_))));
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+}
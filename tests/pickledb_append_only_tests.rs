@@ -0,0 +1,283 @@
+use pickledb::{ListEnd, PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+mod common;
+
+fn file_len(file_name: &str) -> u64 {
+    fs::metadata(file_name).unwrap().len()
+}
+
+#[test]
+fn append_only_replay() {
+    set_test_rsc!("append_only_replay.db");
+
+    for ser_method in &[SerializationMethod::Json, SerializationMethod::Bin, SerializationMethod::Yaml,
+                        SerializationMethod::Cbor, SerializationMethod::MsgPack] {
+        let mut db = PickleDb::new("append_only_replay.db", PickleDbDumpPolicy::AppendOnly, *ser_method);
+
+        db.set("key1", &1).unwrap();
+        db.set("key2", &String::from("value2")).unwrap();
+        db.set_with_ttl("key3", &3, Duration::from_secs(3600)).unwrap();
+        db.lcreate("list1").unwrap().lextend(&[1,2,3]).unwrap();
+        db.lpop::<i32>("list1", 0).unwrap();
+        db.rem("key2").unwrap();
+        db.dcreate("dict1").unwrap();
+        db.dadd("dict1", "a", &1).unwrap();
+        db.sadd("set1", &1).unwrap();
+        db.sadd("set1", &2).unwrap();
+        db.srem("set1", &1).unwrap();
+        db.zadd("zset1", 1.0, &1).unwrap();
+        db.zincrby("zset1", 2.0, &1).unwrap();
+
+        // the key is changed from a value to a list
+        db.lcreate("key1").unwrap().ladd(&10).unwrap();
+
+        // the log is replayed on load
+        let read_db = PickleDb::load_read_only("append_only_replay.db", *ser_method).unwrap();
+        assert!(!read_db.exists("key2"));
+        assert_eq!(read_db.get::<i32>("key3").unwrap(), 3);
+        assert!(read_db.ttl("key3").unwrap().is_some());
+        assert_eq!(read_db.llen("list1"), 2);
+        assert_eq!(read_db.lget::<i32>("list1", 0).unwrap(), 2);
+        assert!(read_db.lexists("key1"));
+        assert_eq!(read_db.lget::<i32>("key1", 0).unwrap(), 10);
+        assert_eq!(read_db.dget::<i32>("dict1", "a").unwrap(), 1);
+        assert_eq!(read_db.smembers::<i32>("set1").unwrap(), vec![2]);
+        assert_eq!(read_db.zscore("zset1", &1), Some(3.0));
+        assert_eq!(read_db.total_keys(), 6);
+    }
+}
+
+#[test]
+fn append_only_writes_log() {
+    set_test_rsc!("append_only_writes_log.db");
+
+    let mut db = PickleDb::new("append_only_writes_log.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Bin);
+    for i in 0..100 {
+        db.set(&format!("key{}", i), &i).unwrap();
+    }
+
+    // changes are appended to the log, the DB file isn't rewritten
+    let db_file_len = file_len("append_only_writes_log.db");
+    let log_file_len = file_len("append_only_writes_log.db.log");
+    db.set("key100", &100).unwrap();
+    assert_eq!(file_len("append_only_writes_log.db"), db_file_len);
+    assert!(file_len("append_only_writes_log.db.log") > log_file_len);
+
+    // compact writes the whole DB to the DB file and truncates the log
+    db.compact().unwrap();
+    assert!(file_len("append_only_writes_log.db") > db_file_len);
+    assert_eq!(file_len("append_only_writes_log.db.log"), 0);
+
+    // changes after compacting are appended to the log again
+    db.rem("key0").unwrap();
+    assert!(file_len("append_only_writes_log.db.log") > 0);
+
+    let read_db = PickleDb::load_read_only("append_only_writes_log.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.total_keys(), 100);
+    assert!(!read_db.exists("key0"));
+    assert_eq!(read_db.get::<i32>("key100").unwrap(), 100);
+}
+
+#[test]
+fn append_only_load_and_continue() {
+    set_test_rsc!("append_only_load_and_continue.db");
+
+    {
+        let mut db = PickleDb::new("append_only_load_and_continue.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+        db.set("key1", &1).unwrap();
+        db.set("key2", &2).unwrap();
+    }
+
+    {
+        let mut db = PickleDb::load("append_only_load_and_continue.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json).unwrap();
+        assert_eq!(db.get::<i32>("key2").unwrap(), 2);
+        db.set("key2", &20).unwrap();
+        db.set("key3", &3).unwrap();
+    }
+
+    let read_db = PickleDb::load_read_only("append_only_load_and_continue.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+    assert_eq!(read_db.get::<i32>("key2").unwrap(), 20);
+    assert_eq!(read_db.get::<i32>("key3").unwrap(), 3);
+
+    // dumping with another policy writes the log into the DB file and removes the log
+    let mut db = PickleDb::load("append_only_load_and_continue.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json).unwrap();
+    db.dump().unwrap();
+    assert!(!Path::new("append_only_load_and_continue.db.log").exists());
+    let read_db = PickleDb::load_read_only("append_only_load_and_continue.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key2").unwrap(), 20);
+}
+
+#[test]
+fn append_only_new_db_replaces_old_log() {
+    set_test_rsc!("append_only_new_db_replaces_old_log.db");
+
+    {
+        let mut db = PickleDb::new("append_only_new_db_replaces_old_log.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+        db.set("old_key", &1).unwrap();
+        db.set("other_key", &1).unwrap();
+    }
+
+    // a new DB at the same location doesn't see the keys of the old log
+    let mut db = PickleDb::new("append_only_new_db_replaces_old_log.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+    db.set("new_key", &2).unwrap();
+
+    let read_db = PickleDb::load_read_only("append_only_new_db_replaces_old_log.db", SerializationMethod::Json).unwrap();
+    assert!(!read_db.exists("old_key"));
+    assert!(read_db.exists("new_key"));
+    assert_eq!(read_db.total_keys(), 1);
+}
+
+#[test]
+fn append_only_transaction() {
+    set_test_rsc!("append_only_transaction.db");
+
+    let mut db = PickleDb::new("append_only_transaction.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+
+    // a rolled back transaction doesn't reach the log
+    let res = db.transaction(|tx| {
+        tx.set("key1", &2)?;
+        tx.ladd("list1", &1)?;
+        Ok(())
+    });
+    assert!(matches!(res, Err(PickleDbError::KeyNotFound(_))));
+    let read_db = PickleDb::load_read_only("append_only_transaction.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+
+    // a committed transaction is appended to the log
+    db.transaction(|tx| -> Result<(), PickleDbError> {
+        tx.set("key1", &3)?;
+        tx.set("key2", &4)?;
+        Ok(())
+    }).unwrap();
+    let read_db = PickleDb::load_read_only("append_only_transaction.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 3);
+    assert_eq!(read_db.get::<i32>("key2").unwrap(), 4);
+}
+
+#[test]
+fn append_only_rolled_back_keys() {
+    set_test_rsc!("append_only_rolled_back_keys.db");
+
+    let mut db = PickleDb::new("append_only_rolled_back_keys.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+
+    // the keys changed in a rolled back transaction aren't appended with the next change
+    db.begin().unwrap();
+    db.set("rolled_back_key", &2).unwrap();
+    db.rollback().unwrap();
+    db.set("key1", &3).unwrap();
+
+    let log = fs::read("append_only_rolled_back_keys.db.log").unwrap();
+    assert!(!String::from_utf8_lossy(&log).contains("rolled_back_key"));
+    let read_db = PickleDb::load_read_only("append_only_rolled_back_keys.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(3));
+    assert!(!read_db.exists("rolled_back_key"));
+}
+
+#[test]
+fn append_only_torn_and_corrupted_log() {
+    set_test_rsc!("append_only_torn_and_corrupted_log.db");
+
+    let mut db = PickleDb::new("append_only_torn_and_corrupted_log.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.set("key2", &2).unwrap();
+    db.set("key3", &3).unwrap();
+
+    // a record that was only partially written at the end of the log is ignored
+    let log = fs::read("append_only_torn_and_corrupted_log.db.log").unwrap();
+    fs::write("append_only_torn_and_corrupted_log.db.log", &log[..log.len() - 3]).unwrap();
+    let read_db = PickleDb::load_read_only("append_only_torn_and_corrupted_log.db", SerializationMethod::Json).unwrap();
+    assert!(read_db.exists("key2"));
+    assert!(!read_db.exists("key3"));
+
+    // a record that can't be deserialized is an error
+    let mut corrupted = log.clone();
+    corrupted[5] = b'#';
+    fs::write("append_only_torn_and_corrupted_log.db.log", &corrupted).unwrap();
+    assert!(matches!(PickleDb::load_read_only("append_only_torn_and_corrupted_log.db", SerializationMethod::Json),
+                     Err(PickleDbError::Corrupted(_))));
+}
+
+#[test]
+fn append_only_torn_transaction() {
+    set_test_rsc!("append_only_torn_transaction.db");
+
+    let mut db = PickleDb::new("append_only_torn_transaction.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.set("key2", &1).unwrap();
+    let committed_len = file_len("append_only_torn_transaction.db.log") as usize;
+    db.transaction(|tx| -> Result<(), PickleDbError> {
+        tx.set("key1", &2)?;
+        tx.set("key2", &2)?;
+        tx.set("key3", &2)?;
+        Ok(())
+    }).unwrap();
+    drop(db);
+
+    // the process crashed right after the first record of the transaction was written
+    let log = fs::read("append_only_torn_transaction.db.log").unwrap();
+    let first_len = 4 + u32::from_le_bytes([log[committed_len], log[committed_len + 1], log[committed_len + 2], log[committed_len + 3]]) as usize;
+    assert!(committed_len + first_len < log.len());
+    fs::write("append_only_torn_transaction.db.log", &log[..committed_len + first_len]).unwrap();
+
+    // none of the changes of the transaction are replayed, and they're cut off the log
+    let db = PickleDb::load("append_only_torn_transaction.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json).unwrap();
+    assert_eq!(db.get::<i32>("key1"), Some(1));
+    assert_eq!(db.get::<i32>("key2"), Some(1));
+    assert!(!db.exists("key3"));
+    assert_eq!(file_len("append_only_torn_transaction.db.log"), committed_len as u64);
+}
+
+#[test]
+fn append_only_torn_log_then_append() {
+    set_test_rsc!("append_only_torn_log_then_append.db");
+
+    let mut db = PickleDb::new("append_only_torn_log_then_append.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Bin);
+    db.set("key1", &1).unwrap();
+    db.set("key2", &2).unwrap();
+    db.set("key3", &3).unwrap();
+    drop(db);
+
+    // the process crashed while appending the last record
+    let log = fs::read("append_only_torn_log_then_append.db.log").unwrap();
+    fs::write("append_only_torn_log_then_append.db.log", &log[..log.len() - 3]).unwrap();
+
+    // a read-only DB doesn't touch the log
+    PickleDb::load_read_only("append_only_torn_log_then_append.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(file_len("append_only_torn_log_then_append.db.log"), log.len() as u64 - 3);
+
+    // the changes appended after loading the DB again are kept
+    let mut db = PickleDb::load("append_only_torn_log_then_append.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Bin).unwrap();
+    assert!(!db.exists("key3"));
+    db.set("key4", &4).unwrap();
+    db.set("key5", &5).unwrap();
+    drop(db);
+
+    let read_db = PickleDb::load_read_only("append_only_torn_log_then_append.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.get::<i32>("key2"), Some(2));
+    assert!(!read_db.exists("key3"));
+    assert_eq!(read_db.get::<i32>("key4"), Some(4));
+    assert_eq!(read_db.get::<i32>("key5"), Some(5));
+}
+
+#[test]
+fn append_only_move_between_lists() {
+    set_test_rsc!("append_only_move_between_lists.db");
+
+    {
+        let mut db = PickleDb::new("append_only_move_between_lists.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::MsgPack);
+        db.lcreate("queue").unwrap().lextend(&[1, 2, 3]).unwrap();
+        db.lcreate("done").unwrap();
+        db.lmove::<i32>("queue", "done", ListEnd::Front, ListEnd::Back).unwrap();
+    }
+
+    // both lists are appended to the log
+    let db = PickleDb::load_read_only("append_only_move_between_lists.db", SerializationMethod::MsgPack).unwrap();
+    assert_eq!(db.lrange::<i32>("queue", 0, -1).unwrap(), vec![2, 3]);
+    assert_eq!(db.lrange::<i32>("done", 0, -1).unwrap(), vec![1]);
+}
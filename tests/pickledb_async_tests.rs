@@ -0,0 +1,267 @@
+#![cfg(feature = "async")]
+
+use pickledb::{AsyncPickleDb, ListEnd, PickleDb, PickleDbDumpPolicy, PickleDbError, PickleDbEvent, SerializationMethod};
+use std::time::Duration;
+
+mod common;
+
+#[tokio::test]
+async fn async_set_get_load() {
+    set_test_rsc!("async_set_get_load.db");
+
+    let db = AsyncPickleDb::new("async_set_get_load.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &100).await.unwrap();
+    db.set("key2", &String::from("value2")).await.unwrap();
+    db.set_with_ttl("key3", &3, Duration::from_secs(3600)).await.unwrap();
+    assert_eq!(db.get::<i32>("key1").await.unwrap(), 100);
+    assert_eq!(db.get::<String>("key2").await.unwrap(), "value2");
+    assert!(db.ttl("key3").await.unwrap().is_some());
+    assert!(db.rem("key2").await.unwrap());
+    assert_eq!(db.total_keys().await, 2);
+
+    // every change was dumped according to the dump policy
+    let read_db = AsyncPickleDb::load_read_only("async_set_get_load.db", SerializationMethod::Json).await.unwrap();
+    assert_eq!(read_db.get::<i32>("key1").await.unwrap(), 100);
+    assert!(!read_db.exists("key2").await);
+    assert!(read_db.exists("key3").await);
+
+    // load errors are returned as usual
+    assert!(matches!(AsyncPickleDb::load_read_only("no_such_file.db", SerializationMethod::Json).await, Err(PickleDbError::Io(_))));
+}
+
+#[tokio::test]
+async fn async_lists() {
+    set_test_rsc!("async_lists.db");
+
+    let db = AsyncPickleDb::new("async_lists.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.lcreate("list1").await.unwrap();
+    db.lextend("list1", &[1, 2, 3]).await.unwrap();
+    db.ladd("list1", &String::from("item")).await.unwrap();
+    assert!(db.lexists("list1").await);
+    assert_eq!(db.llen("list1").await, 4);
+    assert_eq!(db.lget::<String>("list1", 3).await.unwrap(), "item");
+    assert_eq!(db.lpop::<i32>("list1", 0).await.unwrap(), Some(1));
+    assert!(db.lrem_value("list1", &2).await.unwrap());
+    assert!(matches!(db.ladd("list2", &1).await, Err(PickleDbError::KeyNotFound(_))));
+
+    let sum: i32 = db.read(|db| db.liter("list1").unwrap().filter_map(|item| item.get_item::<i32>()).sum()).await;
+    assert_eq!(sum, 3);
+
+    db.dump().await.unwrap();
+    let read_db = PickleDb::load_read_only("async_lists.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.llen("list1"), 2);
+    assert_eq!(db.lrem_list("list1").await.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn async_concurrent_tasks() {
+    set_test_rsc!("async_concurrent_tasks.db");
+
+    let db = AsyncPickleDb::new("async_concurrent_tasks.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.lcreate("list1").await.unwrap();
+
+    let handles: Vec<_> = (0..8).map(|i| {
+        let db = db.clone();
+        tokio::spawn(async move {
+            for j in 0..10 {
+                db.set(&format!("key{}_{}", i, j), &j).await.unwrap();
+                db.ladd("list1", &i).await.unwrap();
+            }
+        })
+    }).collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(db.total_keys().await, 8 * 10 + 1);
+    let read_db = PickleDb::load_read_only("async_concurrent_tasks.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.llen("list1"), 8 * 10);
+}
+
+#[tokio::test]
+async fn async_transaction() {
+    set_test_rsc!("async_transaction.db");
+
+    let db = AsyncPickleDb::new("async_transaction.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).await.unwrap();
+
+    let res = db.transaction(|tx| {
+        tx.set("key1", &2)?;
+        tx.ladd("list1", &1)?;
+        Ok(())
+    }).await;
+    assert!(matches!(res, Err(PickleDbError::KeyNotFound(_))));
+    assert_eq!(db.get::<i32>("key1").await.unwrap(), 1);
+
+    db.transaction(|tx| -> Result<(), PickleDbError> {
+        tx.set("key1", &3)?;
+        tx.lcreate("list1")?.ladd(&1)?;
+        Ok(())
+    }).await.unwrap();
+    let read_db = PickleDb::load_read_only("async_transaction.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 3);
+    assert!(read_db.lexists("list1"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn async_counters() {
+    set_test_rsc!("async_counters.db");
+
+    let db = AsyncPickleDb::new("async_counters.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    let tasks: Vec<_> = (0..4).map(|_| {
+        let db = db.clone();
+        tokio::spawn(async move {
+            for _ in 0..50 {
+                db.incr("counter").await.unwrap();
+            }
+        })
+    }).collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(db.get::<i64>("counter").await.unwrap(), 200);
+    assert_eq!(db.decr("counter").await.unwrap(), 199);
+    assert_eq!(db.incr_by("counter", -99).await.unwrap(), 100);
+    assert_eq!(db.incr_by_float("counter", 0.5).await.unwrap(), 100.5);
+
+    db.set("str", &String::from("hello")).await.unwrap();
+    assert!(matches!(db.incr("str").await, Err(PickleDbError::WrongType(_))));
+}
+
+#[tokio::test]
+async fn async_list_operations() {
+    set_test_rsc!("async_list_operations.db");
+
+    let db = AsyncPickleDb::new("async_list_operations.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.lcreate("queue").await.unwrap();
+    db.lcreate("done").await.unwrap();
+    db.lextend("queue", &[2, 4]).await.unwrap();
+    assert_eq!(db.lpush("queue", &1).await.unwrap(), 3);
+    assert_eq!(db.linsert("queue", -1, &3).await.unwrap(), 4);
+    assert!(db.linsert_after("queue", &4, &5).await.unwrap());
+    assert!(db.linsert_before("queue", &1, &0).await.unwrap());
+    assert!(db.lset("queue", 0, &-1).await.unwrap());
+    assert_eq!(db.lrange::<i32>("queue", 0, -1).await.unwrap(), vec![-1, 1, 2, 3, 4, 5]);
+    assert_eq!(db.ltrim("queue", 1, -1).await.unwrap(), 1);
+    assert_eq!(db.lindex::<i32>("queue", -1).await.unwrap(), 5);
+    assert_eq!(db.lmove::<i32>("queue", "done", ListEnd::Front, ListEnd::Back).await.unwrap(), Some(1));
+    db.ladd("done", &1).await.unwrap();
+    assert_eq!(db.lrem_value_all("done", &1, 0).await.unwrap(), 2);
+    assert_eq!(db.llen("done").await, 0);
+}
+
+#[tokio::test]
+async fn async_sets() {
+    set_test_rsc!("async_sets.db");
+
+    let db = AsyncPickleDb::new("async_sets.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    assert!(db.sadd("set1", &1).await.unwrap());
+    assert!(db.sadd("set1", &2).await.unwrap());
+    assert!(!db.sadd("set1", &2).await.unwrap());
+    assert!(db.sadd("set2", &2).await.unwrap());
+    assert!(db.srem("set2", &3).await.is_ok_and(|removed| !removed));
+    assert!(db.sismember("set1", &1).await);
+    assert_eq!(db.scard("set1").await, 2);
+    assert_eq!(db.sinter::<i32>(&["set1", "set2"]).await.unwrap(), vec![2]);
+    assert_eq!(db.sdiff::<i32>(&["set1", "set2"]).await.unwrap(), vec![1]);
+    assert_eq!(db.sunion::<i32>(&["set1", "set2"]).await.unwrap().len(), 2);
+    assert!(db.srem("set1", &1).await.unwrap());
+    assert_eq!(db.smembers::<i32>("set1").await.unwrap(), vec![2]);
+
+    let read_db = PickleDb::load_read_only("async_sets.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.smembers::<i32>("set1").unwrap(), vec![2]);
+}
+
+#[tokio::test]
+async fn async_dicts() {
+    set_test_rsc!("async_dicts.db");
+
+    let db = AsyncPickleDb::new("async_dicts.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.dcreate("dict1").await.unwrap();
+    db.dadd("dict1", "num", &1).await.unwrap();
+    db.dadd("dict1", "str", &String::from("value")).await.unwrap();
+    db.dcreate("dict2").await.unwrap();
+    db.dadd("dict2", "num2", &2).await.unwrap();
+    assert_eq!(db.dget::<i32>("dict1", "num").await, Some(1));
+    assert!(matches!(db.try_dget::<i32>("dict1", "str").await, Err(PickleDbError::Deserialization { .. })));
+    assert!(db.dexists("dict1", "str").await);
+    assert_eq!(db.dlen("dict1").await, 2);
+    assert_eq!(db.dpop::<String>("dict1", "str").await.unwrap(), Some(String::from("value")));
+    db.dmerge("dict1", "dict2").await.unwrap();
+    let mut keys = db.dkeys("dict1").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["num", "num2"]);
+    assert_eq!(db.dgetall::<i32>("dict1").await.unwrap().get("num2"), Some(&2));
+    assert_eq!(db.dvals::<i32>("dict2").await.unwrap(), vec![2]);
+    assert_eq!(db.drem("dict2").await.unwrap(), 1);
+
+    let read_db = PickleDb::load_read_only("async_dicts.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.dlen("dict1"), 2);
+    assert!(!read_db.exists("dict2"));
+}
+
+#[tokio::test]
+async fn async_sorted_sets() {
+    set_test_rsc!("async_sorted_sets.db");
+
+    let db = AsyncPickleDb::new("async_sorted_sets.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    assert!(db.zadd("zset1", 2.0, &String::from("b")).await.unwrap());
+    assert!(db.zadd("zset1", 1.0, &String::from("a")).await.unwrap());
+    assert_eq!(db.zincrby("zset1", 2.0, &String::from("a")).await.unwrap(), 3.0);
+    assert_eq!(db.zscore("zset1", &String::from("a")).await, Some(3.0));
+    assert_eq!(db.zcard("zset1").await, 2);
+    assert_eq!(db.zrange_by_rank::<String>("zset1", 0, -1).await.unwrap(),
+               vec![(String::from("b"), 2.0), (String::from("a"), 3.0)]);
+    assert_eq!(db.zrange_by_score::<String>("zset1", 2.5, 4.0).await.unwrap(), vec![(String::from("a"), 3.0)]);
+    assert!(db.zrem("zset1", &String::from("b")).await.unwrap());
+
+    let read_db = PickleDb::load_read_only("async_sorted_sets.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.zrange_by_rank::<String>("zset1", 0, -1).unwrap(), vec![(String::from("a"), 3.0)]);
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn async_load_encrypted() {
+    set_test_rsc!("async_load_encrypted.db");
+
+    let key = pickledb::EncryptionKey::new([1; 32]);
+    let mut db = PickleDb::new("async_load_encrypted.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_encryption(&key);
+    db.set("key1", &1).unwrap();
+    drop(db);
+
+    let db = AsyncPickleDb::load_encrypted("async_load_encrypted.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json, &key).await.unwrap();
+    assert_eq!(db.get::<i32>("key1").await, Some(1));
+    assert!(matches!(AsyncPickleDb::load("async_load_encrypted.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).await,
+                     Err(PickleDbError::Decryption(_))));
+}
+
+#[tokio::test]
+async fn async_snapshot_and_save_as() {
+    set_test_rsc!("async_snapshot.db");
+    set_test_rsc!("async_snapshot_copy.db");
+
+    let db = AsyncPickleDb::new("async_snapshot.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).await.unwrap();
+
+    let snapshot = db.snapshot().await;
+    db.set("key1", &2).await.unwrap();
+    assert_eq!(snapshot.get::<i32>("key1"), Some(1));
+
+    db.save_as("async_snapshot_copy.db").await.unwrap();
+    let copy = AsyncPickleDb::load_read_only("async_snapshot_copy.db", SerializationMethod::Json).await.unwrap();
+    assert_eq!(copy.get::<i32>("key1").await, Some(2));
+}
+
+#[tokio::test]
+async fn async_subscribe_channel() {
+    set_test_rsc!("async_subscribe_channel.db");
+
+    let db = AsyncPickleDb::new("async_subscribe_channel.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    let events = db.subscribe_channel("key").await;
+    db.set("key1", &1).await.unwrap();
+    db.set("other1", &1).await.unwrap();
+    db.rem("key1").await.unwrap();
+
+    let received: Vec<PickleDbEvent> = events.try_iter().collect();
+    assert_eq!(received, vec![PickleDbEvent::Set(String::from("key1")), PickleDbEvent::Removed(String::from("key1"))]);
+}
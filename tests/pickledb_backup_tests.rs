@@ -0,0 +1,157 @@
+use pickledb::{PickleDb, PickleDbBackupPolicy, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+fn remove_backups(db: &PickleDb) {
+    for backup in db.backups().unwrap() {
+        fs::remove_file(backup.path()).unwrap();
+    }
+}
+
+#[test]
+fn backup_every_dump() {
+    set_test_rsc!("backup_every_dump.db");
+
+    let mut db = PickleDb::new("backup_every_dump.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_backups(PickleDbBackupPolicy::EveryDump(3));
+    // the first dump has no previous version to back up
+    db.set("key1", &1).unwrap();
+    assert!(db.backups().unwrap().is_empty());
+
+    for i in 2..=6 {
+        db.set("key1", &i).unwrap();
+    }
+
+    // only the last 3 versions are kept, the most recent one first
+    let backups = db.backups().unwrap();
+    assert_eq!(backups.iter().map(|backup| backup.number()).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(backups[0].path(), "backup_every_dump.db.bak.1");
+    assert!(backups[0].modified().is_some());
+    assert!(!Path::new("backup_every_dump.db.bak.4").exists());
+
+    for (backup, value) in backups.iter().zip([5, 4, 3]) {
+        let backup_db = PickleDb::load_read_only(backup.path(), SerializationMethod::Json).unwrap();
+        assert_eq!(backup_db.get::<i32>("key1"), Some(value));
+    }
+
+    remove_backups(&db);
+}
+
+#[test]
+fn backup_periodic() {
+    set_test_rsc!("backup_periodic.db");
+
+    let mut db = PickleDb::new("backup_periodic.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin)
+        .with_backups(PickleDbBackupPolicy::Periodic(Duration::from_secs(60 * 60), 5));
+    for i in 1..=5 {
+        db.set("key1", &i).unwrap();
+    }
+
+    // a single backup is made within the interval
+    let backups = db.backups().unwrap();
+    assert_eq!(backups.len(), 1);
+    let backup_db = PickleDb::load_read_only(backups[0].path(), SerializationMethod::Bin).unwrap();
+    assert_eq!(backup_db.get::<i32>("key1"), Some(1));
+
+    remove_backups(&db);
+}
+
+#[test]
+fn backup_periodic_from_backup_time() {
+    set_test_rsc!("backup_periodic_from_backup_time.db");
+
+    let mut db = PickleDb::new("backup_periodic_from_backup_time.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_backups(PickleDbBackupPolicy::Periodic(Duration::from_millis(500), 5));
+    db.set("key1", &1).unwrap();
+    thread::sleep(Duration::from_millis(600));
+    db.set("key1", &2).unwrap();
+
+    // the backup holds a version that was dumped more than the interval ago, but the interval is measured from
+    // the time the backup was made, so the next dump isn't backed up
+    db.set("key1", &3).unwrap();
+    let backups = db.backups().unwrap();
+    assert_eq!(backups.len(), 1);
+    let backup_db = PickleDb::load_read_only(backups[0].path(), SerializationMethod::Json).unwrap();
+    assert_eq!(backup_db.get::<i32>("key1"), Some(1));
+
+    // until the interval passes
+    thread::sleep(Duration::from_millis(600));
+    db.set("key1", &4).unwrap();
+    assert_eq!(db.backups().unwrap().len(), 2);
+
+    remove_backups(&db);
+}
+
+#[test]
+fn backup_append_only() {
+    set_test_rsc!("backup_append_only.db");
+
+    let mut db = PickleDb::new("backup_append_only.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json)
+        .with_backups(PickleDbBackupPolicy::EveryDump(2));
+    db.set("key1", &1).unwrap();
+    db.set("key1", &2).unwrap();
+    assert!(db.backups().unwrap().is_empty());
+
+    // the DB file is only replaced when the log is compacted, and the backup doesn't hold the changes in the log
+    db.compact().unwrap();
+    assert_eq!(db.backups().unwrap().len(), 1);
+    let backup_db = PickleDb::load_read_only("backup_append_only.db.bak.1", SerializationMethod::Json).unwrap();
+    assert_eq!(backup_db.get::<i32>("key1"), Some(1));
+
+    remove_backups(&db);
+}
+
+#[test]
+fn restore_backup() {
+    set_test_rsc!("restore_backup.db");
+
+    let mut db = PickleDb::new("restore_backup.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Yaml)
+        .with_backups(PickleDbBackupPolicy::EveryDump(5));
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().ladd(&1).unwrap();
+    db.set("key1", &2).unwrap();
+    db.rem("list1").unwrap();
+
+    // backup 1 is the version before list1 was removed
+    db.restore_backup(1).unwrap();
+    assert_eq!(db.get::<i32>("key1"), Some(2));
+    assert_eq!(db.lget::<i32>("list1", 0), Some(1));
+    assert!(!db.is_dirty());
+
+    let read_db = PickleDb::load_read_only("restore_backup.db", SerializationMethod::Yaml).unwrap();
+    assert_eq!(read_db.lget::<i32>("list1", 0), Some(1));
+
+    // the replaced version is backed up as well, so the restore can be undone
+    db.restore_backup(1).unwrap();
+    assert!(!db.lexists("list1"));
+
+    remove_backups(&db);
+}
+
+#[test]
+fn restore_backup_errors() {
+    set_test_rsc!("restore_backup_errors.db");
+
+    let mut db = PickleDb::new("restore_backup_errors.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_backups(PickleDbBackupPolicy::EveryDump(1));
+    db.set("key1", &1).unwrap();
+    db.set("key1", &2).unwrap();
+
+    assert!(matches!(db.restore_backup(2), Err(PickleDbError::Io(_))));
+    assert_eq!(db.get::<i32>("key1"), Some(2));
+
+    // a corrupted backup doesn't change the data
+    fs::write("restore_backup_errors.db.bak.1", "not a DB").unwrap();
+    assert!(matches!(db.restore_backup(1), Err(PickleDbError::Corrupted(_))));
+    assert_eq!(db.get::<i32>("key1"), Some(2));
+
+    // a read-only DB can't restore a backup
+    let mut read_db = PickleDb::load_read_only("restore_backup_errors.db", SerializationMethod::Json).unwrap();
+    assert!(matches!(read_db.restore_backup(1), Err(PickleDbError::ReadOnly)));
+
+    remove_backups(&db);
+}
@@ -0,0 +1,120 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod, SharedPickleDb};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+#[test]
+fn incr_and_decr() {
+    set_test_rsc!("incr_and_decr.db");
+
+    let ser_methods = [SerializationMethod::Json, SerializationMethod::Bin, SerializationMethod::Yaml,
+                       SerializationMethod::Cbor, SerializationMethod::MsgPack];
+    for ser_method in ser_methods.iter() {
+        {
+            let mut db = PickleDb::new("incr_and_decr.db", PickleDbDumpPolicy::AutoDump, *ser_method);
+
+            // a missing key starts at 0
+            assert_eq!(db.incr("counter").unwrap(), 1);
+            assert_eq!(db.incr("counter").unwrap(), 2);
+            assert_eq!(db.incr_by("counter", 10).unwrap(), 12);
+            assert_eq!(db.incr_by("counter", -20).unwrap(), -8);
+            assert_eq!(db.decr("counter").unwrap(), -9);
+            assert_eq!(db.decr("other").unwrap(), -1);
+
+            // a value that was set can be incremented
+            db.set("key1", &100i64).unwrap();
+            assert_eq!(db.incr("key1").unwrap(), 101);
+        }
+
+        // the counters are dumped
+        let db = PickleDb::load_read_only("incr_and_decr.db", *ser_method).unwrap();
+        assert_eq!(db.get::<i64>("counter").unwrap(), -9);
+        assert_eq!(db.get::<i64>("other").unwrap(), -1);
+        assert_eq!(db.get::<i64>("key1").unwrap(), 101);
+    }
+}
+
+#[test]
+fn incr_by_float() {
+    set_test_rsc!("incr_by_float.db");
+
+    let mut db = PickleDb::new("incr_by_float.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+
+    assert_eq!(db.incr_by_float("float", 1.5).unwrap(), 1.5);
+    assert_eq!(db.incr_by_float("float", -0.25).unwrap(), 1.25);
+    assert_eq!(db.get::<f64>("float").unwrap(), 1.25);
+
+    // an integer can be incremented by a float, but then it's no longer an integer
+    db.incr_by("int", 10).unwrap();
+    assert_eq!(db.incr_by_float("int", 0.5).unwrap(), 10.5);
+    assert!(matches!(db.incr("int"), Err(PickleDbError::WrongType(_))));
+
+    // the result must be a finite number
+    assert!(matches!(db.incr_by_float("float", f64::INFINITY), Err(PickleDbError::Overflow(_))));
+    assert!(matches!(db.incr_by_float("float", f64::NAN), Err(PickleDbError::Overflow(_))));
+    assert_eq!(db.get::<f64>("float").unwrap(), 1.25);
+}
+
+#[test]
+fn incr_errors() {
+    set_test_rsc!("incr_errors.db");
+
+    let mut db = PickleDb::new("incr_errors.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("str", &String::from("hello")).unwrap();
+    db.lcreate("list1").unwrap();
+    db.dcreate("dict1").unwrap();
+
+    // values that aren't numbers
+    assert!(matches!(db.incr("str"), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.incr_by_float("str", 1.0), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.incr("list1"), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.decr("dict1"), Err(PickleDbError::WrongType(_))));
+    assert_eq!(db.get::<String>("str").unwrap(), "hello");
+    assert!(db.lexists("list1"));
+
+    // overflow leaves the value as is
+    db.set("max", &i64::MAX).unwrap();
+    assert!(matches!(db.incr("max"), Err(PickleDbError::Overflow(_))));
+    assert_eq!(db.get::<i64>("max").unwrap(), i64::MAX);
+    db.set("min", &i64::MIN).unwrap();
+    assert!(matches!(db.decr("min"), Err(PickleDbError::Overflow(_))));
+}
+
+#[test]
+fn incr_keeps_ttl() {
+    set_test_rsc!("incr_keeps_ttl.db");
+
+    let mut db = PickleDb::new("incr_keeps_ttl.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.set_with_ttl("counter", &5, Duration::from_millis(200)).unwrap();
+
+    assert_eq!(db.incr("counter").unwrap(), 6);
+    assert!(db.ttl("counter").unwrap().is_some());
+
+    // an expired counter starts over without a TTL
+    thread::sleep(Duration::from_millis(250));
+    assert_eq!(db.incr("counter").unwrap(), 1);
+    assert_eq!(db.ttl("counter").unwrap(), None);
+}
+
+#[test]
+fn shared_incr_from_threads() {
+    set_test_rsc!("shared_incr_from_threads.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("shared_incr_from_threads.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin));
+
+    // no increment is lost when several threads increment the same counter
+    let handles: Vec<_> = (0..4).map(|_| {
+        let db = db.clone();
+        thread::spawn(move || {
+            for _ in 0..100 {
+                db.incr("counter").unwrap();
+            }
+        })
+    }).collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(db.get::<i64>("counter").unwrap(), 400);
+    assert_eq!(db.incr_by_float("float", 0.5).unwrap(), 0.5);
+}
@@ -0,0 +1,206 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::collections::HashMap;
+
+mod common;
+
+#[test]
+fn basic_dict() {
+    set_test_rsc!("basic_dict.db");
+
+    let mut db = PickleDb::new("basic_dict.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+
+    // create a dict with values of different types
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "num", &100).unwrap();
+    db.dadd("dict1", "str", &String::from("hello")).unwrap();
+    db.dadd("dict1", "vec", &vec![1, 2, 3]).unwrap();
+
+    assert!(db.exists("dict1"));
+    assert!(!db.lexists("dict1"));
+    assert_eq!(db.dlen("dict1"), 3);
+    assert!(db.dexists("dict1", "num"));
+    assert!(!db.dexists("dict1", "other"));
+    assert!(!db.dexists("dict2", "num"));
+    assert_eq!(db.dget::<i32>("dict1", "num").unwrap(), 100);
+    assert_eq!(db.dget::<String>("dict1", "str").unwrap(), "hello");
+    assert_eq!(db.dget::<Vec<i32>>("dict1", "vec").unwrap(), vec![1, 2, 3]);
+    assert!(db.dget::<i32>("dict1", "other").is_none());
+
+    let mut keys = db.dkeys("dict1").unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["num", "str", "vec"]);
+    assert!(db.dkeys("dict2").is_none());
+
+    // override a value
+    db.dadd("dict1", "num", &200).unwrap();
+    assert_eq!(db.dget::<i32>("dict1", "num").unwrap(), 200);
+
+    // the dict is dumped to the file
+    let read_db = PickleDb::load_read_only("basic_dict.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.dlen("dict1"), 3);
+    assert_eq!(read_db.dget::<i32>("dict1", "num").unwrap(), 200);
+
+    // pop a value
+    assert_eq!(db.dpop::<String>("dict1", "str").unwrap(), Some(String::from("hello")));
+    assert_eq!(db.dpop::<String>("dict1", "str").unwrap(), None);
+    assert!(!db.dexists("dict1", "str"));
+
+    // a value of the wrong type isn't popped
+    assert!(matches!(db.dpop::<String>("dict1", "vec"), Err(PickleDbError::Deserialization { .. })));
+    assert!(db.dexists("dict1", "vec"));
+
+    // remove the dict
+    assert_eq!(db.drem("dict1").unwrap(), 2);
+    assert!(!db.exists("dict1"));
+    assert_eq!(db.drem("dict1").unwrap(), 0);
+    let read_db = PickleDb::load_read_only("basic_dict.db", SerializationMethod::Json).unwrap();
+    assert!(!read_db.exists("dict1"));
+}
+
+#[test]
+fn dict_getall_and_vals() {
+    set_test_rsc!("dict_getall_and_vals.db");
+
+    let mut db = PickleDb::new("dict_getall_and_vals.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "a", &1).unwrap();
+    db.dadd("dict1", "b", &2).unwrap();
+
+    let mut expected = HashMap::new();
+    expected.insert(String::from("a"), 1);
+    expected.insert(String::from("b"), 2);
+    assert_eq!(db.dgetall::<i32>("dict1").unwrap(), expected);
+
+    let mut vals = db.dvals::<i32>("dict1").unwrap();
+    vals.sort();
+    assert_eq!(vals, vec![1, 2]);
+
+    // values of different types can't be read as a single type
+    db.dadd("dict1", "c", &String::from("str")).unwrap();
+    assert!(db.dgetall::<i32>("dict1").is_none());
+    assert!(db.dvals::<i32>("dict1").is_none());
+    assert!(db.dgetall::<i32>("dict2").is_none());
+    assert!(db.dvals::<i32>("dict2").is_none());
+}
+
+#[test]
+fn dict_merge() {
+    set_test_rsc!("dict_merge.db");
+
+    let mut db = PickleDb::new("dict_merge.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin);
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "a", &1).unwrap();
+    db.dadd("dict1", "b", &2).unwrap();
+    db.dcreate("dict2").unwrap();
+    db.dadd("dict2", "b", &20).unwrap();
+    db.dadd("dict2", "c", &30).unwrap();
+
+    db.dmerge("dict1", "dict2").unwrap();
+    assert_eq!(db.dlen("dict1"), 3);
+    assert_eq!(db.dget::<i32>("dict1", "a").unwrap(), 1);
+    assert_eq!(db.dget::<i32>("dict1", "b").unwrap(), 20);
+    assert_eq!(db.dget::<i32>("dict1", "c").unwrap(), 30);
+
+    // the other dict isn't changed
+    assert_eq!(db.dlen("dict2"), 2);
+
+    // merging with a dict that doesn't exist or with a value
+    db.set("key1", &1).unwrap();
+    assert!(matches!(db.dmerge("dict1", "dict3"), Err(PickleDbError::KeyNotFound(_))));
+    assert!(matches!(db.dmerge("dict3", "dict1"), Err(PickleDbError::KeyNotFound(_))));
+    assert!(matches!(db.dmerge("dict1", "key1"), Err(PickleDbError::WrongType(_))));
+    assert_eq!(db.dlen("dict1"), 3);
+
+    let read_db = PickleDb::load_read_only("dict_merge.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.dget::<i32>("dict1", "b").unwrap(), 20);
+}
+
+#[test]
+fn dict_override_rules() {
+    set_test_rsc!("dict_override_rules.db");
+
+    let mut db = PickleDb::new("dict_override_rules.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+
+    // a dict overrides a value
+    db.set("key1", &1).unwrap();
+    db.dcreate("key1").unwrap();
+    assert!(db.get::<i32>("key1").is_none());
+    assert_eq!(db.dlen("key1"), 0);
+
+    // a list overrides a dict
+    db.dadd("key1", "a", &1).unwrap();
+    db.lcreate("key1").unwrap();
+    assert!(!db.dexists("key1", "a"));
+    assert!(db.lexists("key1"));
+
+    // a dict overrides a list
+    db.dcreate("key1").unwrap();
+    assert!(!db.lexists("key1"));
+
+    // a value overrides a dict
+    db.dadd("key1", "a", &1).unwrap();
+    db.set("key1", &2).unwrap();
+    assert!(!db.dexists("key1", "a"));
+    assert_eq!(db.get::<i32>("key1").unwrap(), 2);
+    assert_eq!(db.total_keys(), 1);
+
+    // rem removes dicts
+    db.dcreate("dict1").unwrap();
+    assert_eq!(db.get_all().len(), 2);
+    assert!(db.rem("dict1").unwrap());
+    assert!(!db.exists("dict1"));
+}
+
+#[test]
+fn dict_errors() {
+    set_test_rsc!("dict_errors.db");
+
+    let mut db = PickleDb::new("dict_errors.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "a", &1).unwrap();
+
+    // add to a dict that doesn't exist or isn't a dict
+    assert!(matches!(db.dadd("dict2", "a", &1), Err(PickleDbError::KeyNotFound(_))));
+    assert!(matches!(db.dadd("key1", "a", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.dadd("list1", "a", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.dpop::<i32>("dict2", "a"), Err(PickleDbError::KeyNotFound(_))));
+
+    // tell a missing key apart from a wrong type
+    assert_eq!(db.try_dget::<i32>("dict1", "a").unwrap(), Some(1));
+    assert_eq!(db.try_dget::<i32>("dict1", "b").unwrap(), None);
+    assert_eq!(db.try_dget::<i32>("dict2", "a").unwrap(), None);
+    assert!(matches!(db.try_dget::<i32>("key1", "a"), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.try_dget::<String>("dict1", "a"), Err(PickleDbError::Deserialization { .. })));
+
+    // other APIs report a dict as the wrong type
+    assert!(matches!(db.try_get::<i32>("dict1"), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.ladd("dict1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.diter("key1"), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.diter("dict2"), Err(PickleDbError::KeyNotFound(_))));
+}
+
+#[test]
+fn iter_dict() {
+    set_test_rsc!("iter_dict.db");
+
+    let mut db = PickleDb::new("iter_dict.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "num", &1).unwrap();
+    db.dadd("dict1", "str", &String::from("hello")).unwrap();
+
+    let mut count = 0;
+    for kv in db.diter("dict1").unwrap() {
+        match kv.get_key() {
+            "num" => {
+                assert_eq!(kv.get_value::<i32>().unwrap(), 1);
+                assert!(matches!(kv.try_get_value::<String>(), Err(PickleDbError::Deserialization { .. })));
+            },
+            "str" => assert_eq!(kv.get_value::<String>().unwrap(), "hello"),
+            _ => panic!("unexpected key {}", kv.get_key()),
+        }
+        count += 1;
+    }
+    assert_eq!(count, 2);
+}
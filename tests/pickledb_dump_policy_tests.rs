@@ -1,4 +1,4 @@
-use pickledb::{PickleDb, PickleDbDumpPolicy};
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
 use std::time::Duration;
 use std::{thread, time};
 
@@ -9,69 +9,69 @@ fn auto_dump_poilcy_test() {
     set_test_rsc!("auto_dump_poilcy_test.db");
 
     // create a DB with AutoDump policy
-    let mut db = PickleDb::new("auto_dump_poilcy_test.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("auto_dump_poilcy_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // set a key-value pair
-    db.set("key1", &1);
+    db.set("key1", &1).unwrap();
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
     }
 
     // remove a key
-    assert!(db.rem("key1"));
+    assert!(db.rem("key1").unwrap());
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.get::<i32>("key1").is_none());
     }
 
     // create a list
-    db.lcreate("list1");
+    db.lcreate("list1").unwrap();
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("list1"));
         assert_eq!(read_db.llen("list1"), 0);
     }
 
     // add values to list
-    db.lextend("list1", &vec![1,2,3]);
+    db.lextend("list1", &[1,2,3]).unwrap();
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.llen("list1"), 3);
     }
 
     // pop an item from a list
-    db.lpop::<i32>("list1", 0);
+    db.lpop::<i32>("list1", 0).unwrap().unwrap();
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.llen("list1"), 2);
     }
 
     // remove an item from a list
-    db.lrem_value("list1", &2);
+    db.lrem_value("list1", &2).unwrap();
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.llen("list1"), 1);
     }
 
     // remove a list
-    db.lrem_list("list1");
+    db.lrem_list("list1").unwrap();
 
     // verify the change in the DB
     {
-        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("auto_dump_poilcy_test.db", SerializationMethod::Json).unwrap();
         assert!(!read_db.exists("list1"));
     }
 }
@@ -81,29 +81,29 @@ fn read_only_policy_test() {
     set_test_rsc!("read_only_policy_test.db");
 
     // create a DB and set a value
-    let mut db = PickleDb::new("read_only_policy_test.db", PickleDbDumpPolicy::AutoDump);
-    db.set("key1", &String::from("value1"));
+    let mut db = PickleDb::new("read_only_policy_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &String::from("value1")).unwrap();
 
     // create a read only instance of the same DB
-    let mut read_db1 = PickleDb::load_read_only("read_only_policy_test.db").unwrap();
+    let mut read_db1 = PickleDb::load_read_only("read_only_policy_test.db", SerializationMethod::Json).unwrap();
 
     // set a key-value pair in the read-only DB
-    read_db1.set("key2", &String::from("value2"));
+    read_db1.set("key2", &String::from("value2")).unwrap();
     assert!(read_db1.exists("key2"));
 
     // verify the change isn't dumped to the file
     {
-        let read_db2 = PickleDb::load_read_only("read_only_policy_test.db").unwrap();
+        let read_db2 = PickleDb::load_read_only("read_only_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db2.exists("key1"));
         assert!(!read_db2.exists("key2"));
     }
 
     // try to dump data to the file
-    read_db1.dump();
+    assert!(matches!(read_db1.dump(), Err(PickleDbError::ReadOnly)));
 
     // verify the change isn't dumped to the file
     {
-        let read_db2 = PickleDb::load_read_only("read_only_policy_test.db").unwrap();
+        let read_db2 = PickleDb::load_read_only("read_only_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db2.exists("key1"));
         assert!(!read_db2.exists("key2"));
     }
@@ -113,7 +113,7 @@ fn read_only_policy_test() {
 
     // verify the change isn't dumped to the file
     {
-        let read_db2 = PickleDb::load_read_only("read_only_policy_test.db").unwrap();
+        let read_db2 = PickleDb::load_read_only("read_only_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db2.exists("key1"));
         assert!(!read_db2.exists("key2"));
     }
@@ -124,30 +124,30 @@ fn dump_upon_request_policy_test() {
     set_test_rsc!("dump_upon_request_policy_test.db");
 
     // create a DB and set a value
-    let mut db = PickleDb::new("dump_upon_request_policy_test.db", PickleDbDumpPolicy::DumpUponRequest);
-    db.set("key1", &String::from("value1"));
+    let mut db = PickleDb::new("dump_upon_request_policy_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.set("key1", &String::from("value1")).unwrap();
 
     // verify file is not yet created
-    assert!(PickleDb::load_read_only("dump_upon_request_policy_test.db").is_err());
+    assert!(PickleDb::load_read_only("dump_upon_request_policy_test.db", SerializationMethod::Json).is_err());
 
     // dump to file
-    db.dump();
+    db.dump().unwrap();
 
     // verify the change is dumped to the file
     {
-        let read_db = PickleDb::load_read_only("dump_upon_request_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("dump_upon_request_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("key1"));
     }
 
     // set another key
-    db.set("key2", &String::from("value2"));
+    db.set("key2", &String::from("value2")).unwrap();
 
     // drop DB object
     drop(db);
 
     // verify the change is dumped to the file
     {
-        let read_db = PickleDb::load_read_only("dump_upon_request_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("dump_upon_request_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("key1"));
         assert!(read_db.exists("key2"));
     }
@@ -158,46 +158,46 @@ fn periodic_dump_policy_test() {
     set_test_rsc!("periodic_dump_policy_test.db");
 
     // create a DB and set a value
-    let mut db = PickleDb::new("periodic_dump_policy_test.db", PickleDbDumpPolicy::PeriodicDump(Duration::new(1, 0)));
-    db.set("key1", &String::from("value1"));
+    let mut db = PickleDb::new("periodic_dump_policy_test.db", PickleDbDumpPolicy::PeriodicDump(Duration::new(1, 0)), SerializationMethod::Json);
+    db.set("key1", &String::from("value1")).unwrap();
 
     // verify file is not yet created
-    assert!(PickleDb::load_read_only("periodic_dump_policy_test.db").is_err());
+    assert!(PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).is_err());
 
     // sleep for 0.5 sec
     thread::sleep(time::Duration::from_millis(500));
 
     // verify file is not yet created
-    assert!(PickleDb::load_read_only("periodic_dump_policy_test.db").is_err());
+    assert!(PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).is_err());
 
     // sleep for 0.55 sec
     thread::sleep(time::Duration::from_millis(550));
 
     // make another change in the DB
-    db.set("key2", &String::from("value2"));
+    db.set("key2", &String::from("value2")).unwrap();
 
     // verify the change is dumped to the file
     {
-        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("key1"));
         assert!(read_db.exists("key2"));
     }
 
     // make another change in the DB
-    db.set("key3", &String::from("value3"));
+    db.set("key3", &String::from("value3")).unwrap();
 
     // verify the change is not yet dumped to the file
     {
-        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(!read_db.exists("key3"));
     }
 
     // dumb DB to file
-    db.dump();
+    db.dump().unwrap();
 
     // verify the change is now dumped to the file
     {
-        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("key3"));
     }
 
@@ -205,23 +205,108 @@ fn periodic_dump_policy_test() {
     thread::sleep(time::Duration::from_secs(1));
 
     // make another change in the DB
-    db.set("key4", &String::from("value4"));
+    db.set("key4", &String::from("value4")).unwrap();
 
     // verify the change is dumped to the file
     {
-        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("key4"));
     }
 
     // make another change in the DB
-    db.set("key5", &String::from("value5"));
+    db.set("key5", &String::from("value5")).unwrap();
 
     // drop DB and verify change is written to DB
     drop(db);
 
     // verify the change is dumped to the file
     {
-        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db").unwrap();
+        let read_db = PickleDb::load_read_only("periodic_dump_policy_test.db", SerializationMethod::Json).unwrap();
         assert!(read_db.exists("key5"));
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn dirty_tracking_test() {
+    set_test_rsc!("dirty_tracking_test.db");
+
+    // a new DB is dirty until it's dumped
+    let mut db = PickleDb::new("dirty_tracking_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    assert!(db.is_dirty());
+    db.dump().unwrap();
+    assert!(!db.is_dirty());
+
+    // a change makes the DB dirty
+    db.set("key1", &1).unwrap();
+    assert!(db.is_dirty());
+    db.dump().unwrap();
+    assert!(!db.is_dirty());
+
+    // a DB that was just loaded is clean
+    let db = PickleDb::load("dirty_tracking_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).unwrap();
+    assert!(!db.is_dirty());
+    drop(db);
+
+    // with AutoDump every change is dumped so the DB remains clean
+    let mut db = PickleDb::load("dirty_tracking_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).unwrap();
+    db.set("key2", &2).unwrap();
+    assert!(!db.is_dirty());
+
+    // a rolled back transaction leaves the DB as clean as it was
+    db.begin().unwrap();
+    db.set("key3", &3).unwrap();
+    assert!(db.is_dirty());
+    db.rollback().unwrap();
+    assert!(!db.is_dirty());
+}
+
+#[test]
+fn clean_db_is_not_dumped_test() {
+    set_test_rsc!("clean_db_is_not_dumped_test.db");
+
+    // a DB file that PickleDB would write differently, so any rewrite is noticed
+    let contents = "[ {\"key1\": \"1\"}, {} ]";
+    std::fs::write("clean_db_is_not_dumped_test.db", contents).unwrap();
+
+    // neither dump() nor drop rewrite the file of a clean DB
+    {
+        let mut db = PickleDb::load("clean_db_is_not_dumped_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json).unwrap();
+        assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+        db.dump().unwrap();
+        assert_eq!(std::fs::read_to_string("clean_db_is_not_dumped_test.db").unwrap(), contents);
+    }
+    assert_eq!(std::fs::read_to_string("clean_db_is_not_dumped_test.db").unwrap(), contents);
+
+    // compact() always rewrites the file
+    {
+        let mut db = PickleDb::load("clean_db_is_not_dumped_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json).unwrap();
+        db.compact().unwrap();
+        assert_ne!(std::fs::read_to_string("clean_db_is_not_dumped_test.db").unwrap(), contents);
+    }
+
+    // removing keys that don't exist doesn't change the DB, so it remains clean
+    {
+        let mut db = PickleDb::load("clean_db_is_not_dumped_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json).unwrap();
+        let contents = std::fs::read_to_string("clean_db_is_not_dumped_test.db").unwrap();
+        assert!(!db.rem("no_such_key").unwrap());
+        assert_eq!(db.lrem_list("no_such_list").unwrap(), 0);
+        assert_eq!(db.drem("no_such_dict").unwrap(), 0);
+        // a value isn't removed by the APIs that remove lists and dicts
+        assert_eq!(db.lrem_list("key1").unwrap(), 0);
+        assert_eq!(db.drem("key1").unwrap(), 0);
+        assert!(!db.is_dirty());
+        db.begin().unwrap();
+        assert!(!db.rem("no_such_key").unwrap());
+        assert!(!db.is_dirty());
+        db.rollback().unwrap();
+        assert_eq!(std::fs::read_to_string("clean_db_is_not_dumped_test.db").unwrap(), contents);
+    }
+
+    // a changed DB is dumped on drop
+    {
+        let mut db = PickleDb::load("clean_db_is_not_dumped_test.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json).unwrap();
+        db.set("key2", &2).unwrap();
+    }
+    let read_db = PickleDb::load_read_only("clean_db_is_not_dumped_test.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key2").unwrap(), 2);
+}
@@ -0,0 +1,89 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
+use std::time::{Duration, SystemTime};
+
+mod common;
+
+fn temp_files(db_file_name: &str) -> Vec<String> {
+    let prefix = format!("{}.temp.", db_file_name);
+    let mut files: Vec<String> = fs::read_dir(".").unwrap()
+        .filter_map(|entry| entry.unwrap().file_name().into_string().ok())
+        .filter(|name| name.starts_with(&prefix))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn durable_dumps() {
+    set_test_rsc!("durable_dumps.db");
+
+    let mut db = PickleDb::new("durable_dumps.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_durable_dumps();
+
+    // several dumps in the same second don't leave temp files behind
+    for i in 0..10 {
+        db.set("key1", &i).unwrap();
+    }
+    assert!(temp_files("durable_dumps.db").is_empty());
+
+    let read_db = PickleDb::load_read_only("durable_dumps.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(9));
+}
+
+#[test]
+fn durable_append_only() {
+    set_test_rsc!("durable_append_only.db");
+
+    let mut db = PickleDb::new("durable_append_only.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Bin).with_durable_dumps();
+    db.set("key1", &1).unwrap();
+    db.set("key2", &2).unwrap();
+    assert!(Path::new("durable_append_only.db.log").exists());
+
+    let read_db = PickleDb::load_read_only("durable_append_only.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.get::<i32>("key2"), Some(2));
+}
+
+#[test]
+fn stale_temp_files_removed_on_load() {
+    set_test_rsc!("stale_temp_files.db");
+
+    let mut db = PickleDb::new("stale_temp_files.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    drop(db);
+
+    // temp files of dumps that didn't complete, in the old and in the new naming, that are an hour old
+    let hour_ago = SystemTime::now() - Duration::from_secs(60 * 60);
+    let old_temp_file = format!("stale_temp_files.db.temp.{}.5", process::id());
+    for path in ["stale_temp_files.db.temp.1546300800", &old_temp_file] {
+        fs::write(path, "partial").unwrap();
+        File::options().write(true).open(path).unwrap().set_modified(hour_ago).unwrap();
+    }
+    // a temp file of a process that exited, as no process ID can be that high
+    fs::write("stale_temp_files.db.temp.4294967295.5", "partial").unwrap();
+    // a temp file of a dump that may still be running in this process
+    let running_temp_file = format!("stale_temp_files.db.temp.{}.6", process::id());
+    fs::write(&running_temp_file, "partial").unwrap();
+    fs::write("stale_temp_files.db.temperature", "not a temp file").unwrap();
+
+    // a read-only DB leaves them alone
+    PickleDb::load_read_only("stale_temp_files.db", SerializationMethod::Json).unwrap();
+    assert_eq!(temp_files("stale_temp_files.db").len(), 4);
+
+    let db = PickleDb::load("stale_temp_files.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).unwrap();
+    // whether a process exited can only be told where /proc is available
+    let mut expected = vec![running_temp_file.clone()];
+    if !Path::new("/proc/self").exists() {
+        expected.push(String::from("stale_temp_files.db.temp.4294967295.5"));
+        expected.sort();
+    }
+    assert_eq!(temp_files("stale_temp_files.db"), expected);
+    assert!(Path::new("stale_temp_files.db.temperature").exists());
+    assert_eq!(db.get::<i32>("key1"), Some(1));
+
+    for path in expected {
+        fs::remove_file(path).unwrap();
+    }
+    fs::remove_file("stale_temp_files.db.temperature").unwrap();
+}
@@ -0,0 +1,339 @@
+#![cfg(feature = "encryption")]
+
+use pickledb::{EncryptionKey, PickleDb, PickleDbBackupPolicy, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::fs;
+
+mod common;
+
+fn secret() -> String {
+    String::from("top-secret-token")
+}
+
+fn contains(contents: &[u8], text: &str) -> bool {
+    contents.windows(text.len()).any(|window| window == text.as_bytes())
+}
+
+#[test]
+fn encrypted_dump_and_load() {
+    let ser_methods = vec![
+        SerializationMethod::Json,
+        SerializationMethod::Bin,
+        SerializationMethod::Yaml,
+        SerializationMethod::Cbor,
+        SerializationMethod::MsgPack,
+    ];
+    let key = EncryptionKey::new([7; 32]);
+
+    for ser_method in ser_methods {
+        set_test_rsc!("encrypted_dump_and_load.db");
+
+        let mut db = PickleDb::new("encrypted_dump_and_load.db", PickleDbDumpPolicy::AutoDump, ser_method).with_encryption(&key);
+        db.set("token", &secret()).unwrap();
+        db.lcreate("list1").unwrap().ladd(&secret()).unwrap();
+        db.sadd("set1", &1).unwrap();
+
+        // the data isn't written to the file as is
+        let contents = fs::read("encrypted_dump_and_load.db").unwrap();
+        assert!(!contains(&contents, &secret()));
+
+        let read_db = PickleDb::load_encrypted("encrypted_dump_and_load.db", PickleDbDumpPolicy::NeverDump, ser_method, &key).unwrap();
+        assert_eq!(read_db.get::<String>("token").unwrap(), secret());
+        assert_eq!(read_db.lget::<String>("list1", 0).unwrap(), secret());
+        assert!(read_db.sismember("set1", &1));
+
+        // an encrypted file can't be loaded without the key
+        assert!(matches!(PickleDb::load("encrypted_dump_and_load.db", PickleDbDumpPolicy::NeverDump, ser_method),
+                         Err(PickleDbError::Decryption(_))));
+    }
+}
+
+#[test]
+fn encrypted_wrong_key() {
+    set_test_rsc!("encrypted_wrong_key.db");
+
+    let mut db = PickleDb::new("encrypted_wrong_key.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_encryption(&EncryptionKey::new([1; 32]));
+    db.set("token", &secret()).unwrap();
+
+    let res = PickleDb::load_encrypted("encrypted_wrong_key.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json,
+                                       &EncryptionKey::new([2; 32]));
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+
+    // a file encrypted with a key can't be loaded with a passphrase
+    let res = PickleDb::load_encrypted("encrypted_wrong_key.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json,
+                                       &EncryptionKey::from_passphrase("passphrase"));
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+}
+
+#[test]
+fn encrypted_with_passphrase() {
+    set_test_rsc!("encrypted_with_passphrase.db");
+
+    let key = EncryptionKey::from_passphrase("correct horse battery staple");
+    let mut db = PickleDb::new("encrypted_with_passphrase.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_encryption(&key);
+    db.set("token", &secret()).unwrap();
+
+    // the loaded DB keeps being dumped with the same passphrase
+    let mut db = PickleDb::load_encrypted("encrypted_with_passphrase.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json, &key).unwrap();
+    db.set("key1", &1).unwrap();
+    let read_db = PickleDb::load_encrypted("encrypted_with_passphrase.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+    assert_eq!(read_db.get::<String>("token").unwrap(), secret());
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+
+    let res = PickleDb::load_encrypted("encrypted_with_passphrase.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json,
+                                       &EncryptionKey::from_passphrase("wrong passphrase"));
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+}
+
+#[test]
+fn encrypted_tampered_file() {
+    set_test_rsc!("encrypted_tampered_file.db");
+
+    let key = EncryptionKey::new([3; 32]);
+    let mut db = PickleDb::new("encrypted_tampered_file.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin).with_encryption(&key);
+    db.set("token", &secret()).unwrap();
+    let contents = fs::read("encrypted_tampered_file.db").unwrap();
+
+    // flip a single bit of the content
+    let mut tampered = contents.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    fs::write("encrypted_tampered_file.db", &tampered).unwrap();
+    let res = PickleDb::load_encrypted("encrypted_tampered_file.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Bin, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+
+    // cut the file short
+    fs::write("encrypted_tampered_file.db", &contents[..contents.len() / 2]).unwrap();
+    let res = PickleDb::load_encrypted("encrypted_tampered_file.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Bin, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+}
+
+#[test]
+fn encrypted_append_only() {
+    set_test_rsc!("encrypted_append_only.db");
+
+    let key = EncryptionKey::new([4; 32]);
+    let mut db = PickleDb::new("encrypted_append_only.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json).with_encryption(&key);
+    db.set("key1", &1).unwrap();
+    db.set("token", &secret()).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "token", &secret()).unwrap();
+
+    // the changes are appended to the log encrypted
+    let log = fs::read("encrypted_append_only.db.log").unwrap();
+    assert!(!log.is_empty());
+    assert!(!contains(&log, &secret()));
+
+    let read_db = PickleDb::load_encrypted("encrypted_append_only.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+    assert_eq!(read_db.get::<String>("token").unwrap(), secret());
+    assert_eq!(read_db.dget::<String>("dict1", "token").unwrap(), secret());
+
+    // a tampered record in the log isn't replayed
+    let mut tampered = log;
+    tampered[10] ^= 1;
+    fs::write("encrypted_append_only.db.log", &tampered).unwrap();
+    let res = PickleDb::load_encrypted("encrypted_append_only.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+}
+
+#[test]
+fn encrypted_log_records_out_of_place() {
+    set_test_rsc!("encrypted_log_records_out_of_place.db");
+
+    let key = EncryptionKey::new([4; 32]);
+    let mut db = PickleDb::new("encrypted_log_records_out_of_place.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json)
+        .with_encryption(&key);
+    // the first change creates the DB file, the others are appended to the log
+    db.set("key1", &1).unwrap();
+    db.set("key1", &2).unwrap();
+    db.set("key1", &3).unwrap();
+    let log = fs::read("encrypted_log_records_out_of_place.db.log").unwrap();
+
+    // split the log into its records, each of them prefixed with its length
+    let first_len = 4 + u32::from_le_bytes([log[0], log[1], log[2], log[3]]) as usize;
+    let (first, second) = log.split_at(first_len);
+
+    // swapped records aren't replayed
+    fs::write("encrypted_log_records_out_of_place.db.log", [second, first].concat()).unwrap();
+    let res = PickleDb::load_encrypted("encrypted_log_records_out_of_place.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+
+    // a record that is appended again isn't replayed
+    fs::write("encrypted_log_records_out_of_place.db.log", [first, second, first].concat()).unwrap();
+    let res = PickleDb::load_encrypted("encrypted_log_records_out_of_place.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+
+    // the records in their place are replayed, and records appended after a reload follow them
+    fs::write("encrypted_log_records_out_of_place.db.log", &log).unwrap();
+    let mut db = PickleDb::load_encrypted("encrypted_log_records_out_of_place.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json, &key)
+        .unwrap();
+    assert_eq!(db.get::<i32>("key1").unwrap(), 3);
+    db.set("key1", &4).unwrap();
+    let read_db = PickleDb::load_encrypted("encrypted_log_records_out_of_place.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key)
+        .unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 4);
+}
+
+#[test]
+fn encrypt_existing_db() {
+    set_test_rsc!("encrypt_existing_db.db");
+
+    let mut db = PickleDb::new("encrypt_existing_db.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("token", &secret()).unwrap();
+
+    // an unencrypted file can't be loaded as encrypted
+    let key = EncryptionKey::new([5; 32]);
+    let res = PickleDb::load_encrypted("encrypt_existing_db.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+
+    // but a DB loaded from it can be encrypted
+    let mut db = PickleDb::load("encrypt_existing_db.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)
+        .unwrap()
+        .with_encryption(&key);
+    db.dump().unwrap();
+    assert!(!contains(&fs::read("encrypt_existing_db.db").unwrap(), &secret()));
+    let read_db = PickleDb::load_encrypted("encrypt_existing_db.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+    assert_eq!(read_db.get::<String>("token").unwrap(), secret());
+
+    // and its key can be changed
+    let new_key = EncryptionKey::from_passphrase("new passphrase");
+    let mut db = PickleDb::load_encrypted("encrypt_existing_db.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json, &key)
+        .unwrap()
+        .with_encryption(&new_key);
+    db.dump().unwrap();
+    assert!(PickleDb::load_encrypted("encrypt_existing_db.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).is_err());
+    assert!(PickleDb::load_encrypted("encrypt_existing_db.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &new_key).is_ok());
+}
+
+#[test]
+fn encrypted_readable_dump() {
+    set_test_rsc!("encrypted_readable_dump.db");
+
+    let key = EncryptionKey::new([6; 32]);
+    let mut db = PickleDb::new("encrypted_readable_dump.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_readable_dump(false)
+        .with_encryption(&key);
+    db.set("token", &secret()).unwrap();
+
+    assert!(!contains(&fs::read("encrypted_readable_dump.db").unwrap(), &secret()));
+    let read_db = PickleDb::load_encrypted("encrypted_readable_dump.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+    assert_eq!(read_db.get::<String>("token").unwrap(), secret());
+}
+
+#[test]
+fn encrypted_reload() {
+    set_test_rsc!("encrypted_reload.db");
+
+    let key = EncryptionKey::from_passphrase("passphrase");
+    let mut db = PickleDb::new("encrypted_reload.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_encryption(&key);
+    db.set("key1", &1).unwrap();
+    let mut read_db = PickleDb::load_encrypted("encrypted_reload.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+
+    // a new DB encrypts the file with the same passphrase but another salt
+    let mut db = PickleDb::new("encrypted_reload.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_encryption(&key);
+    db.set("key1", &2).unwrap();
+    assert!(read_db.reload_if_changed().unwrap());
+    assert_eq!(read_db.get::<i32>("key1"), Some(2));
+}
+
+#[test]
+fn encrypted_backups() {
+    set_test_rsc!("encrypted_backups.db");
+
+    let key = EncryptionKey::from_passphrase("passphrase");
+    let mut db = PickleDb::new("encrypted_backups.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_encryption(&key)
+        .with_backups(PickleDbBackupPolicy::EveryDump(2));
+    db.set("token", &secret()).unwrap();
+    db.set("token", &String::from("new-token")).unwrap();
+
+    // backups stay encrypted
+    let contents = fs::read("encrypted_backups.db.bak.1").unwrap();
+    assert!(!contains(&contents, &secret()));
+
+    db.restore_backup(1).unwrap();
+    assert_eq!(db.get::<String>("token").unwrap(), secret());
+    let read_db = PickleDb::load_encrypted("encrypted_backups.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+    assert_eq!(read_db.get::<String>("token").unwrap(), secret());
+
+    for backup in db.backups().unwrap() {
+        fs::remove_file(backup.path()).unwrap();
+    }
+}
+
+#[test]
+fn encrypted_save_as() {
+    set_test_rsc!("encrypted_save_as.db");
+    set_test_rsc!("encrypted_save_as_copy.db");
+
+    let key = EncryptionKey::new([3; 32]);
+    let mut db = PickleDb::new("encrypted_save_as.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).with_encryption(&key);
+    db.set("token", &secret()).unwrap();
+
+    // the copy is encrypted with the same key
+    db.snapshot().write_to("encrypted_save_as_copy.db").unwrap();
+    let contents = fs::read("encrypted_save_as_copy.db").unwrap();
+    assert!(!contains(&contents, &secret()));
+    let copy = PickleDb::load_encrypted("encrypted_save_as_copy.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key).unwrap();
+    assert_eq!(copy.get::<String>("token").unwrap(), secret());
+}
+
+#[test]
+fn encrypted_log_of_earlier_dump() {
+    set_test_rsc!("encrypted_log_of_earlier_dump.db");
+
+    let key = EncryptionKey::new([8; 32]);
+    let mut db = PickleDb::new("encrypted_log_of_earlier_dump.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json)
+        .with_encryption(&key);
+    db.set("balance", &5).unwrap();
+    db.set("balance", &999).unwrap();
+    let log = fs::read("encrypted_log_of_earlier_dump.db.log").unwrap();
+    db.compact().unwrap();
+
+    // the log of the dump before compact() isn't replayed on top of the new dump
+    fs::write("encrypted_log_of_earlier_dump.db.log", &log).unwrap();
+    let res = PickleDb::load_encrypted("encrypted_log_of_earlier_dump.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key);
+    assert!(matches!(res, Err(PickleDbError::Decryption(_))));
+}
+
+#[test]
+fn encrypted_truncated_log() {
+    set_test_rsc!("encrypted_truncated_log.db");
+
+    let key = EncryptionKey::new([9; 32]);
+    let mut db = PickleDb::new("encrypted_truncated_log.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json)
+        .with_encryption(&key);
+    db.set("key1", &1).unwrap();
+    // both keys are appended to the log together
+    db.transaction(|db| {
+        db.set("key1", &2)?;
+        db.set("key2", &2)
+    }).unwrap();
+    let log = fs::read("encrypted_truncated_log.db.log").unwrap();
+
+    // a record that was partially written by a crash is ignored along with the records that were appended with it
+    fs::write("encrypted_truncated_log.db.log", &log[..log.len() - 1]).unwrap();
+    let read_db = PickleDb::load_encrypted("encrypted_truncated_log.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key)
+        .unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+    assert!(!read_db.exists("key2"));
+
+    // and so is a log that was cut off right after the first record
+    let first_len = 4 + u32::from_le_bytes([log[0], log[1], log[2], log[3]]) as usize;
+    assert!(first_len < log.len());
+    fs::write("encrypted_truncated_log.db.log", &log[..first_len]).unwrap();
+    let mut db = PickleDb::load_encrypted("encrypted_truncated_log.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json, &key)
+        .unwrap();
+    assert_eq!(db.get::<i32>("key1"), Some(1));
+    assert!(!db.exists("key2"));
+
+    // the first record is cut off the log, so the changes appended later on can be read back
+    db.set("key3", &3).unwrap();
+    drop(db);
+    let read_db = PickleDb::load_encrypted("encrypted_truncated_log.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json, &key)
+        .unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+    assert!(!read_db.exists("key2"));
+    assert_eq!(read_db.get::<i32>("key3"), Some(3));
+}
@@ -0,0 +1,187 @@
+use pickledb::{ListEnd, PickleDb, PickleDbDumpPolicy, PickleDbError, PickleDbEvent, SerializationMethod, SharedPickleDb};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+fn received(events: &Receiver<PickleDbEvent>) -> Vec<PickleDbEvent> {
+    events.try_iter().collect()
+}
+
+fn key(name: &str) -> String {
+    String::from(name)
+}
+
+#[test]
+fn events_of_changes() {
+    set_test_rsc!("events_of_changes.db");
+
+    let mut db = PickleDb::new("events_of_changes.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    let events = db.subscribe_channel("");
+
+    db.set("key1", &1).unwrap();
+    db.incr("key1").unwrap();
+    db.lcreate("list1").unwrap().ladd(&1).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.sadd("set1", &1).unwrap();
+    db.zadd("zset1", 1.0, &1).unwrap();
+    db.rem("key1").unwrap();
+    assert_eq!(received(&events), vec![
+        PickleDbEvent::Set(key("key1")),
+        PickleDbEvent::Set(key("key1")),
+        PickleDbEvent::ListChanged(key("list1")),
+        PickleDbEvent::ListChanged(key("list1")),
+        PickleDbEvent::DictChanged(key("dict1")),
+        PickleDbEvent::SetChanged(key("set1")),
+        PickleDbEvent::SortedSetChanged(key("zset1")),
+        PickleDbEvent::Removed(key("key1")),
+    ]);
+
+    // a change of several keys sends an event for each of them
+    db.lcreate("list2").unwrap();
+    received(&events);
+    db.lmove::<i32>("list1", "list2", ListEnd::Front, ListEnd::Back).unwrap();
+    assert_eq!(received(&events), vec![PickleDbEvent::ListChanged(key("list1")), PickleDbEvent::ListChanged(key("list2"))]);
+
+    // but rotating a list sends a single event
+    db.lmove::<i32>("list2", "list2", ListEnd::Front, ListEnd::Back).unwrap();
+    assert_eq!(received(&events), vec![PickleDbEvent::ListChanged(key("list2"))]);
+
+    // a failed change sends no event
+    assert!(matches!(db.ladd("dict1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(received(&events).is_empty());
+
+    // removing keys that don't exist sends no event
+    assert!(!db.rem("no_such_key").unwrap());
+    assert_eq!(db.lrem_list("no_such_list").unwrap(), 0);
+    assert_eq!(db.drem("no_such_dict").unwrap(), 0);
+    db.set("key2", &2).unwrap();
+    received(&events);
+    assert_eq!(db.lrem_list("key2").unwrap(), 0);
+    assert_eq!(db.drem("key2").unwrap(), 0);
+    assert!(received(&events).is_empty());
+
+    // events are sent under every dump policy
+    let mut db = PickleDb::new("events_of_changes.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    let events = db.subscribe_channel("");
+    db.set("key1", &1).unwrap();
+    assert_eq!(received(&events), vec![PickleDbEvent::Set(key("key1"))]);
+}
+
+#[test]
+fn events_with_prefix() {
+    set_test_rsc!("events_with_prefix.db");
+
+    let mut db = PickleDb::new("events_with_prefix.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    let user_events = db.subscribe_channel("user:");
+    let all_events = db.subscribe_channel("");
+
+    db.set("user:1", &String::from("alice")).unwrap();
+    db.set("session:1", &100).unwrap();
+    db.set("user:2", &String::from("bob")).unwrap();
+
+    assert_eq!(received(&user_events), vec![PickleDbEvent::Set(key("user:1")), PickleDbEvent::Set(key("user:2"))]);
+    assert_eq!(received(&all_events).len(), 3);
+}
+
+#[test]
+fn events_callback_and_unsubscribe() {
+    set_test_rsc!("events_callback_and_unsubscribe.db");
+
+    let mut db = PickleDb::new("events_callback_and_unsubscribe.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    let keys = Arc::new(Mutex::new(Vec::new()));
+    let callback_keys = Arc::clone(&keys);
+    let subscription = db.subscribe("", move |event| callback_keys.lock().unwrap().push(String::from(event.key())));
+
+    db.set("key1", &1).unwrap();
+    db.set("key2", &2).unwrap();
+    assert!(db.unsubscribe(subscription));
+    assert!(!db.unsubscribe(subscription));
+    db.set("key3", &3).unwrap();
+    assert_eq!(*keys.lock().unwrap(), vec!["key1", "key2"]);
+
+    // a channel subscription ends when the receiver is dropped
+    let events = db.subscribe_channel("");
+    drop(events);
+    db.set("key4", &4).unwrap();
+}
+
+#[test]
+fn events_of_expired_keys() {
+    set_test_rsc!("events_of_expired_keys.db");
+
+    let mut db = PickleDb::new("events_of_expired_keys.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    let events = db.subscribe_channel("");
+    db.set_with_ttl("key1", &1, Duration::from_millis(50)).unwrap();
+    db.set_with_ttl("key2", &2, Duration::from_millis(50)).unwrap();
+    received(&events);
+
+    thread::sleep(Duration::from_millis(100));
+
+    // the expired key is removed when it's changed
+    assert_eq!(db.incr("key1").unwrap(), 1);
+    assert_eq!(received(&events), vec![PickleDbEvent::Expired(key("key1")), PickleDbEvent::Set(key("key1"))]);
+
+    // or when the DB is dumped
+    db.dump().unwrap();
+    assert_eq!(received(&events), vec![PickleDbEvent::Expired(key("key2"))]);
+
+    // removing an expired list or dict is like removing a missing one, so it's not a change
+    db.lcreate("list1").unwrap().ladd(&1).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "a", &1).unwrap();
+    db.expire("list1", Duration::from_millis(50)).unwrap();
+    db.expire("dict1", Duration::from_millis(50)).unwrap();
+    db.dump().unwrap();
+    received(&events);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(db.lrem_list("list1").unwrap(), 0);
+    assert_eq!(db.drem("dict1").unwrap(), 0);
+    assert_eq!(received(&events), vec![PickleDbEvent::Expired(key("list1")), PickleDbEvent::Expired(key("dict1"))]);
+    assert!(!db.is_dirty());
+}
+
+#[test]
+fn events_of_transactions() {
+    set_test_rsc!("events_of_transactions.db");
+
+    let mut db = PickleDb::new("events_of_transactions.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    let events = db.subscribe_channel("");
+
+    // events are sent when the transaction is committed
+    db.begin().unwrap();
+    db.set("key1", &1).unwrap();
+    db.set("key2", &2).unwrap();
+    assert!(received(&events).is_empty());
+    db.commit().unwrap();
+    assert_eq!(received(&events), vec![PickleDbEvent::Set(key("key1")), PickleDbEvent::Set(key("key2"))]);
+
+    // and discarded when it's rolled back
+    let res: Result<(), PickleDbError> = db.transaction(|db| {
+        db.rem("key1")?;
+        db.ladd("key2", &1)?;
+        Ok(())
+    });
+    assert!(res.is_err());
+    assert!(received(&events).is_empty());
+    assert!(db.exists("key1"));
+}
+
+#[test]
+fn events_of_shared_db() {
+    set_test_rsc!("events_of_shared_db.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("events_of_shared_db.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json));
+    let events = db.subscribe_channel("");
+
+    let writer_db = db.clone();
+    thread::spawn(move || {
+        writer_db.set("key1", &1).unwrap();
+        writer_db.rem("key1").unwrap();
+    }).join().unwrap();
+
+    assert_eq!(events.recv().unwrap(), PickleDbEvent::Set(key("key1")));
+    assert_eq!(events.recv().unwrap(), PickleDbEvent::Removed(key("key1")));
+}
@@ -1,4 +1,4 @@
-use pickledb::{PickleDb, PickleDbDumpPolicy};
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
 
 mod common;
 
@@ -9,23 +9,23 @@ extern crate serde_derive;
 fn basic_set_get() {
     set_test_rsc!("basic_set_get.db");
 
-    let mut db = PickleDb::new("basic_set_get.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("basic_set_get.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // set a number
     let num = 100;
-    db.set("num", &num);
+    db.set("num", &num).unwrap();
 
     // set a floating point number
     let float_num = 1.224;
-    db.set("float", &float_num);
+    db.set("float", &float_num).unwrap();
 
     // set a String
     let mystr = String::from("my string");
-    db.set("string", &mystr);
+    db.set("string", &mystr).unwrap();
 
     // set a Vec
     let myvec = vec![1,2,3];
-    db.set("vec", &myvec);
+    db.set("vec", &myvec).unwrap();
 
     // set a struct
     #[derive(Serialize, Deserialize, Debug)]
@@ -35,7 +35,7 @@ fn basic_set_get() {
     }
     let mycoor = Coor { x: 1, y
     : 2 };
-    db.set("struct", &mycoor);
+    db.set("struct", &mycoor).unwrap();
 
 
     // read a num
@@ -57,23 +57,23 @@ fn set_load_get() {
     set_test_rsc!("set_load_get.db");
 
     // create a db with auto_dump == false
-    let mut db = PickleDb::new("set_load_get.db", PickleDbDumpPolicy::DumpUponRequest);
+    let mut db = PickleDb::new("set_load_get.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
 
     // set a number
     let num = 100;
-    db.set("num", &num);
+    db.set("num", &num).unwrap();
 
     // set a floating point number
     let float_num = 1.224;
-    db.set("float", &float_num);
+    db.set("float", &float_num).unwrap();
 
     // set a String
     let mystr = String::from("my string");
-    db.set("string", &mystr);
+    db.set("string", &mystr).unwrap();
 
     // set a Vec
     let myvec = vec![1,2,3];
-    db.set("vec", &myvec);
+    db.set("vec", &myvec).unwrap();
 
     // set a struct
     #[derive(Serialize, Deserialize, Debug)]
@@ -83,14 +83,14 @@ fn set_load_get() {
     }
     let mycoor = Coor { x: 1, y
     : 2 };
-    db.set("struct", &mycoor);
+    db.set("struct", &mycoor).unwrap();
 
 
     // dump db to file
-    assert!(db.dump());
+    assert!(db.dump().is_ok());
 
     // read db from file
-    let read_db = PickleDb::load_read_only("set_load_get.db").unwrap();
+    let read_db = PickleDb::load_read_only("set_load_get.db", SerializationMethod::Json).unwrap();
 
     // read a num
     assert_eq!(read_db.get::<i32>("num").unwrap(), num);
@@ -111,23 +111,23 @@ fn set_load_get_auto_dump() {
     set_test_rsc!("set_load_get_auto_dump.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("set_load_get_auto_dump.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("set_load_get_auto_dump.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // set a number
     let num = 100;
-    db.set("num", &num);
+    db.set("num", &num).unwrap();
 
     // set a floating point number
     let float_num = 1.224;
-    db.set("float", &float_num);
+    db.set("float", &float_num).unwrap();
 
     // set a String
     let mystr = String::from("my string");
-    db.set("string", &mystr);
+    db.set("string", &mystr).unwrap();
 
     // set a Vec
     let myvec = vec![1,2,3];
-    db.set("vec", &myvec);
+    db.set("vec", &myvec).unwrap();
 
     // set a struct
     #[derive(Serialize, Deserialize, Debug)]
@@ -137,10 +137,10 @@ fn set_load_get_auto_dump() {
     }
     let mycoor = Coor { x: 1, y
     : 2 };
-    db.set("struct", &mycoor);
+    db.set("struct", &mycoor).unwrap();
 
 
-    let read_db = PickleDb::load_read_only("set_load_get_auto_dump.db").unwrap();
+    let read_db = PickleDb::load_read_only("set_load_get_auto_dump.db", SerializationMethod::Json).unwrap();
 
     // read a num
     assert_eq!(read_db.get::<i32>("num").unwrap(), num);
@@ -160,46 +160,46 @@ fn set_load_get_auto_dump2() {
     set_test_rsc!("set_load_get_auto_dump2.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("set_load_get_auto_dump2.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("set_load_get_auto_dump2.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // set a number
     let num = 100;
-    db.set("num", &num);
+    db.set("num", &num).unwrap();
 
     // read this number immediately
     {
-        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db").unwrap();
+        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.get::<i32>("num").unwrap(), num);
     }
 
     // set another number
     let num2 = 200;
-    db.set("num2", &num2);
+    db.set("num2", &num2).unwrap();
 
     // read this other number immediately
     {
-        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db").unwrap();
+        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.get::<i32>("num2").unwrap(), num2);
     }
 
     // set a different value for a given key
-    db.set("num", &101);
+    db.set("num", &101).unwrap();
 
     // read the new value
     assert_eq!(db.get::<i32>("num").unwrap(), 101);
     {
-        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db").unwrap();
+        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.get::<i32>("num").unwrap(), 101);
     }
 
     // set a different value of a different type for a given key
-    db.set("num", &vec![1,2,3]);
+    db.set("num", &vec![1,2,3]).unwrap();
 
     // read the new value
     assert!(db.get::<i32>("num").is_none());
     assert_eq!(db.get::<Vec<i32>>("num").unwrap(), vec![1,2,3]);
     {
-        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db").unwrap();
+        let read_db = PickleDb::load_read_only("set_load_get_auto_dump2.db", SerializationMethod::Json).unwrap();
         assert_eq!(read_db.get::<Vec<i32>>("num").unwrap(), vec![1,2,3]);
     }
 
@@ -211,16 +211,16 @@ fn set_special_strings() {
     set_test_rsc!("set_special_strings.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("set_special_strings.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("set_special_strings.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
-    db.set("string1", &String::from("\"dobule_quotes\""));
-    db.set("string2", &String::from("\'single_quotes\'"));
-    db.set("string3", &String::from("שָׁלוֹם"));
-    db.set("string4", &String::from("😻"));
-    db.set("string5", &String::from("\nescapes\t\r"));
-    db.set("string6", &String::from("my\\folder"));
+    db.set("string1", &String::from("\"dobule_quotes\"")).unwrap();
+    db.set("string2", &String::from("\'single_quotes\'")).unwrap();
+    db.set("string3", &String::from("שָׁלוֹם")).unwrap();
+    db.set("string4", &String::from("😻")).unwrap();
+    db.set("string5", &String::from("\nescapes\t\r")).unwrap();
+    db.set("string6", &String::from("my\\folder")).unwrap();
 
-    let read_db = PickleDb::load_read_only("set_special_strings.db").unwrap();
+    let read_db = PickleDb::load_read_only("set_special_strings.db", SerializationMethod::Json).unwrap();
     assert_eq!(read_db.get::<String>("string1").unwrap(), String::from("\"dobule_quotes\""));
     assert_eq!(read_db.get::<String>("string2").unwrap(), String::from("\'single_quotes\'"));
     assert_eq!(read_db.get::<String>("string3").unwrap(), String::from("שָׁלוֹם"));
@@ -234,13 +234,13 @@ fn edge_cases() {
     set_test_rsc!("edge_cases.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("edge_cases.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("edge_cases.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     let x = 123;
-    db.set("num", &x);
+    db.set("num", &x).unwrap();
 
     // load a read only version of the db from file
-    let read_db = PickleDb::load_read_only("edge_cases.db").unwrap();
+    let read_db = PickleDb::load_read_only("edge_cases.db", SerializationMethod::Json).unwrap();
 
     assert_eq!(db.get::<i32>("num"), Some(x));
     assert_eq!(read_db.get::<i32>("num"), Some(x));
@@ -248,17 +248,48 @@ fn edge_cases() {
     assert_eq!(read_db.get::<String>("num"), None);
 }
 
+#[test]
+fn try_get_missing_vs_wrong_type() {
+    set_test_rsc!("try_get_missing_vs_wrong_type.db");
+
+    let mut db = PickleDb::new("try_get_missing_vs_wrong_type.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+
+    db.set("num", &123).unwrap();
+    db.lcreate("list1").unwrap();
+
+    // a value of the right type
+    assert_eq!(db.try_get::<i32>("num").unwrap(), Some(123));
+
+    // a key that doesn't exist
+    assert_eq!(db.try_get::<i32>("no_such_key").unwrap(), None);
+
+    // a value of the wrong type reports the key name
+    match db.try_get::<String>("num") {
+        Err(PickleDbError::Deserialization { key, .. }) => assert_eq!(key, "num"),
+        _ => panic!(),
+    }
+
+    // a key that holds a list
+    assert!(matches!(db.try_get::<i32>("list1"), Err(PickleDbError::WrongType(_))));
+
+    // same for the iterator items
+    for key_value in db.iter() {
+        assert_eq!(key_value.try_get_value::<i32>().unwrap(), 123);
+        assert!(matches!(key_value.try_get_value::<String>(), Err(PickleDbError::Deserialization { .. })));
+    }
+}
+
 #[test]
 fn get_all_keys() {
     set_test_rsc!("get_all_keys.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("get_all_keys.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("get_all_keys.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // insert 10 keys: key0..key9
     let num = 100;
     for i in 0..10 {
-        db.set(&format!("{}{}", "key", i), &num);
+        db.set(&format!("{}{}", "key", i), &num).unwrap();
     }
 
     // verify we have 10 keys
@@ -281,33 +312,33 @@ fn rem_keys() {
     set_test_rsc!("rem_keys.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("rem_keys.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("rem_keys.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // insert 10 keys: key0..key9
     let num = 100;
     for i in 0..10 {
-        db.set(&format!("{}{}", "key", i), &num);
+        db.set(&format!("{}{}", "key", i), &num).unwrap();
     }
 
     // remove 2 keys
-    assert!(db.rem("key5"));
-    assert!(db.rem("key8"));
+    assert!(db.rem("key5").unwrap());
+    assert!(db.rem("key8").unwrap());
 
     // verify we only have 8 keys now
     assert_eq!(db.total_keys(), 8);
 
     // verify both keys were removed
-    for i in vec![5,8].iter() {
-        assert_eq!(db.exists(&format!("{}{}", "key", i)), false);
+    for i in [5,8].iter() {
+        assert!(!db.exists(&format!("{}{}", "key", i)));
     }
 
     // verify the other keys are still there
-    for i in vec![0,1,2,3,4,6,7,9].iter() {
+    for i in [0,1,2,3,4,6,7,9].iter() {
         assert!(db.exists(&format!("{}{}", "key", i)));
     }
 
     // verify keys were also removed from the file
-    let read_db = PickleDb::load_read_only("rem_keys.db").unwrap();
+    let read_db = PickleDb::load_read_only("rem_keys.db", SerializationMethod::Json).unwrap();
     assert_eq!(read_db.total_keys(), 8);
 }
 
@@ -316,18 +347,18 @@ fn iter_test() {
     set_test_rsc!("iter_test.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("iter_test.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("iter_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
-    let keys = vec!["key1", "key2", "key3", "key4", "key5"];
+    let keys = ["key1", "key2", "key3", "key4", "key5"];
     // add a few keys and values
-    db.set(keys[0], &1);
-    db.set(keys[1], &1.1);
-    db.set(keys[2], &String::from("value1"));
-    db.set(keys[3], &vec![1,2,3]);
-    db.set(keys[4], &('a', 'b', 'c'));
+    db.set(keys[0], &1).unwrap();
+    db.set(keys[1], &1.1).unwrap();
+    db.set(keys[2], &String::from("value1")).unwrap();
+    db.set(keys[3], &vec![1,2,3]).unwrap();
+    db.set(keys[4], &('a', 'b', 'c')).unwrap();
 
     // iterate the db
-    let mut keys_seen = vec![false, false, false, false, false];
+    let mut keys_seen = [false, false, false, false, false];
     for key_value in db.iter() {
 
         // find the index of the current key in the keys vec
@@ -343,11 +374,11 @@ fn iter_test() {
             "key3" => assert_eq!(key_value.get_value::<String>().unwrap(), String::from("value1")),
             "key4" => assert_eq!(key_value.get_value::<Vec<i32>>().unwrap(), vec![1,2,3]),
             "key5" => assert_eq!(key_value.get_value::<(char, char, char)>().unwrap(), ('a', 'b', 'c')),
-            _ => assert!(false)
+            _ => panic!()
         }
     }
 
     // verify all 5 keys were seen
-    assert_eq!(keys_seen.iter().filter(|&t| *t == true).count(), 5);
+    assert_eq!(keys_seen.iter().filter(|&t| *t).count(), 5);
 }
 
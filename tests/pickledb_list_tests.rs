@@ -1,4 +1,4 @@
-use pickledb::{PickleDb,PickleDbDumpPolicy};
+use pickledb::{ListEnd, PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
 
 mod common;
 
@@ -9,25 +9,25 @@ extern crate serde_derive;
 fn basic_lists() {
     set_test_rsc!("basic_lists.db");
 
-    let mut db = PickleDb::new("basic_lists.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("basic_lists.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
-    db.lcreate("list1");
+    db.lcreate("list1").unwrap();
 
     // add a number to list1
     let num = 100;
-    assert!(db.ladd("list1", &num).is_some());
+    assert!(db.ladd("list1", &num).is_ok());
 
     // add a floating point number to list1
     let float_num = 1.224;
-    assert!(db.ladd("list1", &float_num).is_some());
+    assert!(db.ladd("list1", &float_num).is_ok());
 
     // add a string to list1
     let mystr = String::from("my string");
-    assert!(db.ladd("list1", &mystr).is_some());
+    assert!(db.ladd("list1", &mystr).is_ok());
 
     // add a Vec to list1
     let myvec = vec![1,2,3];
-    assert!(db.ladd("list1", &myvec).is_some());
+    assert!(db.ladd("list1", &myvec).is_ok());
 
     // add a struct to list1
     #[derive(Serialize, Deserialize, Debug)]
@@ -37,18 +37,18 @@ fn basic_lists() {
     }
     let mycoor = Coor { x: 1, y
     : 2 };
-    assert!(db.ladd("list1", &mycoor).is_some());
+    assert!(db.ladd("list1", &mycoor).is_ok());
 
     // create another list
-    db.lcreate("list2");
+    db.lcreate("list2").unwrap();
 
     // add a number to list2
     let num2 = 200;
-    assert!(db.ladd("list2", &num2).is_some());
+    assert!(db.ladd("list2", &num2).is_ok());
 
     // add a string to list2
     let mystr2 = String::from("hello world");
-    assert!(db.ladd("list2", &mystr2).is_some());
+    assert!(db.ladd("list2", &mystr2).is_ok());
 
 
     // read first item in list1 - int
@@ -81,7 +81,7 @@ fn basic_lists() {
 
 
     // load the file as read only db
-    let read_db = PickleDb::load("basic_lists.db", PickleDbDumpPolicy::NeverDump).unwrap();
+    let read_db = PickleDb::load("basic_lists.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
 
     // verify lists length
     assert_eq!(read_db.llen("list1"), 5);
@@ -114,29 +114,29 @@ fn basic_lists() {
 fn add_and_extend_lists() {
     set_test_rsc!("add_and_extend_lists.db");
 
-    let mut db = PickleDb::new("add_and_extend_lists.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("add_and_extend_lists.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // create 3 lists
-    db.lcreate("list1");
-    db.lcreate("list2");
-    db.lcreate("list3");
+    db.lcreate("list1").unwrap();
+    db.lcreate("list2").unwrap();
+    db.lcreate("list3").unwrap();
 
     // list1 - add 6 elements using lextend
-    assert!(db.lextend("list1", &vec![1,2,3,4,5,6]).is_some());
+    assert!(db.lextend("list1", &[1,2,3,4,5,6]).is_ok());
 
     // list1 - add 6 elements using ladd
     db.ladd("list2", &1).unwrap()
-        .ladd(&2)
-        .ladd(&3)
-        .ladd(&4)
-        .ladd(&5)
-        .ladd(&6);
+        .ladd(&2).unwrap()
+        .ladd(&3).unwrap()
+        .ladd(&4).unwrap()
+        .ladd(&5).unwrap()
+        .ladd(&6).unwrap();
 
     // list3 - add 6 elements using lextend and ladd
     db.ladd("list3", &1).unwrap()
-        .lextend(&vec![2,3])
-        .ladd(&4)
-        .lextend(&vec![5,6]);
+        .lextend(&[2,3]).unwrap()
+        .ladd(&4).unwrap()
+        .lextend(&[5,6]).unwrap();
 
     // verify lists length
     assert_eq!(db.llen("list1"), 6);
@@ -151,7 +151,7 @@ fn add_and_extend_lists() {
     }
 
     // read db from file
-    let read_db = PickleDb::load("add_and_extend_lists.db", PickleDbDumpPolicy::NeverDump).unwrap();
+    let read_db = PickleDb::load("add_and_extend_lists.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
 
     // check all values in all lists
     for x in 0..5 {
@@ -165,17 +165,17 @@ fn add_and_extend_lists() {
 fn override_lists() {
     set_test_rsc!("override_lists.db");
 
-    let mut db = PickleDb::new("override_lists.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("override_lists.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // create a list and add some values to it
-    db.lcreate("list1")
-      .lextend(&vec!["aa", "bb", "cc"]);
+    db.lcreate("list1").unwrap()
+      .lextend(&["aa", "bb", "cc"]).unwrap();
 
     // verify list len is 3
     assert_eq!(db.llen("list1"), 3);
 
     // override the list
-    db.lcreate("list1");
+    db.lcreate("list1").unwrap();
 
     // verify list is now empty (override)
     assert!(db.lexists("list1"));
@@ -183,13 +183,13 @@ fn override_lists() {
 
     // read the list from file and verify the same
     {
-        let read_db = PickleDb::load("override_lists.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("override_lists.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert!(read_db.lexists("list1"));
         assert_eq!(read_db.llen("list1"), 0);
     }
 
     // add items to the override list
-    assert!(db.lextend("list1", &vec![1,2,3,4]).is_some());
+    assert!(db.lextend("list1", &[1,2,3,4]).is_ok());
 
     // verify list contains the new data
     assert!(db.lexists("list1"));
@@ -197,7 +197,7 @@ fn override_lists() {
 
     // read the list from file and verify the same
     {
-        let read_db = PickleDb::load("override_lists.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("override_lists.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert!(read_db.lexists("list1"));
         assert_eq!(read_db.llen("list1"), 4);
     }
@@ -207,12 +207,12 @@ fn override_lists() {
 fn lget_corner_cases() {
     set_test_rsc!("lget_corner_cases.db");
 
-    let mut db = PickleDb::new("lget_corner_cases.db", PickleDbDumpPolicy::DumpUponRequest);
+    let mut db = PickleDb::new("lget_corner_cases.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
 
     // create a list and add some values
-    db.lcreate("list1")
-      .lextend(&vec!["hello", "world", "good", "morning"])
-      .ladd(&100);
+    db.lcreate("list1").unwrap()
+      .lextend(&["hello", "world", "good", "morning"]).unwrap()
+      .ladd(&100).unwrap();
 
     // lget values that exist
     assert_eq!(db.lget::<String>("list1", 0).unwrap(), "hello");
@@ -229,87 +229,110 @@ fn lget_corner_cases() {
 
     // lget list that doesn't exist
     assert!(db.lget::<i32>("list2", 5).is_none());
+
+    // try_lget tells a missing item apart from a wrong type
+    assert_eq!(db.try_lget::<String>("list1", 0).unwrap(), Some(String::from("hello")));
+    assert_eq!(db.try_lget::<i32>("list1", 5).unwrap(), None);
+    assert_eq!(db.try_lget::<i32>("list2", 0).unwrap(), None);
+    match db.try_lget::<i32>("list1", 0) {
+        Err(PickleDbError::Deserialization { key, .. }) => assert_eq!(key, "list1"),
+        _ => panic!(),
+    }
+    db.set("key1", &1).unwrap();
+    assert!(matches!(db.try_lget::<i32>("key1", 0), Err(PickleDbError::WrongType(_))));
+
+    // same for the list iterator items
+    let item = db.liter("list1").unwrap().next().unwrap();
+    assert_eq!(item.try_get_item::<String>().unwrap(), "hello");
+    assert!(matches!(item.try_get_item::<i32>(), Err(PickleDbError::Deserialization { .. })));
 }
 
 #[test]
 fn add_to_non_existent_list() {
     set_test_rsc!("add_to_non_existent_list.db");
 
-    let mut db = PickleDb::new("add_to_non_existent_list.db", PickleDbDumpPolicy::DumpUponRequest);
+    let mut db = PickleDb::new("add_to_non_existent_list.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
 
     let num = 100;
     let vec_of_nums = vec![1,2,3];
 
     // add items to list that doesn't exist
-    assert!(db.ladd("list1", &num).is_none());
-    assert!(db.lextend("list1", &vec_of_nums).is_none());
+    assert!(db.ladd("list1", &num).is_err());
+    assert!(db.lextend("list1", &vec_of_nums).is_err());
 
     // creat a list
-    db.lcreate("list1");
+    db.lcreate("list1").unwrap();
 
     // add items to list that doesn't exist
-    assert!(db.ladd("list2", &num).is_none());
-    assert!(db.lextend("list2", &vec_of_nums).is_none());
+    assert!(db.ladd("list2", &num).is_err());
+    assert!(db.lextend("list2", &vec_of_nums).is_err());
 
     // add items to the list that was created
-    assert!(db.ladd("list1", &num).is_some());
-    assert!(db.lextend("list1", &vec_of_nums).is_some());
+    assert!(db.ladd("list1", &num).is_ok());
+    assert!(db.lextend("list1", &vec_of_nums).is_ok());
 
     // delete the list
-    assert!(db.rem("list1"));
+    assert!(db.rem("list1").unwrap());
 
     // add items to list that doesn't exist
-    assert!(db.ladd("list1", &num).is_none());
-    assert!(db.lextend("list1", &vec_of_nums).is_none());
+    assert!(db.ladd("list1", &num).is_err());
+    assert!(db.lextend("list1", &vec_of_nums).is_err());
+
+    // the error tells whether the list is missing or the key holds a value
+    assert!(matches!(db.ladd("list1", &num), Err(PickleDbError::KeyNotFound(_))));
+    db.set("list1", &num).unwrap();
+    assert!(matches!(db.ladd("list1", &num), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.lpop::<i32>("list1", 0), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.lrem_value("list1", &num), Err(PickleDbError::WrongType(_))));
 }
 
 #[test]
 fn remove_list() {
     set_test_rsc!("remove_list.db");
 
-    let mut db = PickleDb::new("remove_list.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("remove_list.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // create some lists add add values to them
-    db.lcreate("list1")
-      .lextend(&vec![1,2,3,4,5,6,7,8,9,10]);
+    db.lcreate("list1").unwrap()
+      .lextend(&[1,2,3,4,5,6,7,8,9,10]).unwrap();
 
-    db.lcreate("list2")
-      .lextend(&vec!['a', 'b', 'c', 'd', 'e']);
+    db.lcreate("list2").unwrap()
+      .lextend(&['a', 'b', 'c', 'd', 'e']).unwrap();
 
-    db.lcreate("list3")
-      .lextend(&vec![1.2, 1.3, 2.1, 3.1, 3.3, 7.889]);
+    db.lcreate("list3").unwrap()
+      .lextend(&[1.2, 1.3, 2.1, 3.1, 3.3, 7.889]).unwrap();
 
-    db.lcreate("list4")
-      .lextend(&vec!["aaa", "bbb", "ccc", "ddd", "eee"]);
+    db.lcreate("list4").unwrap()
+      .lextend(&["aaa", "bbb", "ccc", "ddd", "eee"]).unwrap();
 
     // verify number of lists in file
     {
-        let read_db = PickleDb::load("remove_list.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("remove_list.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert_eq!(read_db.total_keys(), 4);
     }
 
     // remove list1 using rem
-    assert!(db.rem("list1"));
+    assert!(db.rem("list1").unwrap());
 
     // verify number of lists
     assert_eq!(db.total_keys(), 3);
 
     // verify number of lists in file
     {
-        let read_db = PickleDb::load("remove_list.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("remove_list.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert_eq!(read_db.total_keys(), 3);
     }
 
 
     // remove list1 using lrem_list
-    assert_eq!(db.lrem_list("list3"), 6);
+    assert_eq!(db.lrem_list("list3").unwrap(), 6);
 
     // verify number of lists
     assert_eq!(db.total_keys(), 2);
 
     // verify number of lists in file
     {
-        let read_db = PickleDb::load("remove_list.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("remove_list.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert_eq!(read_db.total_keys(), 2);
     }
 }
@@ -318,7 +341,7 @@ fn remove_list() {
 fn remove_values_from_list() {
     set_test_rsc!("remove_values_from_list.db");
 
-    let mut db = PickleDb::new("remove_values_from_list.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("remove_values_from_list.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // add a struct to list1
     #[derive(Serialize, Deserialize, Debug)]
@@ -327,18 +350,18 @@ fn remove_values_from_list() {
     }
 
     // create a list and add some values
-    db.lcreate("list1")
-      .lextend(&vec![1,2,3])
-      .ladd(&String::from("hello"))
-      .ladd(&1.234)
-      .lextend(&vec![MySquare { x: 4 }, MySquare { x: 10 }]);
+    db.lcreate("list1").unwrap()
+      .lextend(&[1,2,3]).unwrap()
+      .ladd(&String::from("hello")).unwrap()
+      .ladd(&1.234).unwrap()
+      .lextend(&[MySquare { x: 4 }, MySquare { x: 10 }]).unwrap();
 
     // list now looks like this:
     // Indices: [0, 1, 2, 3,       4,     5,           6           ]
     // Values:  [1, 2, 3, "hello", 1.234, MySquare(4), MySquare(10)]
 
     // pop the floating number
-    assert_eq!(db.lpop::<f32>("list1", 4).unwrap(), 1.234);
+    assert_eq!(db.lpop::<f32>("list1", 4).unwrap().unwrap(), 1.234);
 
     // list now looks like this:
     // Indices: [0, 1, 2, 3,       4,           5           ]
@@ -349,13 +372,13 @@ fn remove_values_from_list() {
 
     // read this from file as well
     {
-        let read_db = PickleDb::load("remove_values_from_list.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("remove_values_from_list.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert_eq!(read_db.lget::<MySquare>("list1", 4).unwrap().x, 4);
         assert_eq!(read_db.lget::<String>("list1", 3).unwrap(), "hello");
     }
 
     // pop the first element
-    assert_eq!(db.lpop::<i32>("list1", 0).unwrap(), 1);
+    assert_eq!(db.lpop::<i32>("list1", 0).unwrap().unwrap(), 1);
 
     // list now looks like this:
     // Indices: [0, 1, 2,       3,           4           ]
@@ -364,8 +387,15 @@ fn remove_values_from_list() {
     assert_eq!(db.lget::<MySquare>("list1", 4).unwrap().x, 10);
     assert_eq!(db.lget::<i32>("list1", 1).unwrap(), 3);
 
+    // pop an item with the wrong type, it shouldn't be removed
+    assert!(matches!(db.lpop::<MySquare>("list1", 2), Err(PickleDbError::Deserialization { .. })));
+    assert_eq!(db.llen("list1"), 5);
+
+    // pop an item out of bounds
+    assert!(db.lpop::<i32>("list1", 5).unwrap().is_none());
+
     // remove the "hello" string
-    assert!(db.lrem_value("list1", &String::from("hello")));
+    assert!(db.lrem_value("list1", &String::from("hello")).unwrap());
 
     // list now looks like this:
     // Indices: [0, 1, 2,           3           ]
@@ -376,13 +406,13 @@ fn remove_values_from_list() {
 
     // read this from file as well
     {
-        let read_db = PickleDb::load("remove_values_from_list.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("remove_values_from_list.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert_eq!(read_db.lget::<MySquare>("list1", 3).unwrap().x, 10);
         assert_eq!(read_db.lget::<i32>("list1", 1).unwrap(), 3);
     }
 
     // remove the MySquare(4)
-    assert!(db.lrem_value("list1", &MySquare { x: 4 }));
+    assert!(db.lrem_value("list1", &MySquare { x: 4 }).unwrap());
 
     // list now looks like this:
     // Indices: [0, 1, 2           ]
@@ -393,7 +423,7 @@ fn remove_values_from_list() {
 
     // read this from file as well
     {
-        let read_db = PickleDb::load("remove_values_from_list.db", PickleDbDumpPolicy::NeverDump).unwrap();
+        let read_db = PickleDb::load("remove_values_from_list.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json).unwrap();
         assert_eq!(read_db.lget::<MySquare>("list1", 2).unwrap().x, 10);
         assert_eq!(read_db.lget::<i32>("list1", 0).unwrap(), 2);
     }
@@ -403,16 +433,16 @@ fn remove_values_from_list() {
 fn list_with_special_strings() {
     set_test_rsc!("list_with_special_strings.db");
 
-    let mut db = PickleDb::new("list_with_special_strings.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("list_with_special_strings.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     // create a list and add special strings to it
-    db.lcreate("list1")
-      .ladd(&String::from("\"dobule_quotes\""))
-      .ladd(&String::from("\'single_quotes\'"))
-      .ladd(&String::from("שָׁלוֹם"))
-      .ladd(&String::from("😻"))
-      .ladd(&String::from("\nescapes\t\r"))
-      .ladd(&String::from("my\\folder"));
+    db.lcreate("list1").unwrap()
+      .ladd(&String::from("\"dobule_quotes\"")).unwrap()
+      .ladd(&String::from("\'single_quotes\'")).unwrap()
+      .ladd(&String::from("שָׁלוֹם")).unwrap()
+      .ladd(&String::from("😻")).unwrap()
+      .ladd(&String::from("\nescapes\t\r")).unwrap()
+      .ladd(&String::from("my\\folder")).unwrap();
  
     // read special strings
     assert_eq!(db.lget::<String>("list1", 0).unwrap(), String::from("\"dobule_quotes\""));
@@ -423,7 +453,7 @@ fn list_with_special_strings() {
     assert_eq!(db.lget::<String>("list1", 5).unwrap(), String::from("my\\folder"));
 
     // load db from file
-    let read_db = PickleDb::load_read_only("list_with_special_strings.db").unwrap();
+    let read_db = PickleDb::load_read_only("list_with_special_strings.db", SerializationMethod::Json).unwrap();
 
     // read strgins from list loaded from file
     assert_eq!(read_db.lget::<String>("list1", 0).unwrap(), String::from("\"dobule_quotes\""));
@@ -439,22 +469,22 @@ fn list_iter_test() {
     set_test_rsc!("list_iter_test.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("list_iter_test.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("list_iter_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     let values = (1, 1.1, String::from("value"), vec![1,2,3], ('a', 'b', 'c'));
 
     // create a list with some values
-    db.lcreate("list1")
-      .ladd(&values.0)
-      .ladd(&values.1)
-      .ladd(&values.2)
-      .ladd(&values.3)
-      .ladd(&values.4);
+    db.lcreate("list1").unwrap()
+      .ladd(&values.0).unwrap()
+      .ladd(&values.1).unwrap()
+      .ladd(&values.2).unwrap()
+      .ladd(&values.3).unwrap()
+      .ladd(&values.4).unwrap();
 
     let mut index = 0;
 
     // iterate over the list
-    for item in db.liter("list1") {
+    for item in db.liter("list1").unwrap() {
         // check each item
         match index {
             0 => assert_eq!(item.get_item::<i32>().unwrap(), values.0),
@@ -462,7 +492,7 @@ fn list_iter_test() {
             2 => assert_eq!(item.get_item::<String>().unwrap(), values.2),
             3 => assert_eq!(item.get_item::<Vec<i32>>().unwrap(), values.3),
             4 => assert_eq!(item.get_item::<(char, char, char)>().unwrap(), values.4),
-            _ => assert!(false)
+            _ => panic!()
         }
         index += 1;
     }
@@ -472,25 +502,191 @@ fn list_iter_test() {
 }
 
 #[test]
-#[should_panic]
 fn list_doesnt_exist_iter_test() {
     set_test_rsc!("list_doesnt_exist_iter_test.db");
 
     // create a db with auto_dump == true
-    let mut db = PickleDb::new("list_doesnt_exist_iter_test.db", PickleDbDumpPolicy::AutoDump);
+    let mut db = PickleDb::new("list_doesnt_exist_iter_test.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
 
     let values = (1, 1.1, String::from("value"), vec![1,2,3], ('a', 'b', 'c'));
 
     // create a list with some values
-    db.lcreate("list1")
-      .ladd(&values.0)
-      .ladd(&values.1)
-      .ladd(&values.2)
-      .ladd(&values.3)
-      .ladd(&values.4);
+    db.lcreate("list1").unwrap()
+      .ladd(&values.0).unwrap()
+      .ladd(&values.1).unwrap()
+      .ladd(&values.2).unwrap()
+      .ladd(&values.3).unwrap()
+      .ladd(&values.4).unwrap();
+
+    // iterate over a non-existent list - should return an error
+    assert!(matches!(db.liter("list2"), Err(PickleDbError::KeyNotFound(ref name)) if name == "list2"));
+}
 
-    // iterate over a non-existent list - should panic here
-    for _item in db.liter("list2") {
+#[test]
+fn push_and_insert() {
+    set_test_rsc!("push_and_insert.db");
+
+    let mut db = PickleDb::new("push_and_insert.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.lcreate("list1").unwrap().lextend(&[3, 5]).unwrap();
+
+    // push to the front
+    assert_eq!(db.lpush("list1", &2).unwrap(), 3);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![2, 3, 5]);
+
+    // insert at an index, a negative index and indexes out of bounds
+    assert_eq!(db.linsert("list1", 2, &4).unwrap(), 4);
+    assert_eq!(db.linsert("list1", -1, &45).unwrap(), 5);
+    assert_eq!(db.linsert("list1", -100, &1).unwrap(), 6);
+    assert_eq!(db.linsert("list1", 100, &6).unwrap(), 7);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![1, 2, 3, 4, 45, 5, 6]);
+
+    // insert before and after an item
+    assert!(db.linsert_before("list1", &1, &0).unwrap());
+    assert!(db.linsert_after("list1", &6, &7).unwrap());
+    assert!(db.linsert_after("list1", &4, &String::from("four")).unwrap());
+    assert!(!db.linsert_before("list1", &100, &0).unwrap());
+    assert_eq!(db.llen("list1"), 10);
+    assert_eq!(db.lget::<String>("list1", 5).unwrap(), "four");
+    assert_eq!(db.lindex::<i32>("list1", 0).unwrap(), 0);
+    assert_eq!(db.lindex::<i32>("list1", -1).unwrap(), 7);
+
+    // all changes are dumped
+    let read_db = PickleDb::load_read_only("push_and_insert.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.llen("list1"), 10);
+    assert_eq!(read_db.lindex::<i32>("list1", -1).unwrap(), 7);
+
+    // lists that don't exist
+    assert!(matches!(db.lpush("list2", &1), Err(PickleDbError::KeyNotFound(_))));
+    assert!(matches!(db.linsert("list2", 0, &1), Err(PickleDbError::KeyNotFound(_))));
+    assert!(matches!(db.linsert_after("list2", &1, &1), Err(PickleDbError::KeyNotFound(_))));
+    db.set("key1", &1).unwrap();
+    assert!(matches!(db.lpush("key1", &1), Err(PickleDbError::WrongType(_))));
+}
 
-    }
-}
\ No newline at end of file
+#[test]
+fn lset_and_lindex() {
+    set_test_rsc!("lset_and_lindex.db");
+
+    let mut db = PickleDb::new("lset_and_lindex.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin);
+    db.lcreate("list1").unwrap().lextend(&[1, 2, 3]).unwrap();
+
+    assert!(db.lset("list1", 0, &10).unwrap());
+    assert!(db.lset("list1", -1, &30).unwrap());
+    assert!(!db.lset("list1", 3, &40).unwrap());
+    assert!(!db.lset("list1", -4, &40).unwrap());
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![10, 2, 30]);
+
+    assert_eq!(db.lindex::<i32>("list1", 1).unwrap(), 2);
+    assert_eq!(db.lindex::<i32>("list1", -3).unwrap(), 10);
+    assert!(db.lindex::<i32>("list1", 3).is_none());
+    assert!(db.lindex::<i32>("list1", -4).is_none());
+    assert!(db.lindex::<i32>("list2", 0).is_none());
+    assert!(matches!(db.lset("list2", 0, &1), Err(PickleDbError::KeyNotFound(_))));
+
+    let read_db = PickleDb::load_read_only("lset_and_lindex.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.lindex::<i32>("list1", -1).unwrap(), 30);
+}
+
+#[test]
+fn lrange_and_ltrim() {
+    set_test_rsc!("lrange_and_ltrim.db");
+
+    let mut db = PickleDb::new("lrange_and_ltrim.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.lcreate("list1").unwrap().lextend(&[0, 1, 2, 3, 4, 5]).unwrap();
+
+    assert_eq!(db.lrange::<i32>("list1", 1, 3).unwrap(), vec![1, 2, 3]);
+    assert_eq!(db.lrange::<i32>("list1", -2, -1).unwrap(), vec![4, 5]);
+    assert_eq!(db.lrange::<i32>("list1", -100, 1).unwrap(), vec![0, 1]);
+    assert_eq!(db.lrange::<i32>("list1", 4, 100).unwrap(), vec![4, 5]);
+    assert!(db.lrange::<i32>("list1", 3, 2).unwrap().is_empty());
+    assert!(db.lrange::<i32>("list1", 6, 10).unwrap().is_empty());
+    assert!(db.lrange::<i32>("list1", 0, -7).unwrap().is_empty());
+    assert!(db.lrange::<i32>("list2", 0, -1).unwrap().is_empty());
+
+    // items of another type
+    db.ladd("list1", &String::from("str")).unwrap();
+    assert!(matches!(db.lrange::<i32>("list1", 0, -1), Err(PickleDbError::Deserialization { .. })));
+    db.set("key1", &1).unwrap();
+    assert!(matches!(db.lrange::<i32>("key1", 0, -1), Err(PickleDbError::WrongType(_))));
+
+    // trim the list
+    assert_eq!(db.ltrim("list1", 1, -2).unwrap(), 2);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(db.ltrim("list1", 0, 100).unwrap(), 0);
+    assert_eq!(db.ltrim("list1", -2, -1).unwrap(), 3);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![4, 5]);
+    let read_db = PickleDb::load_read_only("lrange_and_ltrim.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.lrange::<i32>("list1", 0, -1).unwrap(), vec![4, 5]);
+
+    // trimming to an empty range empties the list but keeps it
+    assert_eq!(db.ltrim("list1", 1, 0).unwrap(), 2);
+    assert!(db.lexists("list1"));
+    assert_eq!(db.llen("list1"), 0);
+    assert!(matches!(db.ltrim("list2", 0, 1), Err(PickleDbError::KeyNotFound(_))));
+}
+
+#[test]
+fn remove_all_values_from_list() {
+    set_test_rsc!("remove_all_values_from_list.db");
+
+    let mut db = PickleDb::new("remove_all_values_from_list.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    let items = [1, 2, 1, 3, 1, 4, 1];
+
+    // remove from the front
+    db.lcreate("list1").unwrap().lextend(&items).unwrap();
+    assert_eq!(db.lrem_value_all("list1", &1, 2).unwrap(), 2);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![2, 3, 1, 4, 1]);
+
+    // remove from the back
+    db.lcreate("list1").unwrap().lextend(&items).unwrap();
+    assert_eq!(db.lrem_value_all("list1", &1, -3).unwrap(), 3);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![1, 2, 3, 4]);
+
+    // remove all
+    db.lcreate("list1").unwrap().lextend(&items).unwrap();
+    assert_eq!(db.lrem_value_all("list1", &1, 0).unwrap(), 4);
+    assert_eq!(db.lrange::<i32>("list1", 0, -1).unwrap(), vec![2, 3, 4]);
+    assert_eq!(db.lrem_value_all("list1", &1, 0).unwrap(), 0);
+    assert_eq!(db.lrem_value_all("list1", &2, 100).unwrap(), 1);
+
+    let read_db = PickleDb::load_read_only("remove_all_values_from_list.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.lrange::<i32>("list1", 0, -1).unwrap(), vec![3, 4]);
+    assert!(matches!(db.lrem_value_all("list2", &1, 0), Err(PickleDbError::KeyNotFound(_))));
+}
+
+#[test]
+fn move_between_lists() {
+    set_test_rsc!("move_between_lists.db");
+
+    let mut db = PickleDb::new("move_between_lists.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.lcreate("queue").unwrap().lextend(&[1, 2, 3]).unwrap();
+    db.lcreate("in_progress").unwrap();
+
+    assert_eq!(db.lmove::<i32>("queue", "in_progress", ListEnd::Front, ListEnd::Back).unwrap(), Some(1));
+    assert_eq!(db.lmove::<i32>("queue", "in_progress", ListEnd::Back, ListEnd::Front).unwrap(), Some(3));
+    assert_eq!(db.lrange::<i32>("queue", 0, -1).unwrap(), vec![2]);
+    assert_eq!(db.lrange::<i32>("in_progress", 0, -1).unwrap(), vec![3, 1]);
+
+    // both lists are dumped
+    let read_db = PickleDb::load_read_only("move_between_lists.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.lrange::<i32>("queue", 0, -1).unwrap(), vec![2]);
+    assert_eq!(read_db.lrange::<i32>("in_progress", 0, -1).unwrap(), vec![3, 1]);
+
+    // rotate a list
+    assert_eq!(db.lmove::<i32>("in_progress", "in_progress", ListEnd::Back, ListEnd::Front).unwrap(), Some(1));
+    assert_eq!(db.lrange::<i32>("in_progress", 0, -1).unwrap(), vec![1, 3]);
+
+    // an item of another type isn't moved
+    assert!(matches!(db.lmove::<String>("queue", "in_progress", ListEnd::Front, ListEnd::Back),
+                     Err(PickleDbError::Deserialization { .. })));
+    assert_eq!(db.llen("queue"), 1);
+
+    // an empty source list
+    assert_eq!(db.lmove::<i32>("queue", "in_progress", ListEnd::Front, ListEnd::Back).unwrap(), Some(2));
+    assert_eq!(db.lmove::<i32>("queue", "in_progress", ListEnd::Front, ListEnd::Back).unwrap(), None);
+    assert_eq!(db.llen("in_progress"), 3);
+
+    // lists that don't exist
+    assert!(matches!(db.lmove::<i32>("queue", "other", ListEnd::Front, ListEnd::Back), Err(PickleDbError::KeyNotFound(_))));
+    assert!(matches!(db.lmove::<i32>("other", "queue", ListEnd::Front, ListEnd::Back), Err(PickleDbError::KeyNotFound(_))));
+}
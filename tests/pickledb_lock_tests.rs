@@ -0,0 +1,120 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, PickleDbLockPolicy, SerializationMethod};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod common;
+
+fn load_locked(name: &str, dump_policy: PickleDbDumpPolicy, lock_policy: PickleDbLockPolicy) -> Result<PickleDb, PickleDbError> {
+    PickleDb::load_locked(name, dump_policy, SerializationMethod::Json, lock_policy)
+}
+
+#[test]
+fn lock_exclusive_writer() {
+    set_test_rsc!("lock_exclusive_writer.db");
+
+    let mut db = PickleDb::new_locked("lock_exclusive_writer.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json,
+                                      PickleDbLockPolicy::NoWait).unwrap();
+    db.set("key1", &1).unwrap();
+
+    // no other DB can lock the file while the writer holds it
+    assert!(matches!(load_locked("lock_exclusive_writer.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Locked)));
+    assert!(matches!(load_locked("lock_exclusive_writer.db", PickleDbDumpPolicy::NeverDump, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Locked)));
+    assert!(matches!(PickleDb::new_locked("lock_exclusive_writer.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json,
+                                          PickleDbLockPolicy::NoWait), Err(PickleDbError::Locked)));
+
+    // the lock is advisory, so a DB that doesn't lock the file can still load it
+    assert_eq!(PickleDb::load_read_only("lock_exclusive_writer.db", SerializationMethod::Json).unwrap().get::<i32>("key1"), Some(1));
+
+    // the lock is released when the DB is dropped
+    drop(db);
+    let db = load_locked("lock_exclusive_writer.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::NoWait).unwrap();
+    assert_eq!(db.get::<i32>("key1"), Some(1));
+}
+
+#[test]
+fn lock_shared_readers() {
+    set_test_rsc!("lock_shared_readers.db");
+
+    let mut db = PickleDb::new("lock_shared_readers.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    drop(db);
+
+    // several readers can lock the file at the same time
+    let reader1 = load_locked("lock_shared_readers.db", PickleDbDumpPolicy::NeverDump, PickleDbLockPolicy::NoWait).unwrap();
+    let reader2 = load_locked("lock_shared_readers.db", PickleDbDumpPolicy::NeverDump, PickleDbLockPolicy::NoWait).unwrap();
+    assert_eq!(reader2.get::<i32>("key1"), Some(1));
+
+    // but a writer can't while they hold it
+    assert!(matches!(load_locked("lock_shared_readers.db", PickleDbDumpPolicy::DumpUponRequest, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Locked)));
+    drop(reader1);
+    assert!(matches!(load_locked("lock_shared_readers.db", PickleDbDumpPolicy::DumpUponRequest, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Locked)));
+    drop(reader2);
+    assert!(load_locked("lock_shared_readers.db", PickleDbDumpPolicy::DumpUponRequest, PickleDbLockPolicy::NoWait).is_ok());
+}
+
+#[test]
+fn lock_wait_with_timeout() {
+    set_test_rsc!("lock_wait_with_timeout.db");
+
+    let mut db = PickleDb::new_locked("lock_wait_with_timeout.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json,
+                                      PickleDbLockPolicy::NoWait).unwrap();
+    db.set("key1", &1).unwrap();
+
+    // waiting fails if the lock isn't released in time
+    let start = Instant::now();
+    assert!(matches!(load_locked("lock_wait_with_timeout.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::Wait(Duration::from_millis(50))),
+                     Err(PickleDbError::Locked)));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    // and succeeds once the lock is released, after the DB is dumped upon drop
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        drop(db);
+    });
+    let db = load_locked("lock_wait_with_timeout.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::Wait(Duration::from_secs(10))).unwrap();
+    assert_eq!(db.get::<i32>("key1"), Some(1));
+    writer.join().unwrap();
+}
+
+#[test]
+fn lock_missing_file() {
+    set_test_rsc!("lock_missing_file.db");
+
+    // a DB file that doesn't exist can't be loaded, and the lock isn't held after the failure
+    assert!(matches!(load_locked("lock_missing_file.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Io(_))));
+    assert!(PickleDb::new_locked("lock_missing_file.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json,
+                                 PickleDbLockPolicy::NoWait).is_ok());
+}
+
+#[test]
+fn lock_read_only() {
+    set_test_rsc!("lock_read_only.db");
+
+    let mut db = PickleDb::new("lock_read_only.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+
+    // DBs that don't lock the file can be used along with each other
+    let reader = PickleDb::load_read_only("lock_read_only.db", SerializationMethod::Json).unwrap();
+    assert_eq!(reader.get::<i32>("key1"), Some(1));
+    drop(reader);
+    drop(db);
+
+    // read-only DBs take a shared lock, which keeps writers out
+    let reader1 = PickleDb::load_read_only_locked("lock_read_only.db", SerializationMethod::Json, PickleDbLockPolicy::NoWait).unwrap();
+    let reader2 = PickleDb::load_read_only_locked("lock_read_only.db", SerializationMethod::Json, PickleDbLockPolicy::NoWait).unwrap();
+    assert_eq!(reader2.get::<i32>("key1"), Some(1));
+    assert!(matches!(load_locked("lock_read_only.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Locked)));
+    drop(reader1);
+    drop(reader2);
+
+    // and can't take it while a writer holds it
+    let _writer = load_locked("lock_read_only.db", PickleDbDumpPolicy::AutoDump, PickleDbLockPolicy::NoWait).unwrap();
+    assert!(matches!(PickleDb::load_read_only_locked("lock_read_only.db", SerializationMethod::Json, PickleDbLockPolicy::NoWait),
+                     Err(PickleDbError::Locked)));
+}
@@ -0,0 +1,112 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod, SharedPickleDb};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+fn fill_db(db: &mut PickleDb) {
+    db.set("user:2", &2).unwrap();
+    db.set("session:b", &String::from("b")).unwrap();
+    db.lcreate("user:1").unwrap().ladd(&1).unwrap();
+    db.set("user:3", &3).unwrap();
+    db.dcreate("session:a").unwrap();
+    db.set("user", &0).unwrap();
+    db.set("userx", &0).unwrap();
+}
+
+#[test]
+fn ordered_get_all_and_iter() {
+    set_test_rsc!("ordered_get_all_and_iter.db");
+
+    let mut db = PickleDb::new("ordered_get_all_and_iter.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)
+        .with_ordered_keys();
+    fill_db(&mut db);
+
+    // values, lists and dicts are returned in order
+    assert_eq!(db.get_all(), vec!["session:a", "session:b", "user", "user:1", "user:2", "user:3", "userx"]);
+
+    // values are iterated in order
+    let keys: Vec<String> = db.iter().map(|kv| kv.get_key().to_string()).collect();
+    assert_eq!(keys, vec!["session:b", "user", "user:2", "user:3", "userx"]);
+
+    // keys stay ordered after they're changed
+    db.rem("user").unwrap();
+    db.set("a", &1).unwrap();
+    db.lcreate("session:b").unwrap();
+    assert_eq!(db.get_all(), vec!["a", "session:a", "session:b", "user:1", "user:2", "user:3", "userx"]);
+}
+
+#[test]
+fn range_and_scan_prefix() {
+    set_test_rsc!("range_and_scan_prefix.db");
+
+    // the queries return the same results with and without ordered keys
+    for ordered in &[false, true] {
+        let mut db = PickleDb::new("range_and_scan_prefix.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+        if *ordered {
+            db = db.with_ordered_keys();
+        }
+        fill_db(&mut db);
+
+        assert_eq!(db.scan_prefix("user:"), vec!["user:1", "user:2", "user:3"]);
+        assert_eq!(db.scan_prefix("session:"), vec!["session:a", "session:b"]);
+        assert_eq!(db.scan_prefix("user"), vec!["user", "user:1", "user:2", "user:3", "userx"]);
+        assert!(db.scan_prefix("other").is_empty());
+        assert_eq!(db.scan_prefix("").len(), 7);
+
+        assert_eq!(db.range("user:1".."user:3"), vec!["user:1", "user:2"]);
+        assert_eq!(db.range("user:1"..="user:3"), vec!["user:1", "user:2", "user:3"]);
+        assert_eq!(db.range(.."user"), vec!["session:a", "session:b"]);
+        assert_eq!(db.range("user:3"..), vec!["user:3", "userx"]);
+        assert_eq!(db.range(..).len(), 7);
+
+        // empty and reversed ranges
+        assert!(db.range("user:1".."user:1").is_empty());
+        assert!(db.range("user:3".."user:1").is_empty());
+    }
+}
+
+#[test]
+fn ordered_keys_skip_expired() {
+    set_test_rsc!("ordered_keys_skip_expired.db");
+
+    let mut db = PickleDb::new("ordered_keys_skip_expired.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)
+        .with_ordered_keys();
+    db.set("key:1", &1).unwrap();
+    db.set_with_ttl("key:2", &2, Duration::from_millis(100)).unwrap();
+    db.set("key:3", &3).unwrap();
+    assert_eq!(db.scan_prefix("key:").len(), 3);
+
+    thread::sleep(Duration::from_millis(150));
+    assert_eq!(db.scan_prefix("key:"), vec!["key:1", "key:3"]);
+    assert_eq!(db.range("key:1"..="key:3"), vec!["key:1", "key:3"]);
+    assert_eq!(db.get_all(), vec!["key:1", "key:3"]);
+}
+
+#[test]
+fn ordered_keys_load_and_rollback() {
+    set_test_rsc!("ordered_keys_load_and_rollback.db");
+
+    {
+        let mut db = PickleDb::new("ordered_keys_load_and_rollback.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin);
+        fill_db(&mut db);
+    }
+
+    // a loaded DB can be ordered too
+    let mut db = PickleDb::load("ordered_keys_load_and_rollback.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin)
+        .unwrap()
+        .with_ordered_keys();
+    assert_eq!(db.get_all(), vec!["session:a", "session:b", "user", "user:1", "user:2", "user:3", "userx"]);
+
+    // the keys stay ordered after a transaction is rolled back
+    db.begin().unwrap();
+    db.set("user:0", &0).unwrap();
+    db.rollback().unwrap();
+    db.set("a", &0).unwrap();
+    assert_eq!(db.get_all(), vec!["a", "session:a", "session:b", "user", "user:1", "user:2", "user:3", "userx"]);
+
+    // and through a shared handle
+    let shared = SharedPickleDb::new(db);
+    assert_eq!(shared.scan_prefix("session:"), vec!["session:a", "session:b"]);
+    assert_eq!(shared.range("user:2"..), vec!["user:2", "user:3", "userx"]);
+}
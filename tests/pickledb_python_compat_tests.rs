@@ -0,0 +1,214 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+
+mod common;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Coor {
+    x: i32,
+    y: i32,
+}
+
+/// A struct whose fields aren't declared in alphabetical order
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Point3 {
+    z: i32,
+    y: i32,
+    x: i32,
+}
+
+fn read_json(file: &str) -> Value {
+    serde_json::from_slice(&fs::read(file).unwrap()).unwrap()
+}
+
+#[test]
+fn load_python_file() {
+    set_test_rsc!("load_python_file.db");
+
+    // a file as it's written by Python's pickleDB
+    fs::write("load_python_file.db",
+              r#"{"key1": 100, "key2": "hello", "key3": null, "list1": [1, "two", {"x": 3, "y": 4}], "dict1": {"a": 1.5, "b": [1, 2]}}"#)
+        .unwrap();
+
+    let db = PickleDb::load_python_compat("load_python_file.db", PickleDbDumpPolicy::NeverDump).unwrap();
+    assert_eq!(db.total_keys(), 5);
+
+    // scalars are loaded as values
+    assert_eq!(db.get::<i32>("key1").unwrap(), 100);
+    assert_eq!(db.get::<String>("key2").unwrap(), "hello");
+    assert_eq!(db.get::<Option<i32>>("key3").unwrap(), None);
+
+    // arrays are loaded as lists
+    assert!(db.lexists("list1"));
+    assert_eq!(db.llen("list1"), 3);
+    assert_eq!(db.lget::<i32>("list1", 0).unwrap(), 1);
+    assert_eq!(db.lget::<String>("list1", 1).unwrap(), "two");
+    assert_eq!(db.lget::<Coor>("list1", 2).unwrap(), Coor { x: 3, y: 4 });
+
+    // objects are loaded as dicts
+    assert_eq!(db.dlen("dict1"), 2);
+    assert_eq!(db.dget::<f64>("dict1", "a").unwrap(), 1.5);
+    assert_eq!(db.dget::<Vec<i32>>("dict1", "b").unwrap(), vec![1, 2]);
+}
+
+#[test]
+fn dump_python_file() {
+    set_test_rsc!("dump_python_file.db");
+
+    let mut db = PickleDb::new_python_compat("dump_python_file.db", PickleDbDumpPolicy::AutoDump);
+    db.set("key1", &100).unwrap();
+    db.set("key2", &String::from("two")).unwrap();
+    db.lcreate("list1").unwrap().lextend(&[1, 2]).unwrap().ladd(&Coor { x: 3, y: 4 }).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "a", &true).unwrap();
+    db.sadd("set1", &2).unwrap();
+    db.sadd("set1", &1).unwrap();
+    db.zadd("zset1", 2.0, &String::from("b")).unwrap();
+    db.zadd("zset1", 1.0, &String::from("a")).unwrap();
+
+    // values are written as nested JSON, not as strings, sets are written as objects holding their sorted members
+    // and sorted sets as objects holding their member and score pairs
+    assert_eq!(read_json("dump_python_file.db"), json!({
+        "key1": 100,
+        "key2": "two",
+        "list1": [1, 2, {"x": 3, "y": 4}],
+        "dict1": {"a": true},
+        "set1": {"$set": [1, 2]},
+        "zset1": {"$sorted_set": [["a", 1.0], ["b", 2.0]]},
+    }));
+
+    // removing keys removes them from the file
+    db.rem("key1").unwrap();
+    db.lrem_list("list1").unwrap();
+    db.rem("set1").unwrap();
+    db.rem("zset1").unwrap();
+    assert_eq!(read_json("dump_python_file.db"), json!({"key2": "two", "dict1": {"a": true}}));
+}
+
+#[test]
+fn python_file_round_trip() {
+    set_test_rsc!("python_file_round_trip.db");
+
+    fs::write("python_file_round_trip.db", r#"{"key1": 1, "list1": ["a"]}"#).unwrap();
+
+    {
+        let mut db = PickleDb::load_python_compat("python_file_round_trip.db", PickleDbDumpPolicy::DumpUponRequest).unwrap();
+
+        // a DB that wasn't changed isn't rewritten
+        db.dump().unwrap();
+        assert_eq!(fs::read_to_string("python_file_round_trip.db").unwrap(), r#"{"key1": 1, "list1": ["a"]}"#);
+
+        db.lcreate("key2").unwrap().lextend(&[1, 2]).unwrap();
+        db.ladd("list1", &String::from("b")).unwrap();
+
+        // the expiry time isn't written to the file
+        db.set_with_ttl("key3", &3, std::time::Duration::from_secs(60)).unwrap();
+        db.dump().unwrap();
+    }
+
+    assert_eq!(read_json("python_file_round_trip.db"), json!({"key1": 1, "key2": [1, 2], "key3": 3, "list1": ["a", "b"]}));
+
+    let db = PickleDb::load_python_compat("python_file_round_trip.db", PickleDbDumpPolicy::NeverDump).unwrap();
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert_eq!(db.lget::<i32>("key2", 1).unwrap(), 2);
+    assert_eq!(db.ttl("key3").unwrap(), None);
+    assert_eq!(db.lget::<String>("list1", 1).unwrap(), "b");
+}
+
+#[test]
+fn python_file_sets_round_trip() {
+    set_test_rsc!("python_file_sets_round_trip.db");
+
+    {
+        let mut db = PickleDb::new_python_compat("python_file_sets_round_trip.db", PickleDbDumpPolicy::AutoDump);
+        db.sadd("set1", &Coor { x: 1, y: 2 }).unwrap();
+        db.sadd("set1", &String::from("a")).unwrap();
+        db.sadd("set2", &Point3 { z: 3, y: 2, x: 1 }).unwrap();
+        db.zadd("zset1", 2.5, &String::from("b")).unwrap();
+        db.zadd("zset1", 1.0, &Coor { x: 3, y: 4 }).unwrap();
+        db.dcreate("dict1").unwrap();
+        db.dadd("dict1", "$set", &1).unwrap();
+    }
+
+    // sets and sorted sets keep their types, and a dict with a key like the one of a set is still a dict
+    let mut db = PickleDb::load_python_compat("python_file_sets_round_trip.db", PickleDbDumpPolicy::AutoDump).unwrap();
+    assert_eq!(db.scard("set1"), 2);
+    assert!(db.sismember("set1", &Coor { x: 1, y: 2 }));
+    assert!(!db.lexists("set1"));
+    assert!(db.sismember("set2", &Point3 { z: 3, y: 2, x: 1 }));
+    assert_eq!(db.zcard("zset1"), 2);
+    assert_eq!(db.zscore("zset1", &Coor { x: 3, y: 4 }), Some(1.0));
+    assert_eq!(db.dget::<i32>("dict1", "$set"), Some(1));
+
+    // a dict that would be read back as a set can't be written
+    db.dadd("dict1", "$set", &vec![1, 2]).unwrap_err();
+    assert!(matches!(db.dump(), Err(PickleDbError::Serialization(_))));
+    db.dadd("dict1", "key2", &2).unwrap();
+    db.dump().unwrap();
+}
+
+#[test]
+fn python_file_nested_values() {
+    set_test_rsc!("python_file_nested_values.db");
+
+    let mut db = PickleDb::new_python_compat("python_file_nested_values.db", PickleDbDumpPolicy::DumpUponRequest);
+    db.set("key1", &1).unwrap();
+    db.dump().unwrap();
+
+    // values that are JSON arrays or objects would be read back as lists or dicts, so they can't be written
+    db.set("vec", &vec![1, 2, 3]).unwrap();
+    assert!(matches!(db.dump(), Err(PickleDbError::Serialization(_))));
+    db.rem("vec").unwrap();
+    let mut map = HashMap::new();
+    map.insert(String::from("a"), 1);
+    db.set("map", &map).unwrap();
+    assert!(matches!(db.dump(), Err(PickleDbError::Serialization(_))));
+    db.rem("map").unwrap();
+    db.set("coor", &Coor { x: 1, y: 2 }).unwrap();
+    assert!(matches!(db.dump(), Err(PickleDbError::Serialization(_))));
+    db.rem("coor").unwrap();
+    assert_eq!(read_json("python_file_nested_values.db"), json!({"key1": 1}));
+
+    // but they can be nested in lists and dicts, and they're read back the same
+    db.lcreate("list1").unwrap().ladd(&vec![1, 2, 3]).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "map", &map).unwrap();
+    db.dump().unwrap();
+    let db = PickleDb::load_python_compat("python_file_nested_values.db", PickleDbDumpPolicy::NeverDump).unwrap();
+    assert_eq!(db.lget::<Vec<i32>>("list1", 0).unwrap(), vec![1, 2, 3]);
+    assert_eq!(db.dget::<HashMap<String, i32>>("dict1", "map").unwrap(), map);
+}
+
+#[test]
+fn load_invalid_python_file() {
+    set_test_rsc!("load_invalid_python_file.db");
+
+    // the file must hold a single JSON object
+    fs::write("load_invalid_python_file.db", "[1, 2, 3]").unwrap();
+    assert!(matches!(PickleDb::load_python_compat("load_invalid_python_file.db", PickleDbDumpPolicy::NeverDump),
+                     Err(PickleDbError::Corrupted(_))));
+
+    // members of a sorted set must be member and score pairs
+    fs::write("load_invalid_python_file.db", r#"{"zset1": {"$sorted_set": [["a", "1.0"]]}}"#).unwrap();
+    assert!(matches!(PickleDb::load_python_compat("load_invalid_python_file.db", PickleDbDumpPolicy::NeverDump),
+                     Err(PickleDbError::Corrupted(_))));
+
+    // a file in Python's layout can only be loaded in Python compatible mode
+    fs::write("load_invalid_python_file.db", r#"{"key1": 1}"#).unwrap();
+    assert!(matches!(PickleDb::load("load_invalid_python_file.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json),
+                     Err(PickleDbError::Corrupted(_))));
+
+    // and vice versa
+    {
+        let mut db = PickleDb::new("load_invalid_python_file.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+        db.set("key1", &1).unwrap();
+    }
+    assert!(matches!(PickleDb::load_python_compat("load_invalid_python_file.db", PickleDbDumpPolicy::NeverDump),
+                     Err(PickleDbError::Corrupted(_))));
+
+    assert!(matches!(PickleDb::load_python_compat("no_such_python_file.db", PickleDbDumpPolicy::NeverDump),
+                     Err(PickleDbError::Io(_))));
+}
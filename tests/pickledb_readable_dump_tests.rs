@@ -0,0 +1,241 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::time::Duration;
+
+mod common;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Coor {
+    x: i32,
+    y: i32,
+}
+
+/// A struct whose fields aren't declared in alphabetical order
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Point3 {
+    z: i32,
+    y: i32,
+    x: i32,
+}
+
+fn fill_db(db: &mut PickleDb) {
+    db.set("key2", &Coor { x: 1, y: 2 }).unwrap();
+    db.set("key1", &String::from("hello")).unwrap();
+    db.lcreate("list1").unwrap().lextend(&[1, 2]).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "b", &true).unwrap();
+    db.dadd("dict1", "a", &1).unwrap();
+    db.sadd("set1", &2).unwrap();
+    db.sadd("set1", &1).unwrap();
+    db.zadd("zset1", 2.5, &String::from("b")).unwrap();
+    db.zadd("zset1", 1.0, &String::from("a")).unwrap();
+}
+
+#[test]
+fn readable_dump_layout() {
+    set_test_rsc!("readable_dump_layout.db");
+
+    let mut db = PickleDb::new("readable_dump_layout.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_readable_dump(false);
+    fill_db(&mut db);
+
+    // keys are sorted, each entry is on its own line and values are nested JSON
+    assert_eq!(fs::read_to_string("readable_dump_layout.db").unwrap(), r#"{
+  "values": {
+    "key1": "hello",
+    "key2": {"x":1,"y":2}
+  },
+  "lists": {
+    "list1": [1,2]
+  },
+  "dicts": {
+    "dict1": {"a":1,"b":true}
+  },
+  "sets": {
+    "set1": [1,2]
+  },
+  "sorted_sets": {
+    "zset1": [["a",1.0],["b",2.5]]
+  },
+  "expiry": {}
+}
+"#);
+
+    // dumping the same data again produces the same file
+    db.rem("key1").unwrap();
+    db.set("key1", &String::from("hello")).unwrap();
+    let contents = fs::read_to_string("readable_dump_layout.db").unwrap();
+    for _ in 0..5 {
+        db.set("key3", &3).unwrap();
+        db.rem("key3").unwrap();
+        assert_eq!(fs::read_to_string("readable_dump_layout.db").unwrap(), contents);
+    }
+
+    // an empty DB
+    let mut db = PickleDb::new("readable_dump_layout.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)
+        .with_readable_dump(false);
+    db.dump().unwrap();
+    assert_eq!(fs::read_to_string("readable_dump_layout.db").unwrap(),
+               "{\n  \"values\": {},\n  \"lists\": {},\n  \"dicts\": {},\n  \"sets\": {},\n  \"sorted_sets\": {},\n  \"expiry\": {}\n}\n");
+}
+
+#[test]
+fn readable_dump_pretty_values() {
+    set_test_rsc!("readable_dump_pretty_values.db");
+
+    let mut db = PickleDb::new("readable_dump_pretty_values.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+        .with_readable_dump(true);
+    db.set("key1", &Coor { x: 1, y: 2 }).unwrap();
+
+    assert_eq!(fs::read_to_string("readable_dump_pretty_values.db").unwrap(), r#"{
+  "values": {
+    "key1": {
+      "x": 1,
+      "y": 2
+    }
+  },
+  "lists": {},
+  "dicts": {},
+  "sets": {},
+  "sorted_sets": {},
+  "expiry": {}
+}
+"#);
+}
+
+#[test]
+fn readable_dump_load() {
+    set_test_rsc!("readable_dump_load.db");
+
+    for pretty_values in &[false, true] {
+        {
+            let mut db = PickleDb::new("readable_dump_load.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+                .with_readable_dump(*pretty_values);
+            fill_db(&mut db);
+            db.set_with_ttl("key3", &3, Duration::from_secs(60)).unwrap();
+        }
+
+        // a readable file is loaded like any other JSON DB file
+        let db = PickleDb::load_read_only("readable_dump_load.db", SerializationMethod::Json).unwrap();
+        assert_eq!(db.total_keys(), 7);
+        assert_eq!(db.get::<String>("key1").unwrap(), "hello");
+        assert_eq!(db.get::<Coor>("key2").unwrap(), Coor { x: 1, y: 2 });
+        assert!(db.ttl("key3").unwrap().is_some());
+        assert_eq!(db.lget::<i32>("list1", 1).unwrap(), 2);
+        assert!(db.dget::<bool>("dict1", "b").unwrap());
+        assert!(db.sismember("set1", &2));
+        assert_eq!(db.zscore("zset1", &String::from("b")), Some(2.5));
+    }
+
+    // a file in the default layout can be loaded and dumped in the readable layout
+    {
+        let mut db = PickleDb::new("readable_dump_load.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+        db.set("key1", &1).unwrap();
+    }
+    {
+        let mut db = PickleDb::load("readable_dump_load.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+            .unwrap()
+            .with_readable_dump(false);
+        db.set("key2", &2).unwrap();
+    }
+    assert!(fs::read_to_string("readable_dump_load.db").unwrap().starts_with("{\n  \"values\": {\n    \"key1\": 1,\n    \"key2\": 2\n  },"));
+    let db = PickleDb::load_read_only("readable_dump_load.db", SerializationMethod::Json).unwrap();
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert_eq!(db.get::<i32>("key2").unwrap(), 2);
+}
+
+#[test]
+fn readable_dump_keeps_field_order() {
+    set_test_rsc!("readable_dump_keeps_field_order.db");
+
+    let point = Point3 { z: 3, y: 2, x: 1 };
+    for pretty_values in &[false, true] {
+        {
+            let mut db = PickleDb::new("readable_dump_keeps_field_order.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json)
+                .with_readable_dump(*pretty_values);
+            db.set("key1", &point).unwrap();
+            db.sadd("set1", &point).unwrap();
+            db.lcreate("list1").unwrap().lextend(&[&point, &point]).unwrap();
+            db.zadd("zset1", 1.0, &point).unwrap();
+        }
+        if !*pretty_values {
+            assert!(fs::read_to_string("readable_dump_keeps_field_order.db").unwrap().contains(r#""key1": {"z":3,"y":2,"x":1}"#));
+        }
+
+        // values that are read back are found by the APIs that compare them
+        let mut db = PickleDb::load("readable_dump_keeps_field_order.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json).unwrap();
+        assert_eq!(db.get::<Point3>("key1").unwrap(), point);
+        assert!(db.sismember("set1", &point));
+        assert!(!db.sadd("set1", &point).unwrap());
+        assert!(db.linsert_before("list1", &point, &0).unwrap());
+        assert_eq!(db.lrem_value_all("list1", &point, 0).unwrap(), 2);
+        assert_eq!(db.zscore("zset1", &point), Some(1.0));
+        assert_eq!(db.zincrby("zset1", 1.0, &point).unwrap(), 2.0);
+        assert_eq!(db.zcard("zset1"), 1);
+        assert!(db.srem("set1", &point).unwrap());
+    }
+}
+
+#[test]
+fn readable_dump_large_numbers() {
+    set_test_rsc!("readable_dump_large_numbers.db");
+
+    let mut db = PickleDb::new("readable_dump_large_numbers.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)
+        .with_readable_dump(false);
+    db.set("key1", &u64::MAX).unwrap();
+    db.set("key2", &i64::MIN).unwrap();
+    db.dump().unwrap();
+
+    // a number that doesn't fit in 64 bits isn't written with less precision
+    db.set("key3", &i128::MAX).unwrap();
+    assert!(matches!(db.dump(), Err(PickleDbError::Serialization(_))));
+    db.rem("key3").unwrap();
+    db.sadd("set1", &u128::MAX).unwrap();
+    assert!(matches!(db.dump(), Err(PickleDbError::Serialization(_))));
+    db.srem("set1", &u128::MAX).unwrap();
+    db.dump().unwrap();
+
+    let db = PickleDb::load_read_only("readable_dump_large_numbers.db", SerializationMethod::Json).unwrap();
+    assert_eq!(db.get::<u64>("key1"), Some(u64::MAX));
+    assert_eq!(db.get::<i64>("key2"), Some(i64::MIN));
+}
+
+#[test]
+fn readable_dump_python_compat() {
+    set_test_rsc!("readable_dump_python_compat.db");
+
+    let mut db = PickleDb::new_python_compat("readable_dump_python_compat.db", PickleDbDumpPolicy::DumpUponRequest)
+        .with_readable_dump(false);
+    fill_db(&mut db);
+    // a struct would be read back as a dict in Python's layout, so it's stored as one
+    db.rem("key2").unwrap();
+    db.dcreate("key2").unwrap();
+    db.dadd("key2", "y", &2).unwrap();
+    db.dadd("key2", "x", &1).unwrap();
+    db.dump().unwrap();
+
+    assert_eq!(fs::read_to_string("readable_dump_python_compat.db").unwrap(), r#"{
+  "dict1": {"a":1,"b":true},
+  "key1": "hello",
+  "key2": {"x":1,"y":2},
+  "list1": [1,2],
+  "set1": {"$set":[1,2]},
+  "zset1": {"$sorted_set":[["a",1.0],["b",2.5]]}
+}
+"#);
+}
+
+#[test]
+fn readable_dump_other_serialization() {
+    set_test_rsc!("readable_dump_other_serialization.db");
+
+    // the option is ignored for other serialization methods
+    {
+        let mut db = PickleDb::new("readable_dump_other_serialization.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Yaml)
+            .with_readable_dump(false);
+        fill_db(&mut db);
+    }
+    let db = PickleDb::load_read_only("readable_dump_other_serialization.db", SerializationMethod::Yaml).unwrap();
+    assert_eq!(db.get::<Coor>("key2").unwrap(), Coor { x: 1, y: 2 });
+}
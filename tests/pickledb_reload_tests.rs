@@ -0,0 +1,171 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod, SharedPickleDb};
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod common;
+
+#[test]
+fn reload_read_only() {
+    set_test_rsc!("reload_read_only.db");
+
+    let mut db = PickleDb::new("reload_read_only.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.set("key2", &2).unwrap();
+
+    let mut read_db = PickleDb::load_read_only("reload_read_only.db", SerializationMethod::Json).unwrap();
+    db.set("key1", &100).unwrap();
+    db.rem("key2").unwrap();
+    db.sadd("set1", &1).unwrap();
+
+    // the changes aren't seen until the DB is reloaded
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+    read_db.reload().unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(100));
+    assert!(!read_db.exists("key2"));
+    assert!(read_db.sismember("set1", &1));
+}
+
+#[test]
+fn reload_if_changed() {
+    set_test_rsc!("reload_if_changed.db");
+
+    let mut db = PickleDb::new("reload_if_changed.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Bin);
+    db.set("key1", &1).unwrap();
+
+    let mut read_db = PickleDb::load_read_only("reload_if_changed.db", SerializationMethod::Bin).unwrap();
+    assert!(!read_db.reload_if_changed().unwrap());
+
+    // a change appended to the log
+    db.set("key1", &2).unwrap();
+    assert!(read_db.reload_if_changed().unwrap());
+    assert_eq!(read_db.get::<i32>("key1"), Some(2));
+    assert!(!read_db.reload_if_changed().unwrap());
+
+    // a compaction that replaces the DB file
+    db.set("key2", &3).unwrap();
+    db.compact().unwrap();
+    assert!(read_db.reload_if_changed().unwrap());
+    assert_eq!(read_db.get::<i32>("key2"), Some(3));
+    assert!(!read_db.reload_if_changed().unwrap());
+}
+
+#[test]
+fn reload_keeps_db_options() {
+    set_test_rsc!("reload_keeps_db_options.db");
+
+    let mut db = PickleDb::new_python_compat("reload_keeps_db_options.db", PickleDbDumpPolicy::AutoDump);
+    db.set("b", &1).unwrap();
+
+    let mut read_db = PickleDb::load_python_compat("reload_keeps_db_options.db", PickleDbDumpPolicy::NeverDump)
+        .unwrap()
+        .with_ordered_keys();
+    db.set("a", &2).unwrap();
+    db.lcreate("c").unwrap().ladd(&3).unwrap();
+
+    // the file is read in the layout of Python's PickleDB and the keys stay ordered
+    read_db.reload().unwrap();
+    assert_eq!(read_db.get_all(), vec!["a", "b", "c"]);
+    assert_eq!(read_db.lget::<i32>("c", 0), Some(3));
+}
+
+#[test]
+fn reload_failure_keeps_data() {
+    set_test_rsc!("reload_failure_keeps_data.db");
+
+    let mut db = PickleDb::new("reload_failure_keeps_data.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    let mut read_db = PickleDb::load_read_only("reload_failure_keeps_data.db", SerializationMethod::Json).unwrap();
+
+    fs::write("reload_failure_keeps_data.db", "not a DB").unwrap();
+    assert!(matches!(read_db.reload_if_changed(), Err(PickleDbError::Corrupted(_))));
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+
+    fs::remove_file("reload_failure_keeps_data.db").unwrap();
+    assert!(matches!(read_db.reload(), Err(PickleDbError::Io(_))));
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+
+    // a DB can't be reloaded in the middle of a transaction
+    db.begin().unwrap();
+    assert!(matches!(db.reload(), Err(PickleDbError::TransactionInProgress)));
+}
+
+#[test]
+fn reload_keeps_unsaved_changes() {
+    set_test_rsc!("reload_keeps_unsaved_changes.db");
+
+    let mut db = PickleDb::new("reload_keeps_unsaved_changes.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.dump().unwrap();
+
+    // changes that weren't dumped aren't discarded
+    db.set("key1", &2).unwrap();
+    assert!(db.is_dirty());
+    assert!(matches!(db.reload(), Err(PickleDbError::UnsavedChanges)));
+    assert_eq!(db.get::<i32>("key1"), Some(2));
+
+    db.dump().unwrap();
+    db.reload().unwrap();
+    assert_eq!(db.get::<i32>("key1"), Some(2));
+}
+
+#[test]
+fn reload_if_changed_after_own_dumps() {
+    set_test_rsc!("reload_if_changed_after_own_dumps.db");
+
+    {
+        let mut db = PickleDb::new("reload_if_changed_after_own_dumps.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+        db.set("key1", &1).unwrap();
+    }
+    let mut db = PickleDb::load("reload_if_changed_after_own_dumps.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json)
+        .unwrap();
+    db.set("key2", &2).unwrap();
+    db.dump().unwrap();
+
+    // the dumps of the DB itself aren't changes of the file
+    db.set("key3", &3).unwrap();
+    assert!(!db.reload_if_changed().unwrap());
+    assert_eq!(db.get::<i32>("key3"), Some(3));
+
+    // and neither are the changes it appends to the log
+    let mut db = PickleDb::load("reload_if_changed_after_own_dumps.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json)
+        .unwrap();
+    db.set("key4", &4).unwrap();
+    db.compact().unwrap();
+    db.set("key5", &5).unwrap();
+    assert!(!db.reload_if_changed().unwrap());
+
+    // but the changes of another DB are
+    let mut other_db = PickleDb::load("reload_if_changed_after_own_dumps.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json)
+        .unwrap();
+    other_db.set("key6", &6).unwrap();
+    assert!(db.reload_if_changed().unwrap());
+    assert_eq!(db.get::<i32>("key6"), Some(6));
+    assert_eq!(db.get::<i32>("key5"), Some(5));
+}
+
+#[test]
+fn shared_db_watcher() {
+    set_test_rsc!("shared_db_watcher.db");
+
+    let mut db = PickleDb::new("shared_db_watcher.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+
+    let read_db = SharedPickleDb::new(PickleDb::load_read_only("shared_db_watcher.db", SerializationMethod::Json).unwrap());
+    let watcher = read_db.start_watcher(Duration::from_millis(10));
+    db.set("key1", &2).unwrap();
+
+    let start = Instant::now();
+    while read_db.get::<i32>("key1") != Some(2) {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // no reloads after the watcher is stopped
+    watcher.stop();
+    db.set("key1", &3).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(read_db.get::<i32>("key1"), Some(2));
+    assert!(read_db.reload_if_changed().unwrap());
+    assert_eq!(read_db.get::<i32>("key1"), Some(3));
+}
@@ -0,0 +1,112 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
+
+mod common;
+
+#[macro_use]
+extern crate serde_derive;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Coor {
+    x: i32,
+    y: i32,
+}
+
+fn set_dump_load(file_name: &str, ser_method: SerializationMethod) {
+    let mut db = PickleDb::new(file_name, PickleDbDumpPolicy::DumpUponRequest, ser_method);
+
+    // set values of different types
+    db.set("num", &100).unwrap();
+    db.set("float", &1.5).unwrap();
+    db.set("string", &String::from("my string")).unwrap();
+    db.set("vec", &vec![1,2,3]).unwrap();
+    db.set("struct", &Coor { x: 1, y: 2 }).unwrap();
+
+    // create a heterogeneous list
+    db.lcreate("list1").unwrap()
+      .ladd(&200).unwrap()
+      .ladd(&String::from("item")).unwrap()
+      .ladd(&Coor { x: 3, y: 4 }).unwrap();
+
+    // create a heterogeneous dict
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "num", &300).unwrap();
+    db.dadd("dict1", "struct", &Coor { x: 5, y: 6 }).unwrap();
+
+    // create a heterogeneous set
+    db.sadd("set1", &400).unwrap();
+    db.sadd("set1", &Coor { x: 7, y: 8 }).unwrap();
+
+    // create a heterogeneous sorted set
+    db.zadd("zset1", 2.5, &500).unwrap();
+    db.zadd("zset1", -1.0, &Coor { x: 9, y: 10 }).unwrap();
+
+    // dump db to file
+    assert!(db.dump().is_ok());
+
+    // read db from file and verify all values and list items
+    let read_db = PickleDb::load_read_only(file_name, ser_method).unwrap();
+    assert_eq!(read_db.get::<i32>("num").unwrap(), 100);
+    assert_eq!(read_db.get::<f64>("float").unwrap(), 1.5);
+    assert_eq!(read_db.get::<String>("string").unwrap(), String::from("my string"));
+    assert_eq!(read_db.get::<Vec<i32>>("vec").unwrap(), vec![1,2,3]);
+    assert_eq!(read_db.get::<Coor>("struct").unwrap(), Coor { x: 1, y: 2 });
+    assert_eq!(read_db.llen("list1"), 3);
+    assert_eq!(read_db.lget::<i32>("list1", 0).unwrap(), 200);
+    assert_eq!(read_db.lget::<String>("list1", 1).unwrap(), String::from("item"));
+    assert_eq!(read_db.lget::<Coor>("list1", 2).unwrap(), Coor { x: 3, y: 4 });
+    assert_eq!(read_db.dget::<i32>("dict1", "num").unwrap(), 300);
+    assert_eq!(read_db.dget::<Coor>("dict1", "struct").unwrap(), Coor { x: 5, y: 6 });
+    assert_eq!(read_db.scard("set1"), 2);
+    assert!(read_db.sismember("set1", &400));
+    assert!(read_db.sismember("set1", &Coor { x: 7, y: 8 }));
+    assert_eq!(read_db.zcard("zset1"), 2);
+    assert_eq!(read_db.zscore("zset1", &500), Some(2.5));
+    assert_eq!(read_db.zrange_by_rank::<Coor>("zset1", 0, 0).unwrap(), vec![(Coor { x: 9, y: 10 }, -1.0)]);
+
+    // remove a list item by value, which compares the serialized form
+    let mut db = PickleDb::load(file_name, PickleDbDumpPolicy::DumpUponRequest, ser_method).unwrap();
+    assert!(db.lrem_value("list1", &String::from("item")).unwrap());
+    assert_eq!(db.llen("list1"), 2);
+}
+
+#[test]
+fn json_serialization() {
+    set_test_rsc!("json_serialization.db");
+    set_dump_load("json_serialization.db", SerializationMethod::Json);
+}
+
+#[test]
+fn bin_serialization() {
+    set_test_rsc!("bin_serialization.db");
+    set_dump_load("bin_serialization.db", SerializationMethod::Bin);
+}
+
+#[test]
+fn yaml_serialization() {
+    set_test_rsc!("yaml_serialization.db");
+    set_dump_load("yaml_serialization.db", SerializationMethod::Yaml);
+}
+
+#[test]
+fn cbor_serialization() {
+    set_test_rsc!("cbor_serialization.db");
+    set_dump_load("cbor_serialization.db", SerializationMethod::Cbor);
+}
+
+#[test]
+fn msgpack_serialization() {
+    set_test_rsc!("msgpack_serialization.db");
+    set_dump_load("msgpack_serialization.db", SerializationMethod::MsgPack);
+}
+
+#[test]
+fn json_file_is_readable() {
+    set_test_rsc!("json_file_is_readable.db");
+
+    let mut db = PickleDb::new("json_file_is_readable.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &String::from("value1")).unwrap();
+
+    // values are kept as JSON strings inside the JSON file
+    let contents = std::fs::read_to_string("json_file_is_readable.db").unwrap();
+    assert_eq!(contents, r#"[{"key1":"\"value1\""},{},{},{},{},{}]"#);
+}
@@ -0,0 +1,195 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+
+mod common;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Coor {
+    x: i32,
+    y: i32,
+}
+
+fn sorted(mut members: Vec<i32>) -> Vec<i32> {
+    members.sort();
+    members
+}
+
+#[test]
+fn basic_set() {
+    set_test_rsc!("basic_set.db");
+
+    let mut db = PickleDb::new("basic_set.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+
+    // the set is created by the first member and members can be of different types
+    assert!(db.sadd("set1", &1).unwrap());
+    assert!(db.sadd("set1", &String::from("hello")).unwrap());
+    assert!(db.sadd("set1", &Coor { x: 1, y: 2 }).unwrap());
+
+    // members are unique
+    assert!(!db.sadd("set1", &1).unwrap());
+    assert!(!db.sadd("set1", &Coor { x: 1, y: 2 }).unwrap());
+
+    assert!(db.exists("set1"));
+    assert!(!db.lexists("set1"));
+    assert_eq!(db.scard("set1"), 3);
+    assert_eq!(db.scard("set2"), 0);
+    assert!(db.sismember("set1", &1));
+    assert!(db.sismember("set1", &String::from("hello")));
+    assert!(db.sismember("set1", &Coor { x: 1, y: 2 }));
+    assert!(!db.sismember("set1", &2));
+    assert!(!db.sismember("set2", &1));
+
+    // the set is dumped to the file
+    let read_db = PickleDb::load_read_only("basic_set.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.scard("set1"), 3);
+    assert!(read_db.sismember("set1", &Coor { x: 1, y: 2 }));
+
+    // remove members
+    assert!(db.srem("set1", &String::from("hello")).unwrap());
+    assert!(!db.srem("set1", &String::from("hello")).unwrap());
+    assert!(!db.srem("set2", &1).unwrap());
+    assert_eq!(db.scard("set1"), 2);
+    let read_db = PickleDb::load_read_only("basic_set.db", SerializationMethod::Json).unwrap();
+    assert!(!read_db.sismember("set1", &String::from("hello")));
+
+    // an empty set remains in the DB until it's removed
+    db.srem("set1", &1).unwrap();
+    db.srem("set1", &Coor { x: 1, y: 2 }).unwrap();
+    assert!(db.exists("set1"));
+    assert!(db.rem("set1").unwrap());
+    assert!(!db.exists("set1"));
+}
+
+#[test]
+fn set_members_compared_by_serialized_form() {
+    set_test_rsc!("set_members_compared_by_serialized_form.db");
+
+    let mut db = PickleDb::new("set_members_compared_by_serialized_form.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+
+    // an i32 and an i64 of the same number serialize to the same data, a float doesn't
+    assert!(db.sadd("set1", &1i32).unwrap());
+    assert!(!db.sadd("set1", &1i64).unwrap());
+    assert!(db.sadd("set1", &1.5f64).unwrap());
+    assert!(db.sismember("set1", &1u8));
+    assert_eq!(db.scard("set1"), 2);
+}
+
+#[test]
+fn set_members() {
+    set_test_rsc!("set_members.db");
+
+    let mut db = PickleDb::new("set_members.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    for num in &[3, 1, 2] {
+        db.sadd("set1", num).unwrap();
+    }
+
+    assert_eq!(sorted(db.smembers::<i32>("set1").unwrap()), vec![1, 2, 3]);
+    assert!(db.smembers::<i32>("set2").unwrap().is_empty());
+
+    // members of different types can't be read as a single type
+    db.sadd("set1", &String::from("str")).unwrap();
+    assert!(matches!(db.smembers::<i32>("set1"), Err(PickleDbError::Deserialization { .. })));
+
+    // iterate over members of different types
+    let mut nums = Vec::new();
+    let mut strs = Vec::new();
+    for member in db.siter("set1").unwrap() {
+        match member.get_item::<i32>() {
+            Some(num) => nums.push(num),
+            None => strs.push(member.get_item::<String>().unwrap()),
+        }
+    }
+    assert_eq!(sorted(nums), vec![1, 2, 3]);
+    assert_eq!(strs, vec!["str"]);
+    assert!(matches!(db.siter("set2"), Err(PickleDbError::KeyNotFound(_))));
+}
+
+#[test]
+fn set_algebra() {
+    set_test_rsc!("set_algebra.db");
+
+    let mut db = PickleDb::new("set_algebra.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    for num in &[1, 2, 3, 4] {
+        db.sadd("set1", num).unwrap();
+    }
+    for num in &[3, 4, 5] {
+        db.sadd("set2", num).unwrap();
+    }
+    for num in &[4, 5, 6] {
+        db.sadd("set3", num).unwrap();
+    }
+
+    assert_eq!(sorted(db.sunion(&["set1", "set2"]).unwrap()), vec![1, 2, 3, 4, 5]);
+    assert_eq!(sorted(db.sunion(&["set1", "set2", "set3"]).unwrap()), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(sorted(db.sinter(&["set1", "set2"]).unwrap()), vec![3, 4]);
+    assert_eq!(sorted(db.sinter(&["set1", "set2", "set3"]).unwrap()), vec![4]);
+    assert_eq!(sorted(db.sdiff(&["set1", "set2"]).unwrap()), vec![1, 2]);
+    assert_eq!(sorted(db.sdiff(&["set1", "set2", "set3"]).unwrap()), vec![1, 2]);
+    assert_eq!(sorted(db.sdiff(&["set3", "set1"]).unwrap()), vec![5, 6]);
+
+    // a single set
+    assert_eq!(sorted(db.sunion(&["set1"]).unwrap()), vec![1, 2, 3, 4]);
+    assert_eq!(sorted(db.sinter(&["set1"]).unwrap()), vec![1, 2, 3, 4]);
+    assert_eq!(sorted(db.sdiff(&["set1"]).unwrap()), vec![1, 2, 3, 4]);
+
+    // sets that don't exist are empty
+    assert_eq!(sorted(db.sunion(&["set1", "other"]).unwrap()), vec![1, 2, 3, 4]);
+    assert!(db.sinter::<i32>(&["set1", "other"]).unwrap().is_empty());
+    assert_eq!(sorted(db.sdiff(&["set1", "other"]).unwrap()), vec![1, 2, 3, 4]);
+    assert!(db.sdiff::<i32>(&["other", "set1"]).unwrap().is_empty());
+    assert!(db.sunion::<i32>(&[]).unwrap().is_empty());
+    assert!(db.sinter::<i32>(&[]).unwrap().is_empty());
+    assert!(db.sdiff::<i32>(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn set_wrong_type() {
+    set_test_rsc!("set_wrong_type.db");
+
+    let mut db = PickleDb::new("set_wrong_type.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().ladd(&1).unwrap();
+    db.sadd("set1", &1).unwrap();
+
+    assert!(matches!(db.sadd("key1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.sadd("list1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.srem("key1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.smembers::<i32>("list1"), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.sunion::<i32>(&["set1", "key1"]), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.sinter::<i32>(&["set1", "list1"]), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.sdiff::<i32>(&["set1", "list1"]), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.siter("key1"), Err(PickleDbError::WrongType(_))));
+    assert!(!db.sismember("list1", &1));
+    assert_eq!(db.scard("list1"), 0);
+
+    // other types can't be used on a set
+    assert!(matches!(db.ladd("set1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.dadd("set1", "a", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.incr("set1"), Err(PickleDbError::WrongType(_))));
+
+    // setting a value overrides the set
+    db.set("set1", &2).unwrap();
+    assert_eq!(db.get::<i32>("set1").unwrap(), 2);
+    assert_eq!(db.scard("set1"), 0);
+}
+
+#[test]
+fn set_with_ttl() {
+    set_test_rsc!("set_with_ttl.db");
+
+    let mut db = PickleDb::new("set_with_ttl.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    db.sadd("set1", &1).unwrap();
+    db.expire("set1", Duration::from_millis(50)).unwrap();
+    assert!(db.ttl("set1").unwrap().is_some());
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!db.exists("set1"));
+    assert!(!db.sismember("set1", &1));
+    assert!(db.smembers::<i32>("set1").unwrap().is_empty());
+
+    // adding to an expired set starts a new set
+    assert!(db.sadd("set1", &2).unwrap());
+    assert_eq!(db.smembers::<i32>("set1").unwrap(), vec![2]);
+    assert!(db.ttl("set1").unwrap().is_none());
+}
@@ -0,0 +1,254 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod, SharedPickleDb};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn shared_db_is_send_and_sync() {
+    assert_send_sync::<SharedPickleDb>();
+}
+
+#[test]
+fn shared_db_writers_from_threads() {
+    set_test_rsc!("shared_db_writers_from_threads.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("shared_db_writers_from_threads.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json));
+    db.lcreate("list1").unwrap();
+
+    let handles: Vec<_> = (0..8).map(|i| {
+        let db = db.clone();
+        thread::spawn(move || {
+            for j in 0..20 {
+                db.set(&format!("key{}_{}", i, j), &j).unwrap();
+                db.ladd("list1", &i).unwrap();
+            }
+        })
+    }).collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(db.total_keys(), 8 * 20 + 1);
+    assert_eq!(db.llen("list1"), 8 * 20);
+    assert_eq!(db.get::<i32>("key3_7").unwrap(), 7);
+
+    // the dump policy works as usual, every change was dumped
+    let read_db = PickleDb::load_read_only("shared_db_writers_from_threads.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.total_keys(), 8 * 20 + 1);
+    assert_eq!(read_db.llen("list1"), 8 * 20);
+}
+
+#[test]
+fn shared_db_concurrent_readers() {
+    set_test_rsc!("shared_db_concurrent_readers.db");
+
+    let db = SharedPickleDb::from(PickleDb::new("shared_db_concurrent_readers.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin));
+    db.set("key1", &1).unwrap();
+
+    // a reader holds the DB while another thread reads from it
+    let (tx, rx) = mpsc::channel();
+    let reader_db = db.clone();
+    let reader = db.read(|_| {
+        let reader = thread::spawn(move || {
+            tx.send(reader_db.get::<i32>("key1")).unwrap();
+        });
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), Some(1));
+        reader
+    });
+
+    // wait for the reader's handle to be dropped so the DB isn't dumped after the test cleans up
+    reader.join().unwrap();
+}
+
+#[test]
+fn shared_db_dicts() {
+    set_test_rsc!("shared_db_dicts.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("shared_db_dicts.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json));
+    db.dcreate("dict1").unwrap();
+    let handles: Vec<_> = (0..4).map(|i| {
+        let db = db.clone();
+        thread::spawn(move || db.dadd("dict1", &format!("key{}", i), &i))
+    }).collect();
+    for handle in handles {
+        handle.join().unwrap().unwrap();
+    }
+
+    assert_eq!(db.dlen("dict1"), 4);
+    assert_eq!(db.dget::<i32>("dict1", "key2"), Some(2));
+    assert!(matches!(db.try_dget::<i32>("key1", "key1"), Ok(None)));
+    assert!(db.dexists("dict1", "key3"));
+    assert_eq!(db.dpop::<i32>("dict1", "key3").unwrap(), Some(3));
+    db.dcreate("dict2").unwrap();
+    db.dadd("dict2", "key9", &9).unwrap();
+    db.dmerge("dict1", "dict2").unwrap();
+    assert_eq!(db.dkeys("dict1").unwrap().len(), 4);
+    assert_eq!(db.dvals::<i32>("dict1").unwrap().iter().sum::<i32>(), 12);
+    assert_eq!(db.dgetall::<i32>("dict1").unwrap().get("key9"), Some(&9));
+    assert_eq!(db.drem("dict2").unwrap(), 1);
+
+    let read_db = PickleDb::load_read_only("shared_db_dicts.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.dlen("dict1"), 4);
+    assert!(!read_db.exists("dict2"));
+}
+
+#[test]
+fn shared_db_read_write_closures() {
+    set_test_rsc!("shared_db_read_write_closures.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("shared_db_read_write_closures.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json));
+
+    db.write(|db| -> Result<(), PickleDbError> {
+        db.lcreate("list1")?.lextend(&[1, 2, 3])?;
+        db.set("key1", &10)?;
+        Ok(())
+    }).unwrap();
+
+    let sum: i32 = db.read(|db| db.liter("list1").unwrap().filter_map(|item| item.get_item::<i32>()).sum());
+    assert_eq!(sum, 6);
+    assert_eq!(db.lpop::<i32>("list1", 0).unwrap(), Some(1));
+    assert!(db.lrem_value("list1", &2).unwrap());
+    assert_eq!(db.try_lget::<i32>("list1", 0).unwrap(), Some(3));
+    assert!(matches!(db.try_get::<i32>("list1"), Err(PickleDbError::WrongType(_))));
+
+    // a failed transaction doesn't change the DB
+    let res = db.transaction(|tx| {
+        tx.set("key1", &20)?;
+        tx.ladd("list2", &1)?;
+        Ok(())
+    });
+    assert!(matches!(res, Err(PickleDbError::KeyNotFound(_))));
+    assert_eq!(db.get::<i32>("key1").unwrap(), 10);
+
+    db.dump().unwrap();
+    let read_db = PickleDb::load_read_only("shared_db_read_write_closures.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.llen("list1"), 1);
+}
+
+#[test]
+fn shared_db_after_panic() {
+    set_test_rsc!("shared_db_after_panic.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("shared_db_after_panic.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json));
+    db.set("key1", &1).unwrap();
+
+    // a thread panics while it holds the DB
+    let panicking_db = db.clone();
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        panicking_db.write(|_| panic!("writer failed"))
+    }));
+    assert!(res.is_err());
+
+    // the DB is still usable
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    db.set("key2", &2).unwrap();
+    assert!(db.exists("key2"));
+}
+
+#[test]
+fn shared_db_dumped_when_last_handle_dropped() {
+    set_test_rsc!("shared_db_dumped_when_last_handle_dropped.db");
+
+    {
+        let db = SharedPickleDb::new(PickleDb::new("shared_db_dumped_when_last_handle_dropped.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json));
+        let db2 = db.clone();
+        thread::spawn(move || db2.set("key1", &1).unwrap()).join().unwrap();
+    }
+
+    let read_db = PickleDb::load_read_only("shared_db_dumped_when_last_handle_dropped.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+}
+
+#[test]
+fn flusher_dumps_periodically() {
+    set_test_rsc!("flusher_dumps_periodically.db");
+
+    // the DB is only dumped when the time since the last dump is checked upon a change
+    let db = SharedPickleDb::new(PickleDb::new("flusher_dumps_periodically.db", PickleDbDumpPolicy::PeriodicDump(Duration::from_secs(3600)), SerializationMethod::Json));
+    let flusher = db.start_flusher(Duration::from_millis(100));
+
+    db.set("key1", &1).unwrap();
+    assert!(PickleDb::load_read_only("flusher_dumps_periodically.db", SerializationMethod::Json).is_err());
+
+    // the flusher dumps the change although there are no more changes
+    thread::sleep(Duration::from_millis(500));
+    let read_db = PickleDb::load_read_only("flusher_dumps_periodically.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+
+    db.set("key2", &2).unwrap();
+    thread::sleep(Duration::from_millis(500));
+    let read_db = PickleDb::load_read_only("flusher_dumps_periodically.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key2").unwrap(), 2);
+
+    flusher.stop().unwrap();
+}
+
+#[test]
+fn flusher_stop_dumps_last_changes() {
+    set_test_rsc!("flusher_stop_dumps_last_changes.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("flusher_stop_dumps_last_changes.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin));
+    let flusher = db.start_flusher(Duration::from_secs(3600));
+    db.set("key1", &1).unwrap();
+
+    // stopping doesn't wait for the interval to pass
+    let start = std::time::Instant::now();
+    flusher.stop().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(60));
+
+    let read_db = PickleDb::load_read_only("flusher_stop_dumps_last_changes.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+
+    // the flusher doesn't dump anymore once it's stopped
+    db.set("key2", &2).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    let read_db = PickleDb::load_read_only("flusher_stop_dumps_last_changes.db", SerializationMethod::Bin).unwrap();
+    assert!(!read_db.exists("key2"));
+}
+
+#[test]
+fn flusher_skips_transaction() {
+    set_test_rsc!("flusher_skips_transaction.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("flusher_skips_transaction.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json));
+    db.set("key1", &1).unwrap();
+    let flusher = db.start_flusher(Duration::from_millis(50));
+
+    // the changes of a transaction in progress aren't appended to the log
+    db.write(|db| {
+        db.begin()?;
+        db.set("key2", &2)
+    }).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    let read_db = PickleDb::load_read_only("flusher_skips_transaction.db", SerializationMethod::Json).unwrap();
+    assert!(!read_db.exists("key2"));
+
+    // and they're gone once it's rolled back
+    db.write(|db| db.rollback()).unwrap();
+    flusher.stop().unwrap();
+    let read_db = PickleDb::load_read_only("flusher_skips_transaction.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+    assert!(!read_db.exists("key2"));
+}
+
+#[test]
+fn flusher_outlives_db() {
+    set_test_rsc!("flusher_outlives_db.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("flusher_outlives_db.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json));
+    let flusher = db.start_flusher(Duration::from_millis(50));
+    db.set("key1", &1).unwrap();
+
+    // the DB is dumped when it's dropped and the flusher has nothing left to do
+    drop(db);
+    thread::sleep(Duration::from_millis(200));
+    flusher.stop().unwrap();
+
+    let read_db = PickleDb::load_read_only("flusher_outlives_db.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+}
@@ -0,0 +1,205 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, PickleDbSnapshot, SerializationMethod, SharedPickleDb};
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn snapshot_is_send_and_sync() {
+    assert_send_sync::<PickleDbSnapshot>();
+}
+
+#[test]
+fn snapshot_not_affected_by_changes() {
+    set_test_rsc!("snapshot_not_affected.db");
+
+    let mut db = PickleDb::new("snapshot_not_affected.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().lextend(&[1, 2, 3]).unwrap();
+
+    let snapshot = db.snapshot();
+    db.set("key1", &2).unwrap();
+    db.set("key2", &3).unwrap();
+    db.ladd("list1", &4).unwrap();
+    db.lpop::<i32>("list1", 0).unwrap();
+
+    assert_eq!(snapshot.get::<i32>("key1"), Some(1));
+    assert!(!snapshot.exists("key2"));
+    assert_eq!(snapshot.total_keys(), 2);
+    assert_eq!(snapshot.llen("list1"), 3);
+    assert_eq!(snapshot.lget::<i32>("list1", 0), Some(1));
+    assert!(matches!(snapshot.try_lget::<i32>("key1", 0), Err(PickleDbError::WrongType(_))));
+    assert_eq!(snapshot.liter("list1").unwrap().map(|item| item.get_item::<i32>().unwrap()).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    // clones share the same data and can be read by other threads
+    let clone = snapshot.clone();
+    let handle = thread::spawn(move || clone.iter().map(|kv| String::from(kv.get_key())).collect::<Vec<_>>());
+    assert_eq!(handle.join().unwrap(), vec!["key1"]);
+    assert_eq!(db.get::<i32>("key1"), Some(2));
+}
+
+#[test]
+fn snapshot_of_ordered_keys() {
+    set_test_rsc!("snapshot_of_ordered_keys.db");
+
+    let mut db = PickleDb::new("snapshot_of_ordered_keys.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin)
+        .with_ordered_keys();
+    for key in ["c", "a", "b"] {
+        db.set(key, &1).unwrap();
+    }
+    db.set_with_ttl("d", &1, Duration::from_millis(50)).unwrap();
+
+    let snapshot = db.snapshot();
+    assert_eq!(snapshot.get_all(), vec!["a", "b", "c", "d"]);
+
+    // keys expire in the snapshot like in the DB
+    thread::sleep(Duration::from_millis(100));
+    assert!(!snapshot.exists("d"));
+    assert_eq!(snapshot.get_all(), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn snapshot_write_to() {
+    set_test_rsc!("snapshot_write_to.db");
+    set_test_rsc!("snapshot_write_to_copy.db");
+
+    let mut db = PickleDb::new("snapshot_write_to.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Cbor);
+    db.set("key1", &1).unwrap();
+    db.dcreate("dict1").unwrap();
+    db.dadd("dict1", "field1", &1).unwrap();
+    db.sadd("set1", &1).unwrap();
+    db.zadd("zset1", 1.5, &"a").unwrap();
+
+    let snapshot = db.snapshot();
+    db.set("key1", &2).unwrap();
+    db.dadd("dict1", "field2", &2).unwrap();
+    db.sadd("set1", &2).unwrap();
+    db.zadd("zset1", 2.5, &"b").unwrap();
+
+    // dicts, sets and sorted sets are read like in the DB
+    assert_eq!(snapshot.dget::<i32>("dict1", "field1"), Some(1));
+    assert_eq!(snapshot.dkeys("dict1"), Some(vec![String::from("field1")]));
+    assert!(!snapshot.dexists("dict1", "field2"));
+    assert_eq!(snapshot.dlen("dict1"), 1);
+    assert_eq!(snapshot.diter("dict1").unwrap().count(), 1);
+    assert!(snapshot.sismember("set1", &1));
+    assert!(!snapshot.sismember("set1", &2));
+    assert_eq!(snapshot.smembers::<i32>("set1").unwrap(), vec![1]);
+    assert_eq!(snapshot.scard("set1"), 1);
+    assert_eq!(snapshot.siter("set1").unwrap().count(), 1);
+    assert_eq!(snapshot.zscore("zset1", &"a"), Some(1.5));
+    assert_eq!(snapshot.zscore("zset1", &"b"), None);
+    assert_eq!(snapshot.zrange_by_rank::<String>("zset1", 0, -1).unwrap(), vec![(String::from("a"), 1.5)]);
+    assert_eq!(snapshot.zcard("zset1"), 1);
+    assert!(matches!(snapshot.smembers::<i32>("dict1"), Err(PickleDbError::WrongType(_))));
+
+    snapshot.write_to("snapshot_write_to_copy.db").unwrap();
+
+    let copy = PickleDb::load_read_only("snapshot_write_to_copy.db", SerializationMethod::Cbor).unwrap();
+    assert_eq!(copy.get::<i32>("key1"), Some(1));
+    assert_eq!(copy.dget::<i32>("dict1", "field1"), Some(1));
+    assert!(copy.sismember("set1", &1));
+
+    // the DB keeps dumping to its own file
+    db.set("key2", &3).unwrap();
+    let read_db = PickleDb::load_read_only("snapshot_write_to.db", SerializationMethod::Cbor).unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(2));
+    assert_eq!(read_db.get::<i32>("key2"), Some(3));
+}
+
+#[test]
+fn save_as() {
+    set_test_rsc!("save_as.db");
+    set_test_rsc!("save_as_copy.db");
+
+    let mut db = PickleDb::new("save_as.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.dump().unwrap();
+    db.set("key1", &2).unwrap();
+
+    // the copy holds the changes that weren't dumped yet, and the DB still has to dump them
+    db.save_as("save_as_copy.db").unwrap();
+    assert!(db.is_dirty());
+    let copy = PickleDb::load_read_only("save_as_copy.db", SerializationMethod::Json).unwrap();
+    assert_eq!(copy.get::<i32>("key1"), Some(2));
+    let read_db = PickleDb::load_read_only("save_as.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+
+    // keys that expired are left out of the copy without changing the DB
+    db.set_with_ttl("key2", &1, Duration::from_millis(10)).unwrap();
+    db.dump().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    db.save_as("save_as_copy.db").unwrap();
+    assert!(!db.is_dirty());
+    let copy = PickleDb::load_read_only("save_as_copy.db", SerializationMethod::Json).unwrap();
+    assert_eq!(copy.get_all(), vec!["key1"]);
+
+    // a DB can't be saved in the middle of a transaction
+    db.begin().unwrap();
+    assert!(matches!(db.save_as("save_as_copy.db"), Err(PickleDbError::TransactionInProgress)));
+}
+
+#[test]
+fn save_as_replaces_file_and_log() {
+    set_test_rsc!("save_as_replaces.db");
+    set_test_rsc!("save_as_replaces_copy.db");
+
+    // an append-only DB leaves a log next to the file that is replaced
+    let mut old_db = PickleDb::new("save_as_replaces_copy.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Bin);
+    old_db.set("key1", &1).unwrap();
+    old_db.set("old_key", &1).unwrap();
+    drop(old_db);
+
+    let mut db = PickleDb::new("save_as_replaces.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin);
+    db.set("key1", &2).unwrap();
+    let read_db = PickleDb::load_read_only("save_as_replaces_copy.db", SerializationMethod::Bin).unwrap();
+    assert!(read_db.exists("old_key"));
+
+    // a read-only DB can be saved too
+    db.save_as("save_as_replaces_copy.db").unwrap();
+    read_db.save_as("save_as_replaces.db").unwrap();
+    let copy = PickleDb::load_read_only("save_as_replaces_copy.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(copy.get::<i32>("key1"), Some(2));
+    assert!(!copy.exists("old_key"));
+    let copy = PickleDb::load_read_only("save_as_replaces.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(copy.get::<i32>("key1"), Some(1));
+    assert!(copy.exists("old_key"));
+}
+
+#[test]
+fn save_as_own_file() {
+    set_test_rsc!("save_as_own_file.db");
+
+    let mut db = PickleDb::new("save_as_own_file.db", PickleDbDumpPolicy::AppendOnly, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+
+    // the DB file can't be replaced by a copy, however its path is written
+    assert!(matches!(db.save_as("save_as_own_file.db"), Err(PickleDbError::Io(_))));
+    assert!(matches!(db.save_as("./save_as_own_file.db"), Err(PickleDbError::Io(_))));
+    assert!(matches!(db.snapshot().write_to("save_as_own_file.db"), Err(PickleDbError::Io(_))));
+
+    db.set("key2", &2).unwrap();
+    drop(db);
+    let read_db = PickleDb::load_read_only("save_as_own_file.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1"), Some(1));
+    assert_eq!(read_db.get::<i32>("key2"), Some(2));
+}
+
+#[test]
+fn shared_db_snapshot() {
+    set_test_rsc!("shared_db_snapshot.db");
+    set_test_rsc!("shared_db_snapshot_copy.db");
+
+    let db = SharedPickleDb::new(PickleDb::new("shared_db_snapshot.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json));
+    db.set("key1", &1).unwrap();
+
+    let snapshot = db.snapshot();
+    db.set("key1", &2).unwrap();
+    assert_eq!(snapshot.get::<i32>("key1"), Some(1));
+
+    db.save_as("shared_db_snapshot_copy.db").unwrap();
+    let copy = PickleDb::load_read_only("shared_db_snapshot_copy.db", SerializationMethod::Json).unwrap();
+    assert_eq!(copy.get::<i32>("key1"), Some(2));
+}
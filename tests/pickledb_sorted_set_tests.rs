@@ -0,0 +1,233 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+
+mod common;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Task {
+    id: u32,
+    name: String,
+}
+
+fn player(name: &str) -> String {
+    String::from(name)
+}
+
+#[test]
+fn basic_sorted_set() {
+    set_test_rsc!("basic_sorted_set.db");
+
+    let mut db = PickleDb::new("basic_sorted_set.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+
+    // the sorted set is created by the first member
+    assert!(db.zadd("board", 120.0, &player("alice")).unwrap());
+    assert!(db.zadd("board", 95.5, &player("bob")).unwrap());
+    assert!(db.zadd("board", 130.0, &player("carol")).unwrap());
+
+    // adding a member that is already in the sorted set updates its score
+    assert!(!db.zadd("board", 140.0, &player("bob")).unwrap());
+
+    assert!(db.exists("board"));
+    assert_eq!(db.zcard("board"), 3);
+    assert_eq!(db.zcard("other"), 0);
+    assert_eq!(db.zscore("board", &player("bob")), Some(140.0));
+    assert_eq!(db.zscore("board", &player("dave")), None);
+    assert_eq!(db.zscore("other", &player("bob")), None);
+
+    // the sorted set is dumped to the file
+    let read_db = PickleDb::load_read_only("basic_sorted_set.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.zcard("board"), 3);
+    assert_eq!(read_db.zscore("board", &player("bob")), Some(140.0));
+
+    // remove members
+    assert!(db.zrem("board", &player("alice")).unwrap());
+    assert!(!db.zrem("board", &player("alice")).unwrap());
+    assert!(!db.zrem("other", &player("alice")).unwrap());
+    assert_eq!(db.zcard("board"), 2);
+    let read_db = PickleDb::load_read_only("basic_sorted_set.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.zscore("board", &player("alice")), None);
+
+    // an empty sorted set remains in the DB until it's removed
+    db.zrem("board", &player("bob")).unwrap();
+    db.zrem("board", &player("carol")).unwrap();
+    assert!(db.exists("board"));
+    assert!(db.rem("board").unwrap());
+    assert!(!db.exists("board"));
+}
+
+#[test]
+fn sorted_set_range_by_rank() {
+    set_test_rsc!("sorted_set_range_by_rank.db");
+
+    let mut db = PickleDb::new("sorted_set_range_by_rank.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    db.zadd("board", 3.0, &player("c")).unwrap();
+    db.zadd("board", 1.0, &player("a")).unwrap();
+    db.zadd("board", 4.0, &player("d")).unwrap();
+    db.zadd("board", 2.0, &player("b")).unwrap();
+
+    let all = vec![(player("a"), 1.0), (player("b"), 2.0), (player("c"), 3.0), (player("d"), 4.0)];
+    assert_eq!(db.zrange_by_rank::<String>("board", 0, -1).unwrap(), all);
+    assert_eq!(db.zrange_by_rank::<String>("board", 1, 2).unwrap(), vec![(player("b"), 2.0), (player("c"), 3.0)]);
+    assert_eq!(db.zrange_by_rank::<String>("board", -2, -1).unwrap(), vec![(player("c"), 3.0), (player("d"), 4.0)]);
+    assert_eq!(db.zrange_by_rank::<String>("board", -10, 10).unwrap(), all);
+    assert!(db.zrange_by_rank::<String>("board", 2, 1).unwrap().is_empty());
+    assert!(db.zrange_by_rank::<String>("board", 4, 10).unwrap().is_empty());
+    assert!(db.zrange_by_rank::<String>("other", 0, -1).unwrap().is_empty());
+
+    // updating a score moves the member
+    db.zadd("board", 0.5, &player("d")).unwrap();
+    assert_eq!(db.zrange_by_rank::<String>("board", 0, 0).unwrap(), vec![(player("d"), 0.5)]);
+
+    // members with the same score are ordered by their serialized form
+    db.zadd("ties", 1.0, &player("y")).unwrap();
+    db.zadd("ties", 1.0, &player("x")).unwrap();
+    db.zadd("ties", 1.0, &player("z")).unwrap();
+    assert_eq!(db.zrange_by_rank::<String>("ties", 0, -1).unwrap(),
+               vec![(player("x"), 1.0), (player("y"), 1.0), (player("z"), 1.0)]);
+}
+
+#[test]
+fn sorted_set_range_by_score() {
+    set_test_rsc!("sorted_set_range_by_score.db");
+
+    let mut db = PickleDb::new("sorted_set_range_by_score.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    db.zadd("board", -5.0, &player("a")).unwrap();
+    db.zadd("board", 0.0, &player("b")).unwrap();
+    db.zadd("board", 2.5, &player("c")).unwrap();
+    db.zadd("board", 2.5, &player("d")).unwrap();
+    db.zadd("board", 10.0, &player("e")).unwrap();
+
+    assert_eq!(db.zrange_by_score::<String>("board", 0.0, 2.5).unwrap(),
+               vec![(player("b"), 0.0), (player("c"), 2.5), (player("d"), 2.5)]);
+    assert_eq!(db.zrange_by_score::<String>("board", 2.5, 2.5).unwrap(), vec![(player("c"), 2.5), (player("d"), 2.5)]);
+    assert_eq!(db.zrange_by_score::<String>("board", f64::NEG_INFINITY, -1.0).unwrap(), vec![(player("a"), -5.0)]);
+    assert_eq!(db.zrange_by_score::<String>("board", 3.0, f64::INFINITY).unwrap(), vec![(player("e"), 10.0)]);
+    assert_eq!(db.zrange_by_score::<String>("board", f64::NEG_INFINITY, f64::INFINITY).unwrap().len(), 5);
+    assert!(db.zrange_by_score::<String>("board", 3.0, 4.0).unwrap().is_empty());
+    assert!(db.zrange_by_score::<String>("board", 10.0, 0.0).unwrap().is_empty());
+    assert!(db.zrange_by_score::<String>("other", 0.0, 10.0).unwrap().is_empty());
+
+    // zero and negative zero are the same score
+    db.zadd("board", -0.0, &player("f")).unwrap();
+    assert_eq!(db.zrange_by_score::<String>("board", 0.0, 0.0).unwrap(), vec![(player("b"), 0.0), (player("f"), 0.0)]);
+    assert_eq!(db.zrange_by_score::<String>("board", -0.0, -0.0).unwrap().len(), 2);
+}
+
+#[test]
+fn sorted_set_incr_by() {
+    set_test_rsc!("sorted_set_incr_by.db");
+
+    let mut db = PickleDb::new("sorted_set_incr_by.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+
+    // a missing member starts from 0
+    assert_eq!(db.zincrby("board", 10.0, &player("a")).unwrap(), 10.0);
+    assert_eq!(db.zincrby("board", 5.5, &player("a")).unwrap(), 15.5);
+    assert_eq!(db.zincrby("board", -20.0, &player("a")).unwrap(), -4.5);
+    assert_eq!(db.zincrby("board", 1.0, &player("b")).unwrap(), 1.0);
+    assert_eq!(db.zrange_by_rank::<String>("board", 0, -1).unwrap(), vec![(player("a"), -4.5), (player("b"), 1.0)]);
+
+    let read_db = PickleDb::load_read_only("sorted_set_incr_by.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.zscore("board", &player("a")), Some(-4.5));
+
+    // the score can't overflow
+    db.zadd("board", f64::MAX, &player("c")).unwrap();
+    assert!(matches!(db.zincrby("board", f64::MAX, &player("c")), Err(PickleDbError::Overflow(_))));
+    assert_eq!(db.zscore("board", &player("c")), Some(f64::MAX));
+}
+
+#[test]
+fn sorted_set_invalid_score() {
+    set_test_rsc!("sorted_set_invalid_score.db");
+
+    let mut db = PickleDb::new("sorted_set_invalid_score.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    assert!(matches!(db.zadd("board", f64::NAN, &player("a")), Err(PickleDbError::InvalidScore(_))));
+    assert!(matches!(db.zadd("board", f64::INFINITY, &player("a")), Err(PickleDbError::InvalidScore(_))));
+    assert!(matches!(db.zincrby("board", f64::NAN, &player("a")), Err(PickleDbError::InvalidScore(_))));
+    assert!(matches!(db.zincrby("board", f64::NEG_INFINITY, &player("a")), Err(PickleDbError::InvalidScore(_))));
+    assert!(!db.exists("board"));
+    assert_eq!(db.total_keys(), 0);
+
+    // a NaN bound can't be compared with the scores
+    assert!(matches!(db.zrange_by_score::<String>("board", f64::NAN, 1.0), Err(PickleDbError::InvalidScore(_))));
+    assert!(matches!(db.zrange_by_score::<String>("board", 1.0, f64::NAN), Err(PickleDbError::InvalidScore(_))));
+}
+
+#[test]
+fn sorted_set_invalid_score_in_file() {
+    set_test_rsc!("sorted_set_invalid_score_in_file.db");
+
+    let mut db = PickleDb::new("sorted_set_invalid_score_in_file.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Yaml);
+    db.zadd("board", 1.25, &player("a")).unwrap();
+
+    // a score that isn't finite can't be loaded from a file
+    let contents = fs::read_to_string("sorted_set_invalid_score_in_file.db").unwrap();
+    assert!(contents.contains("1.25"));
+    for score in &[".nan", ".inf", "-.inf"] {
+        fs::write("sorted_set_invalid_score_in_file.db", contents.replace("1.25", score)).unwrap();
+        let res = PickleDb::load_read_only("sorted_set_invalid_score_in_file.db", SerializationMethod::Yaml);
+        assert!(matches!(res, Err(PickleDbError::Corrupted(_))));
+    }
+}
+
+#[test]
+fn sorted_set_priority_queue() {
+    set_test_rsc!("sorted_set_priority_queue.db");
+
+    let mut db = PickleDb::new("sorted_set_priority_queue.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin);
+    db.zadd("tasks", 2.0, &Task { id: 1, name: String::from("write docs") }).unwrap();
+    db.zadd("tasks", 1.0, &Task { id: 2, name: String::from("fix bug") }).unwrap();
+    db.zadd("tasks", 3.0, &Task { id: 3, name: String::from("release") }).unwrap();
+    db.dump().unwrap();
+
+    // take the tasks out of the queue by priority
+    let mut db = PickleDb::load("sorted_set_priority_queue.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Bin).unwrap();
+    let mut ids = Vec::new();
+    while let Some((task, _)) = db.zrange_by_rank::<Task>("tasks", 0, 0).unwrap().pop() {
+        db.zrem("tasks", &task).unwrap();
+        ids.push(task.id);
+    }
+    assert_eq!(ids, vec![2, 1, 3]);
+}
+
+#[test]
+fn sorted_set_wrong_type() {
+    set_test_rsc!("sorted_set_wrong_type.db");
+
+    let mut db = PickleDb::new("sorted_set_wrong_type.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.sadd("set1", &1).unwrap();
+    db.zadd("zset1", 1.0, &1).unwrap();
+
+    assert!(matches!(db.zadd("key1", 1.0, &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.zadd("set1", 1.0, &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.zincrby("key1", 1.0, &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.zrem("set1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.zrange_by_rank::<i32>("key1", 0, -1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.zrange_by_score::<i32>("set1", 0.0, 1.0), Err(PickleDbError::WrongType(_))));
+    assert_eq!(db.zscore("set1", &1), None);
+    assert_eq!(db.zcard("set1"), 0);
+
+    // other types can't be used on a sorted set
+    assert!(matches!(db.sadd("zset1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.ladd("zset1", &1), Err(PickleDbError::WrongType(_))));
+    assert!(matches!(db.incr("zset1"), Err(PickleDbError::WrongType(_))));
+
+    // members of a different type can't be deserialized
+    assert!(matches!(db.zrange_by_rank::<String>("zset1", 0, -1), Err(PickleDbError::Deserialization { .. })));
+}
+
+#[test]
+fn sorted_set_transaction_rollback() {
+    set_test_rsc!("sorted_set_transaction_rollback.db");
+
+    let mut db = PickleDb::new("sorted_set_transaction_rollback.db", PickleDbDumpPolicy::NeverDump, SerializationMethod::Json);
+    db.zadd("board", 1.0, &player("a")).unwrap();
+
+    db.begin().unwrap();
+    db.zincrby("board", 5.0, &player("a")).unwrap();
+    db.zadd("board", 2.0, &player("b")).unwrap();
+    db.rollback().unwrap();
+
+    assert_eq!(db.zrange_by_rank::<String>("board", 0, -1).unwrap(), vec![(player("a"), 1.0)]);
+}
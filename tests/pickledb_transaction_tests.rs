@@ -0,0 +1,164 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::panic::{self, AssertUnwindSafe};
+
+mod common;
+
+/// An application error type that PickleDB errors can be converted to
+#[derive(Debug)]
+enum AppError {
+    Db(PickleDbError),
+    Abort,
+}
+
+impl From<PickleDbError> for AppError {
+    fn from(err: PickleDbError) -> AppError {
+        AppError::Db(err)
+    }
+}
+
+#[test]
+fn transaction_commit() {
+    set_test_rsc!("transaction_commit.db");
+
+    let mut db = PickleDb::new("transaction_commit.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().lextend(&[1,2,3]).unwrap();
+
+    let res = db.transaction(|tx| -> Result<i32, PickleDbError> {
+        tx.set("key1", &10)?;
+        tx.set("key2", &20)?;
+        let item = tx.lpop::<i32>("list1", 0)?.unwrap();
+        tx.ladd("list1", &(item * 100))?;
+
+        // changes are visible inside the transaction but aren't dumped yet
+        assert!(tx.in_transaction());
+        assert_eq!(tx.get::<i32>("key1").unwrap(), 10);
+        let read_db = PickleDb::load_read_only("transaction_commit.db", SerializationMethod::Json).unwrap();
+        assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+        assert!(!read_db.exists("key2"));
+        assert_eq!(read_db.llen("list1"), 3);
+
+        Ok(item)
+    });
+    assert_eq!(res.unwrap(), 1);
+    assert!(!db.in_transaction());
+
+    // all changes were dumped on commit
+    let read_db = PickleDb::load_read_only("transaction_commit.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 10);
+    assert_eq!(read_db.get::<i32>("key2").unwrap(), 20);
+    assert_eq!(read_db.lget::<i32>("list1", 0).unwrap(), 2);
+    assert_eq!(read_db.lget::<i32>("list1", 2).unwrap(), 100);
+}
+
+#[test]
+fn transaction_rollback_on_error() {
+    set_test_rsc!("transaction_rollback_on_error.db");
+
+    let mut db = PickleDb::new("transaction_rollback_on_error.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().lextend(&[1,2,3]).unwrap();
+
+    // the second step fails because list2 doesn't exist
+    let res = db.transaction(|tx| {
+        let item = tx.lpop::<i32>("list1", 0)?.unwrap();
+        tx.set("key1", &2)?;
+        tx.ladd("list2", &item)?;
+        Ok(())
+    });
+    assert!(matches!(res, Err(PickleDbError::KeyNotFound(_))));
+    assert!(!db.in_transaction());
+
+    // nothing was changed, neither in memory nor in the file
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert_eq!(db.llen("list1"), 3);
+    let read_db = PickleDb::load_read_only("transaction_rollback_on_error.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+    assert_eq!(read_db.llen("list1"), 3);
+
+    // the closure can return its own error type
+    let res: Result<(), AppError> = db.transaction(|tx| {
+        tx.rem("key1")?;
+        Err(AppError::Abort)
+    });
+    assert!(matches!(res, Err(AppError::Abort)));
+    assert!(db.exists("key1"));
+
+    let res: Result<(), AppError> = db.transaction(|tx| {
+        tx.rem("key1")?;
+        tx.ladd("list2", &1)?;
+        Ok(())
+    });
+    assert!(matches!(res, Err(AppError::Db(PickleDbError::KeyNotFound(_)))));
+    assert!(db.exists("key1"));
+}
+
+#[test]
+fn transaction_rollback_on_panic() {
+    set_test_rsc!("transaction_rollback_on_panic.db");
+
+    let mut db = PickleDb::new("transaction_rollback_on_panic.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Json);
+    db.set("key1", &1).unwrap();
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        db.transaction(|tx| -> Result<(), PickleDbError> {
+            tx.set("key1", &2)?;
+            tx.set("key2", &3)?;
+            panic!("transaction failed");
+        })
+    }));
+    assert!(res.is_err());
+
+    assert!(!db.in_transaction());
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert!(!db.exists("key2"));
+    let read_db = PickleDb::load_read_only("transaction_rollback_on_panic.db", SerializationMethod::Json).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+    assert!(!read_db.exists("key2"));
+}
+
+#[test]
+fn begin_commit_rollback() {
+    set_test_rsc!("begin_commit_rollback.db");
+
+    let mut db = PickleDb::new("begin_commit_rollback.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin);
+
+    // commit or rollback without a transaction
+    assert!(matches!(db.commit(), Err(PickleDbError::NoTransaction)));
+    assert!(matches!(db.rollback(), Err(PickleDbError::NoTransaction)));
+
+    db.begin().unwrap();
+    assert!(matches!(db.begin(), Err(PickleDbError::TransactionInProgress)));
+    assert!(matches!(db.dump(), Err(PickleDbError::TransactionInProgress)));
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().ladd(&1).unwrap();
+    db.rollback().unwrap();
+
+    assert!(!db.exists("key1"));
+    assert!(!db.lexists("list1"));
+
+    db.begin().unwrap();
+    db.set("key1", &1).unwrap();
+    assert!(PickleDb::load_read_only("begin_commit_rollback.db", SerializationMethod::Bin).is_err());
+    db.commit().unwrap();
+
+    let read_db = PickleDb::load_read_only("begin_commit_rollback.db", SerializationMethod::Bin).unwrap();
+    assert_eq!(read_db.get::<i32>("key1").unwrap(), 1);
+}
+
+#[test]
+fn drop_rolls_back_transaction() {
+    set_test_rsc!("drop_rolls_back_transaction.db");
+
+    {
+        let mut db = PickleDb::new("drop_rolls_back_transaction.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+        db.set("key1", &1).unwrap();
+        db.begin().unwrap();
+        db.set("key2", &2).unwrap();
+    }
+
+    // only the changes made before the transaction were dumped
+    let read_db = PickleDb::load_read_only("drop_rolls_back_transaction.db", SerializationMethod::Json).unwrap();
+    assert!(read_db.exists("key1"));
+    assert!(!read_db.exists("key2"));
+}
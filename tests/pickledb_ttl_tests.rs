@@ -0,0 +1,142 @@
+use pickledb::{PickleDb, PickleDbDumpPolicy, PickleDbError, SerializationMethod};
+use std::fs;
+use std::time::Duration;
+use std::thread;
+
+mod common;
+
+#[test]
+fn set_with_ttl_expires() {
+    set_test_rsc!("set_with_ttl_expires.db");
+
+    let mut db = PickleDb::new("set_with_ttl_expires.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+
+    db.set_with_ttl("key1", &1, Duration::from_millis(200)).unwrap();
+    db.set("key2", &2).unwrap();
+
+    // the key is visible before it expires
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert!(db.exists("key1"));
+    assert_eq!(db.total_keys(), 2);
+    assert!(db.ttl("key1").unwrap().unwrap() <= Duration::from_millis(200));
+    assert!(db.ttl("key2").unwrap().is_none());
+
+    thread::sleep(Duration::from_millis(300));
+
+    // the key is invisible once it expires
+    assert!(db.get::<i32>("key1").is_none());
+    assert!(!db.exists("key1"));
+    assert_eq!(db.total_keys(), 1);
+    assert_eq!(db.get_all(), vec![String::from("key2")]);
+    assert_eq!(db.iter().count(), 1);
+    assert!(matches!(db.ttl("key1"), Err(PickleDbError::KeyNotFound(_))));
+    assert!(!db.rem("key1").unwrap());
+}
+
+#[test]
+fn expire_and_persist() {
+    set_test_rsc!("expire_and_persist.db");
+
+    let mut db = PickleDb::new("expire_and_persist.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+
+    db.set("key1", &1).unwrap();
+    db.lcreate("list1").unwrap().lextend(&[1,2,3]).unwrap();
+
+    // set a TTL for a key and a list, and for a key that doesn't exist
+    assert!(db.expire("key1", Duration::from_millis(200)).unwrap());
+    assert!(db.expire("list1", Duration::from_millis(200)).unwrap());
+    assert!(!db.expire("no_such_key", Duration::from_millis(200)).unwrap());
+
+    // remove the TTL of the key
+    assert!(db.persist("key1").unwrap());
+    assert!(!db.persist("key1").unwrap());
+    assert!(db.ttl("key1").unwrap().is_none());
+
+    thread::sleep(Duration::from_millis(300));
+
+    // the key was persisted, the list expired
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert!(!db.lexists("list1"));
+    assert_eq!(db.llen("list1"), 0);
+    assert!(db.lget::<i32>("list1", 0).is_none());
+    assert!(db.liter("list1").is_err());
+    assert!(matches!(db.ladd("list1", &4), Err(PickleDbError::KeyNotFound(_))));
+}
+
+#[test]
+fn set_removes_ttl() {
+    set_test_rsc!("set_removes_ttl.db");
+
+    let mut db = PickleDb::new("set_removes_ttl.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+
+    db.set_with_ttl("key1", &1, Duration::from_millis(200)).unwrap();
+    db.set("key1", &2).unwrap();
+    assert!(db.ttl("key1").unwrap().is_none());
+
+    db.set_with_ttl("key2", &1, Duration::from_millis(200)).unwrap();
+    db.lcreate("key2").unwrap();
+    assert!(db.ttl("key2").unwrap().is_none());
+
+    thread::sleep(Duration::from_millis(300));
+
+    assert_eq!(db.get::<i32>("key1").unwrap(), 2);
+    assert!(db.lexists("key2"));
+}
+
+#[test]
+fn ttl_survives_load() {
+    set_test_rsc!("ttl_survives_load.db");
+
+    let mut db = PickleDb::new("ttl_survives_load.db", PickleDbDumpPolicy::AutoDump, SerializationMethod::Bin);
+
+    db.set_with_ttl("short", &1, Duration::from_millis(200)).unwrap();
+    db.set_with_ttl("long", &2, Duration::from_secs(3600)).unwrap();
+    db.set("forever", &3).unwrap();
+
+    // the expiry times are loaded from the file
+    {
+        let read_db = PickleDb::load_read_only("ttl_survives_load.db", SerializationMethod::Bin).unwrap();
+        assert!(read_db.ttl("short").unwrap().is_some());
+        assert!(read_db.ttl("long").unwrap().unwrap() > Duration::from_secs(3500));
+        assert!(read_db.ttl("forever").unwrap().is_none());
+    }
+
+    thread::sleep(Duration::from_millis(300));
+
+    // the short key expired after it was loaded
+    {
+        let read_db = PickleDb::load_read_only("ttl_survives_load.db", SerializationMethod::Bin).unwrap();
+        assert!(!read_db.exists("short"));
+        assert!(read_db.exists("long"));
+    }
+}
+
+#[test]
+fn dump_purges_expired_keys() {
+    set_test_rsc!("dump_purges_expired_keys.db");
+
+    let mut db = PickleDb::new("dump_purges_expired_keys.db", PickleDbDumpPolicy::DumpUponRequest, SerializationMethod::Json);
+
+    db.set_with_ttl("key1", &1, Duration::from_millis(100)).unwrap();
+    db.set("key2", &2).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    db.dump().unwrap();
+
+    // the expired key isn't written to the file at all
+    let contents = fs::read_to_string("dump_purges_expired_keys.db").unwrap();
+    assert!(!contents.contains("key1"));
+    assert!(contents.contains("key2"));
+}
+
+#[test]
+fn load_file_without_expiry() {
+    set_test_rsc!("load_file_without_expiry.db");
+
+    // a DB file dumped before key expiry was added
+    fs::write("load_file_without_expiry.db", r#"[{"key1":"1"},{"list1":["2"]}]"#).unwrap();
+
+    let db = PickleDb::load_read_only("load_file_without_expiry.db", SerializationMethod::Json).unwrap();
+    assert_eq!(db.get::<i32>("key1").unwrap(), 1);
+    assert_eq!(db.lget::<i32>("list1", 0).unwrap(), 2);
+    assert!(db.ttl("key1").unwrap().is_none());
+}